@@ -1,10 +1,20 @@
 //use std::env;
 use crate::controllers::footer_controller::FooterCtrl;
 use crate::controllers::header_controller::HeaderCtrl;
+use crate::controllers::palette_controller::PaletteCtrl;
 use crate::controllers::*;
+use crate::keymap::{Action, KeyContext, KeyResolution, Keymap};
+use crate::mouse::{MouseInput, MouseKind, MouseTracker};
+use crate::views::debug::debug_pane_ui;
+use crate::views::help::help_ui;
+use crate::views::theme::Theme;
 use crate::views::{destruct_terminal, init_terminal, masterview};
+use crate::{config, editor, logger};
 use crossterm::event::{self, Event};
-use ratatui::Frame;
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    Frame,
+};
 use std::io::Error;
 
 pub enum AppState {
@@ -16,6 +26,12 @@ pub enum AppState {
 pub struct App {
     header: HeaderCtrl,
     footer: FooterCtrl,
+    palette: PaletteCtrl,
+    keymap: Keymap,
+    mouse: MouseTracker,
+    theme: Theme,
+    show_debug: bool,
+    show_help: bool,
 }
 
 impl Default for App {
@@ -23,6 +39,12 @@ impl Default for App {
         App {
             header: HeaderCtrl::default(),
             footer: FooterCtrl::default(),
+            palette: PaletteCtrl::default(),
+            keymap: Keymap::from_config(&config::get().keymap).unwrap_or_default(),
+            mouse: MouseTracker::default(),
+            theme: Theme::from_config(config::get()).unwrap_or_default(),
+            show_debug: false,
+            show_help: false,
         }
     }
 }
@@ -43,35 +65,180 @@ impl App {
                     }
                     self.footer.set_active_item(self.header.get_active_type());
                     main_controller.init_data();
+                    let ctx = self.key_context(main_controller.as_ref());
+                    self.footer.set_hints(&self.keymap, ctx);
                     term.draw(|f| self.ui_constructor(f, &mut main_controller))
                         .unwrap();
 
-                    if let Event::Key(key) = event::read()? {
-                        match main_controller.key_event_handler(&key) {
-                            AppState::MoveOn => {}
-                            _ => {
-                                match self.header.key_event_handler(&key) {
-                                    AppState::Closing => {
-                                        destruct_terminal(term);
-                                        return Ok(());
+                    match event::read()? {
+                        Event::Key(key) => {
+                            log::debug!("key event {:?} with {:?}", key.code, key.modifiers);
+                            let action = match self.keymap.resolve(ctx, &key) {
+                                KeyResolution::Action(action) => action,
+                                KeyResolution::Pending | KeyResolution::Unbound => continue,
+                            };
+                            log::debug!("dispatching action {:?} in {:?}", action, ctx);
+
+                            if action == Action::OpenEditor {
+                                if let Some(text) = main_controller.editor_text() {
+                                    // the editor needs the terminal, give it back while it runs
+                                    destruct_terminal(term);
+                                    let edited = editor::edit_text(&text);
+                                    term = init_terminal()?;
+                                    match edited {
+                                        Ok(text) => main_controller.set_editor_text(&text),
+                                        Err(e) => log::error!("failed to run editor: {}", e),
                                     }
-                                    _ => {} // we do nothing
                                 }
+                                continue;
+                            }
+
+                            if let AppState::Closing =
+                                self.handle_action(action, &mut main_controller)
+                            {
+                                log::info!("closing rask");
+                                destruct_terminal(term);
+                                return Ok(());
+                            }
+                        }
+                        Event::Mouse(mouse_event) => {
+                            if let Some(input) = self.mouse.translate(&mouse_event) {
+                                log::trace!("mouse event {:?}", input);
+                                self.handle_mouse(input, &mut main_controller);
                             }
                         }
+                        Event::Paste(text) if ctx.takes_text() => {
+                            log::debug!("pasting {} chars in {:?}", text.chars().count(), ctx);
+                            text.replace("\r\n", "\n")
+                                .chars()
+                                .map(|c| if c == '\t' { ' ' } else { c })
+                                .filter(|c| *c == '\n' || !c.is_control())
+                                .for_each(|c| {
+                                    self.handle_action(Action::Insert(c), &mut main_controller);
+                                });
+                        }
+                        _ => {}
                     }
                 }
             }
-            Err(e) => panic!("Problem initiating UI: {:?}", e),
+            Err(e) => {
+                log::error!("problem initiating UI: {}", e);
+                panic!("Problem initiating UI: {:?}", e)
+            }
+        }
+    }
+
+    /// overlays get the keys before the controller below them
+    fn key_context(&self, main_controller: &dyn CtrObj) -> KeyContext {
+        if self.palette.visible {
+            KeyContext::Palette
+        } else if self.show_help {
+            KeyContext::Popup
+        } else {
+            main_controller.key_context()
+        }
+    }
+
+    fn handle_action(&mut self, action: Action, main_controller: &mut Box<dyn CtrObj>) -> AppState {
+        if self.palette.visible {
+            self.palette.action_handler(&action);
+            return self.run_palette_command(main_controller);
+        }
+        if self.show_help {
+            if matches!(action, Action::Cancel | Action::Help | Action::Quit) {
+                self.show_help = false;
+            }
+            return AppState::Running;
+        }
+
+        match action {
+            Action::ToggleDebug => {
+                self.show_debug = !self.show_debug;
+                AppState::Running
+            }
+            Action::Help => {
+                self.show_help = true;
+                AppState::Running
+            }
+            Action::Palette => {
+                let ctx = main_controller.key_context();
+                self.palette.open(&self.keymap, ctx, &self.header.menu);
+                AppState::Running
+            }
+            _ => match main_controller.action_handler(&action) {
+                AppState::MoveOn => AppState::MoveOn,
+                _ => self.header.action_handler(&action),
+            },
+        }
+    }
+
+    /// runs the command picked in the palette, if any
+    fn run_palette_command(&mut self, main_controller: &mut Box<dyn CtrObj>) -> AppState {
+        if let Some(cmd) = self.palette.take_selected() {
+            log::debug!("running palette command '{}'", cmd.label);
+            if let Some(tab) = cmd.tab {
+                if self.header.select_type(tab) {
+                    *main_controller = self.header.get_main_controller();
+                    main_controller.init_data();
+                }
+            }
+            if let Some(action) = cmd.action {
+                return self.handle_action(action, main_controller);
+            }
         }
+        AppState::Running
+    }
+
+    /// same order as keys: overlays first, then the header tabs and the main controller
+    fn handle_mouse(&mut self, input: MouseInput, main_controller: &mut Box<dyn CtrObj>) {
+        if self.palette.visible {
+            self.palette.mouse_handler(&input);
+            self.run_palette_command(main_controller);
+            return;
+        }
+        if self.show_help {
+            if input.kind == MouseKind::Click {
+                self.show_help = false;
+            }
+            return;
+        }
+        if let AppState::MoveOn = self.header.mouse_handler(&input) {
+            return;
+        }
+        main_controller.mouse_handler(&input);
     }
 
     pub fn ui_constructor(&mut self, f: &mut Frame, main_controller: &mut Box<dyn CtrObj>) {
         let ui_zones = masterview(f);
-        self.header.display(f, ui_zones[0]).unwrap();
+        self.header.display(f, ui_zones[0], &self.theme).unwrap();
+
+        self.footer.display(f, ui_zones[2], &self.theme).unwrap();
 
-        self.footer.display(f, ui_zones[2]).unwrap();
+        main_controller
+            .display(f, ui_zones[1], &self.theme)
+            .unwrap();
 
-        main_controller.display(f, ui_zones[1]).unwrap();
+        if self.show_debug {
+            let debug_area = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                .split(ui_zones[1])[1];
+            debug_pane_ui(
+                logger::tail(logger::RECENT_LINES),
+                f,
+                debug_area,
+                &self.theme,
+            );
+        }
+        if self.show_help {
+            help_ui(
+                &self.keymap,
+                main_controller.key_context(),
+                f,
+                ui_zones[1],
+                &self.theme,
+            );
+        }
+        self.palette.display(f, ui_zones[1], &self.theme).unwrap();
     }
 }
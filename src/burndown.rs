@@ -0,0 +1,605 @@
+use crate::models::{
+    project::Project, sprint::Sprint, status_change::StatusChange, task::Task,
+    task_status::TaskStatus, DbObj,
+};
+use crate::UtilFns;
+use chrono::{Duration, Local, NaiveDate, TimeZone};
+use rusqlite::{Connection, Error};
+use std::collections::HashMap;
+
+/// how the dates of a project are written in the project form
+const PROJECT_DATE_FORMAT: &str = "%Y-%m-%d";
+const ASCII_HEIGHT: usize = 12;
+const ASCII_MAX_WIDTH: usize = 72;
+const SVG_WIDTH: f64 = 640.0;
+const SVG_HEIGHT: f64 = 320.0;
+const SVG_MARGIN: f64 = 40.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChartKind {
+    /// remaining work going down to 0
+    Burndown,
+    /// completed work going up to the scope
+    Burnup,
+}
+
+impl ChartKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChartKind::Burndown => "burndown",
+            ChartKind::Burnup => "burnup",
+        }
+    }
+
+    pub fn toggle(&self) -> ChartKind {
+        match self {
+            ChartKind::Burndown => ChartKind::Burnup,
+            ChartKind::Burnup => ChartKind::Burndown,
+        }
+    }
+}
+
+/// work of a project or a sprint day by day, rebuilt from the status changes of its tasks
+#[derive(Debug, Clone)]
+pub struct BurnChart {
+    pub kind: ChartKind,
+    pub title: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// "pts", or "tasks" when none of the tasks is estimated
+    pub unit: String,
+    /// (scope, completed) at the end of each day from `start` to today or `end`
+    pub days: Vec<(i32, i32)>,
+}
+
+impl BurnChart {
+    /// chart of the tasks of `sprint`, or of the whole project when None. the dates come from
+    /// `range`, then from the sprint or the project and fall back to the first task until today
+    pub fn build(
+        conn: &Connection,
+        project: &Project,
+        sprint: Option<&Sprint>,
+        range: Option<(NaiveDate, NaiveDate)>,
+    ) -> Result<BurnChart, Error> {
+        let filter = match sprint {
+            Some(sprint) => format!("sprint_id={}", sprint.id),
+            None => format!("project_id={}", project.id),
+        };
+        let tasks = Task::list(conn, filter)?;
+        let changes = StatusChange::for_project(conn, project.id)?;
+        let done_id = TaskStatus::done_id(conn);
+        let today = Local::now().date_naive();
+
+        let first_task = tasks
+            .iter()
+            .filter(|t| t.created_at > 0)
+            .filter_map(|t| Local.timestamp_opt(t.created_at, 0).single())
+            .map(|d| d.date_naive())
+            .min()
+            .unwrap_or(today);
+        let (start, end) = match (range, sprint) {
+            (Some(range), _) => range,
+            (None, Some(sprint)) => (
+                sprint.start().unwrap_or(first_task),
+                sprint.end().unwrap_or(today),
+            ),
+            (None, None) => (
+                NaiveDate::parse_from_str(&project.start_date, PROJECT_DATE_FORMAT)
+                    .unwrap_or(first_task),
+                NaiveDate::parse_from_str(&project.end_date, PROJECT_DATE_FORMAT).unwrap_or(today),
+            ),
+        };
+        let end = end.max(start);
+
+        let estimated = tasks.iter().any(|t| t.weight > 0);
+        let weight = |task: &Task| match estimated {
+            true => task.weight.max(0),
+            false => 1,
+        };
+
+        // changes are sorted by time, so are the histories
+        let mut histories: HashMap<i32, Vec<&StatusChange>> = HashMap::new();
+        for change in &changes {
+            histories.entry(change.task_id).or_default().push(change);
+        }
+
+        let mut days = vec![];
+        let mut day = start;
+        while day <= end.min(today) {
+            let end_of_day = match end_of_day(day) {
+                Some(timestamp) => timestamp,
+                None => break,
+            };
+            let mut point = (0, 0);
+            for task in tasks.iter().filter(|t| t.created_at <= end_of_day) {
+                let history = histories.get(&task.id).map_or(&[][..], |c| c.as_slice());
+                point.0 += weight(task);
+                if StatusChange::status_at(history, end_of_day) == Some(done_id) {
+                    point.1 += weight(task);
+                }
+            }
+            days.push(point);
+            day += Duration::days(1);
+        }
+
+        let title = match sprint {
+            Some(sprint) => format!("{} - {}", project.name, sprint.name),
+            None => project.name.clone(),
+        };
+        Ok(BurnChart {
+            kind: ChartKind::Burndown,
+            title,
+            start,
+            end,
+            unit: match estimated {
+                true => "pts".to_string(),
+                false => "tasks".to_string(),
+            },
+            days,
+        })
+    }
+
+    /// number of days between start and end, both included
+    pub fn day_count(&self) -> usize {
+        (self.end - self.start).num_days() as usize + 1
+    }
+
+    pub fn max_value(&self) -> i32 {
+        self.days.iter().map(|d| d.0).max().unwrap_or(0).max(1)
+    }
+
+    /// remaining work for a burndown, completed work for a burnup
+    pub fn actual(&self) -> Vec<(f64, f64)> {
+        self.days
+            .iter()
+            .enumerate()
+            .map(|(i, (scope, done))| match self.kind {
+                ChartKind::Burndown => (i as f64, (scope - done) as f64),
+                ChartKind::Burnup => (i as f64, *done as f64),
+            })
+            .collect()
+    }
+
+    /// scope of the project or sprint, only drawn on a burnup
+    pub fn scope(&self) -> Vec<(f64, f64)> {
+        match self.kind {
+            ChartKind::Burndown => vec![],
+            ChartKind::Burnup => self
+                .days
+                .iter()
+                .enumerate()
+                .map(|(i, (scope, _))| (i as f64, *scope as f64))
+                .collect(),
+        }
+    }
+
+    /// straight line from all the work to do on the first day to none left on the last one
+    pub fn ideal(&self) -> Vec<(f64, f64)> {
+        let scope = self.days.first().map(|d| d.0).unwrap_or(0) as f64;
+        let last = (self.day_count() - 1) as f64;
+        match self.kind {
+            ChartKind::Burndown => vec![(0.0, scope), (last, 0.0)],
+            ChartKind::Burnup => vec![(0.0, 0.0), (last, scope)],
+        }
+    }
+
+    /// work completed each day, for the sparkline
+    pub fn daily_done(&self) -> Vec<u64> {
+        let mut previous = 0;
+        self.days
+            .iter()
+            .map(|(_, done)| {
+                let rv = (done - previous).max(0) as u64;
+                previous = *done;
+                rv
+            })
+            .collect()
+    }
+
+    /// the chart drawn with characters, to paste in reports
+    pub fn to_ascii(&self) -> String {
+        let max = self.max_value() as f64;
+        // long ranges are squeezed, several days share a column
+        let cols = self.day_count().min(ASCII_MAX_WIDTH);
+        let last_day = (self.day_count() - 1).max(1) as f64;
+        let col_of = |day: f64| -> usize { (day / last_day * (cols - 1) as f64).round() as usize };
+        let row_of =
+            |value: f64| -> usize { ((value / max) * (ASCII_HEIGHT - 1) as f64).round() as usize };
+        let mut grid = vec![vec![' '; cols]; ASCII_HEIGHT];
+        let ideal = self.ideal();
+        let (first, last) = (ideal[0], ideal[1]);
+        let progress = (0..cols).map(|c| (c, c as f64 / (cols - 1).max(1) as f64));
+        for (col, progress) in progress {
+            grid[row_of(first.1 + (last.1 - first.1) * progress)][col] = '.';
+        }
+        for (x, y) in self.scope() {
+            grid[row_of(y)][col_of(x)] = '-';
+        }
+        for (x, y) in self.actual() {
+            grid[row_of(y)][col_of(x)] = '#';
+        }
+
+        let mut rv = format!(
+            "{} {} ({}, {} to {})\n",
+            self.title,
+            self.kind.name(),
+            self.unit,
+            self.start,
+            self.end
+        );
+        for (i, row) in grid.iter().enumerate().rev() {
+            let label = match i {
+                0 => "0".to_string(),
+                i if i == ASCII_HEIGHT - 1 => self.max_value().to_string(),
+                _ => "".to_string(),
+            };
+            rv.push_str(&format!(
+                "{:>5} |{}\n",
+                label,
+                row.iter().collect::<String>()
+            ));
+        }
+        rv.push_str(&format!("{:>5} +{}\n", "", "-".repeat(cols)));
+        let (start_label, end_label) = (self.start.to_string(), self.end.to_string());
+        let gap = cols
+            .saturating_sub(start_label.len() + end_label.len())
+            .max(1);
+        rv.push_str(&format!(
+            "{:>5}  {}{}{}\n",
+            "",
+            start_label,
+            " ".repeat(gap),
+            end_label
+        ));
+        rv.push_str(match self.kind {
+            ChartKind::Burndown => "# remaining  . ideal\n",
+            ChartKind::Burnup => "# completed  - scope  . ideal\n",
+        });
+        rv
+    }
+
+    /// the chart as a standalone svg image
+    pub fn to_svg(&self) -> String {
+        let max = self.max_value() as f64;
+        let last = (self.day_count() - 1).max(1) as f64;
+        let plot_w = SVG_WIDTH - 2.0 * SVG_MARGIN;
+        let plot_h = SVG_HEIGHT - 2.0 * SVG_MARGIN;
+        let points = |series: &[(f64, f64)]| -> String {
+            series
+                .iter()
+                .map(|(x, y)| {
+                    format!(
+                        "{:.1},{:.1}",
+                        SVG_MARGIN + x / last * plot_w,
+                        SVG_HEIGHT - SVG_MARGIN - y / max * plot_h
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join(" ")
+        };
+        let polyline = |series: &[(f64, f64)], style: &str| -> String {
+            format!(
+                "  <polyline fill=\"none\" {} points=\"{}\"/>\n",
+                style,
+                points(series)
+            )
+        };
+
+        let mut rv = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"12\">\n",
+            w = SVG_WIDTH,
+            h = SVG_HEIGHT
+        );
+        rv.push_str(&format!(
+            "  <rect width=\"{}\" height=\"{}\" fill=\"white\"/>\n",
+            SVG_WIDTH, SVG_HEIGHT
+        ));
+        rv.push_str(&format!(
+            "  <text x=\"{}\" y=\"24\">{} {} ({})</text>\n",
+            SVG_MARGIN,
+            xml_escape(&self.title),
+            self.kind.name(),
+            self.unit
+        ));
+        rv.push_str(&format!(
+            "  <path d=\"M{m} {m} V{b} H{r}\" fill=\"none\" stroke=\"black\"/>\n",
+            m = SVG_MARGIN,
+            b = SVG_HEIGHT - SVG_MARGIN,
+            r = SVG_WIDTH - SVG_MARGIN
+        ));
+        rv.push_str(&format!(
+            "  <text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>\n",
+            SVG_MARGIN - 4.0,
+            SVG_MARGIN + 4.0,
+            self.max_value()
+        ));
+        rv.push_str(&format!(
+            "  <text x=\"{}\" y=\"{}\">{}</text>\n  <text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>\n",
+            SVG_MARGIN,
+            SVG_HEIGHT - SVG_MARGIN + 16.0,
+            self.start,
+            SVG_WIDTH - SVG_MARGIN,
+            SVG_HEIGHT - SVG_MARGIN + 16.0,
+            self.end
+        ));
+        rv.push_str(&polyline(
+            &self.ideal(),
+            "stroke=\"gray\" stroke-dasharray=\"4 4\"",
+        ));
+        if self.kind == ChartKind::Burnup {
+            rv.push_str(&polyline(&self.scope(), "stroke=\"orange\""));
+        }
+        rv.push_str(&polyline(
+            &self.actual(),
+            "stroke=\"steelblue\" stroke-width=\"2\"",
+        ));
+        rv.push_str("</svg>\n");
+        rv
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// timestamp of the last second of a local day
+pub fn end_of_day(day: NaiveDate) -> Option<i64> {
+    Local
+        .from_local_datetime(&(day + Duration::days(1)).and_hms_opt(0, 0, 0)?)
+        .earliest()
+        .map(|midnight| midnight.timestamp() - 1)
+}
+
+#[derive(Clone)]
+struct ChartExport;
+
+impl UtilFns for ChartExport {}
+
+/// the chart of a project (or one of its sprints) as text or svg, for `rask chart`
+pub fn export(
+    project_id: i32,
+    sprint_id: Option<i32>,
+    from: Option<String>,
+    to: Option<String>,
+    kind: ChartKind,
+    format: &str,
+) -> Result<String, String> {
+    let parse_date = |date: Option<String>| -> Result<Option<NaiveDate>, String> {
+        match date {
+            Some(date) => NaiveDate::parse_from_str(&date, PROJECT_DATE_FORMAT)
+                .map(Some)
+                .map_err(|_| format!("invalid date '{}', expected YYYY-MM-DD", date)),
+            None => Ok(None),
+        }
+    };
+    let (from, to) = (parse_date(from)?, parse_date(to)?);
+    let conn = ChartExport::get_db_connection().map_err(|e| e.to_string())?;
+    export_chart(&conn, project_id, sprint_id, (from, to), kind, format)
+}
+
+/// the chart of `export` drawn from the database of `conn`, `range` holding the parsed dates
+fn export_chart(
+    conn: &Connection,
+    project_id: i32,
+    sprint_id: Option<i32>,
+    range: (Option<NaiveDate>, Option<NaiveDate>),
+    kind: ChartKind,
+    format: &str,
+) -> Result<String, String> {
+    let (from, to) = range;
+    let project = Project::get_by_id(conn, project_id)
+        .map_err(|_| format!("no project with id {}", project_id))?;
+    let sprint = match sprint_id {
+        Some(id) => Some(
+            Sprint::get_by_id(conn, id)
+                .ok()
+                .filter(|s| s.project_id == project_id)
+                .ok_or(format!(
+                    "no sprint with id {} in project {}",
+                    id, project_id
+                ))?,
+        ),
+        None => None,
+    };
+    // a single date keeps the other end of the default range
+    let mut chart =
+        BurnChart::build(conn, &project, sprint.as_ref(), None).map_err(|e| e.to_string())?;
+    if from.is_some() || to.is_some() {
+        let range = (from.unwrap_or(chart.start), to.unwrap_or(chart.end));
+        chart = BurnChart::build(conn, &project, sprint.as_ref(), Some(range))
+            .map_err(|e| e.to_string())?;
+    }
+    chart.kind = kind;
+    match format {
+        "ascii" => Ok(chart.to_ascii()),
+        "svg" => Ok(chart.to_svg()),
+        _ => Err(format!("unknown format '{}', use ascii or svg", format)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_db, migrate_db};
+    use chrono::{NaiveTime, Timelike};
+
+    fn days_ago(days: i64) -> i64 {
+        Local::now().timestamp() - days * 86400
+    }
+
+    /// a project with two tasks created 6 days ago: a 3 points one finished 2 days ago and an
+    /// unfinished 5 points one
+    fn project_db() -> (Connection, Project) {
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        migrate_db(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO project (id, reference, name, description, created_by, start_date, end_date, created_at, updated_at) VALUES (1, 'P1', 'Website', '', 1, '', '', 0, 0)",
+            [],
+        )
+        .unwrap();
+        for (weight, status) in [(3, 3), (5, 1)] {
+            let task = Task {
+                project_id: 1,
+                name: "task".to_string(),
+                weight,
+                status,
+                created_by: 1,
+                ..Task::default()
+            };
+            task.save(&conn).unwrap();
+        }
+        conn.execute("UPDATE task SET created_at = ?1", [days_ago(6)])
+            .unwrap();
+        conn.execute("DELETE FROM task_status_change", []).unwrap();
+        conn.execute(
+            "INSERT INTO task_status_change (task_id, from_status, to_status, changed_at) VALUES (1, 0, 1, ?1), (2, 0, 1, ?1), (1, 1, 3, ?2)",
+            (days_ago(6), days_ago(2)),
+        )
+        .unwrap();
+        let project = Project::get_by_id(&conn, 1).unwrap();
+        (conn, project)
+    }
+
+    fn today() -> NaiveDate {
+        Local::now().date_naive()
+    }
+
+    #[test]
+    fn days_follow_the_status_changes() {
+        let (conn, project) = project_db();
+        let range = (today() - Duration::days(5), today());
+        let chart = BurnChart::build(&conn, &project, None, Some(range)).unwrap();
+        assert_eq!(chart.unit, "pts");
+        assert_eq!(chart.day_count(), 6);
+        assert_eq!(chart.days.first(), Some(&(8, 0)));
+        assert_eq!(chart.days.last(), Some(&(8, 3)));
+        assert_eq!(chart.daily_done().iter().sum::<u64>(), 3);
+        assert_eq!(chart.actual().last(), Some(&(5.0, 5.0)));
+    }
+
+    #[test]
+    fn ranges_without_days_or_reversed() {
+        let (conn, project) = project_db();
+        // a range that has not started yet has no day to show
+        let future = (today() + Duration::days(3), today() + Duration::days(9));
+        let chart = BurnChart::build(&conn, &project, None, Some(future)).unwrap();
+        assert!(chart.days.is_empty());
+        assert_eq!(chart.ideal(), vec![(0.0, 0.0), (6.0, 0.0)]);
+        assert!(chart.to_ascii().contains("# remaining"));
+
+        // the end is moved back to the start
+        let reversed = (today(), today() - Duration::days(4));
+        let chart = BurnChart::build(&conn, &project, None, Some(reversed)).unwrap();
+        assert_eq!(chart.end, chart.start);
+        assert_eq!(chart.day_count(), 1);
+        assert_eq!(chart.days, vec![(8, 3)]);
+    }
+
+    #[test]
+    fn long_ranges_are_squeezed_in_the_ascii_chart() {
+        let chart = BurnChart {
+            kind: ChartKind::Burnup,
+            title: "Website".to_string(),
+            start: today() - Duration::days(199),
+            end: today(),
+            unit: "tasks".to_string(),
+            days: (0..200).map(|day| (10, day / 20)).collect(),
+        };
+        let ascii = chart.to_ascii();
+        let rows: Vec<&str> = ascii.lines().filter(|l| l.contains('|')).collect();
+        assert_eq!(rows.len(), ASCII_HEIGHT);
+        assert!(rows
+            .iter()
+            .all(|row| row.chars().count() == 7 + ASCII_MAX_WIDTH));
+        assert!(rows[0].starts_with("   10 |"));
+        assert!(ascii.starts_with("Website burnup (tasks, "));
+        assert!(ascii.ends_with("# completed  - scope  . ideal\n"));
+    }
+
+    #[test]
+    fn svg_draws_the_lines_of_its_kind() {
+        let mut chart = BurnChart {
+            kind: ChartKind::Burndown,
+            title: "R&D <team>".to_string(),
+            start: today() - Duration::days(2),
+            end: today(),
+            unit: "pts".to_string(),
+            days: vec![(4, 0), (4, 2), (6, 3)],
+        };
+        let svg = chart.to_svg();
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains("R&amp;D &lt;team&gt; burndown (pts)"));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+
+        chart.kind = chart.kind.toggle();
+        assert_eq!(chart.to_svg().matches("<polyline").count(), 3);
+    }
+
+    #[test]
+    fn end_of_day_is_the_last_second_of_the_day() {
+        let day = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let last = Local.timestamp_opt(end_of_day(day).unwrap(), 0).unwrap();
+        assert_eq!(last.date_naive(), day);
+        assert_eq!(last.time(), NaiveTime::from_hms_opt(23, 59, 59).unwrap());
+        assert_eq!(
+            Local
+                .timestamp_opt(end_of_day(day).unwrap() + 1, 0)
+                .unwrap()
+                .num_seconds_from_midnight(),
+            0
+        );
+    }
+
+    #[test]
+    fn export_checks_its_arguments() {
+        assert_eq!(
+            export(
+                1,
+                None,
+                Some("15/03/2024".to_string()),
+                None,
+                ChartKind::Burndown,
+                "ascii"
+            ),
+            Err("invalid date '15/03/2024', expected YYYY-MM-DD".to_string())
+        );
+
+        let (conn, _) = project_db();
+        let range = (Some(today() - Duration::days(5)), None);
+        let chart = |project_id, sprint_id, format| {
+            export_chart(
+                &conn,
+                project_id,
+                sprint_id,
+                range,
+                ChartKind::Burnup,
+                format,
+            )
+        };
+        assert_eq!(
+            chart(1, None, "png"),
+            Err("unknown format 'png', use ascii or svg".to_string())
+        );
+        assert_eq!(
+            chart(2, None, "ascii"),
+            Err("no project with id 2".to_string())
+        );
+        assert_eq!(
+            chart(1, Some(4), "ascii"),
+            Err("no sprint with id 4 in project 1".to_string())
+        );
+        let ascii = chart(1, None, "ascii").unwrap();
+        assert!(ascii.starts_with(&format!(
+            "Website burnup (pts, {} to ",
+            today() - Duration::days(5)
+        )));
+        assert!(chart(1, None, "svg")
+            .unwrap()
+            .contains("Website burnup (pts)"));
+    }
+}
@@ -0,0 +1,542 @@
+use crate::estimate::ESTIMATE_SCALES;
+use crate::keymap::Keymap;
+use crate::models::task::{NORMAL_PRIORITY, PRIORITIES};
+use crate::views::theme::Theme;
+use chrono::format::{Item, StrftimeItems};
+use ratatui::style::Color;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+/// per-workspace override, looked up in the current directory
+pub const WORKSPACE_CONFIG_FILE_NAME: &str = ".rask.toml";
+
+pub const TAB_NAMES: [&str; 9] = [
+    "dashboard",
+    "next",
+    "tasks",
+    "projects",
+    "sprints",
+    "calendar",
+    "timeline",
+    "metrics",
+    "contacts",
+];
+pub const TASK_COLUMNS: [&str; 15] = [
+    "id",
+    "project",
+    "name",
+    "description",
+    "weight",
+    "status",
+    "checklist",
+    "comments",
+    "assignees",
+    "due",
+    "start",
+    "depends",
+    "priority",
+    "urgency",
+    "tags",
+];
+pub const PROJECT_COLUMNS: [&str; 6] = [
+    "id",
+    "reference",
+    "name",
+    "description",
+    "start_date",
+    "end_date",
+];
+pub const KEYMAP_PRESETS: [&str; 2] = ["default", "vim"];
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// path to the sqlite file, defaults to `rask.db` in the current directory
+    pub db_path: Option<String>,
+    pub start_tab: String,
+    pub tabs: Vec<String>,
+    pub date_format: String,
+    /// email of the contact you are, `git config user.email` is used when not set
+    pub user_email: Option<String>,
+    pub defaults: DefaultsConfig,
+    pub estimates: EstimatesConfig,
+    pub columns: ColumnsConfig,
+    pub theme: ThemeConfig,
+    /// user themes, `base` names the built-in theme they start from, other keys are colors
+    pub themes: HashMap<String, HashMap<String, String>>,
+    pub keymap: KeymapConfig,
+    pub wip_limits: WipLimitsConfig,
+    pub urgency: UrgencyConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DefaultsConfig {
+    /// task_status id given to new tasks
+    pub status: i32,
+    /// project id given to new tasks, 0 means no project
+    pub project: i32,
+    /// priority given to new tasks, 0 (P0) to 3 (P3)
+    pub priority: i32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EstimatesConfig {
+    /// how task weights are picked and shown: fibonacci, tshirt or hours
+    pub scale: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColumnsConfig {
+    pub tasks: Vec<String>,
+    pub projects: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    /// built-in theme or one of the `[themes.<name>]` sections
+    pub name: String,
+    pub background: Option<String>,
+    pub highlight: Option<String>,
+    /// overrides of single theme colors, ex: `selection_bg = "blue"`
+    #[serde(flatten)]
+    pub colors: HashMap<String, String>,
+}
+
+/// most cards a scrumboard column should hold, 0 for no limit
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WipLimitsConfig {
+    /// task_status id -> limit, ex: `2 = 3`
+    pub statuses: HashMap<String, usize>,
+    /// project id -> limits overriding `statuses` for that project
+    pub projects: HashMap<String, HashMap<String, usize>>,
+}
+
+/// coefficients of the urgency score, each one is multiplied by a factor between 0 and 1 (see
+/// `urgency::score`), a negative coefficient pushes the tasks down
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UrgencyConfig {
+    /// P0 gets all of it, P1 0.65, P2 0.3 and P3 nothing
+    pub priority: f64,
+    /// 0.2 two weeks or more before the due date, growing to all of it a week after
+    pub due: f64,
+    /// grows with the age of the task up to `age_max` days
+    pub age: f64,
+    pub age_max: f64,
+    /// given to tasks waiting for unfinished tasks
+    pub blocked: f64,
+    /// grows with the estimate up to 13
+    pub weight: f64,
+    /// 0.8 for a tagged task, 0.9 with two tags and all of it from three
+    pub tags: f64,
+    /// number of tasks in the Next tab and `rask next`
+    pub next_count: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeymapConfig {
+    pub preset: String,
+    /// action (or context.action) name -> keys, ex: `quit = "ctrl-q"`, `list.first = "g g"`
+    pub bindings: HashMap<String, String>,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            db_path: None,
+            start_tab: "dashboard".to_string(),
+            tabs: vec![
+                "dashboard".to_string(),
+                "next".to_string(),
+                "tasks".to_string(),
+                "projects".to_string(),
+                "sprints".to_string(),
+                "calendar".to_string(),
+                "timeline".to_string(),
+                "metrics".to_string(),
+                "contacts".to_string(),
+            ],
+            date_format: "%Y-%m-%d %H:%M".to_string(),
+            user_email: None,
+            defaults: DefaultsConfig::default(),
+            estimates: EstimatesConfig::default(),
+            columns: ColumnsConfig::default(),
+            theme: ThemeConfig::default(),
+            themes: HashMap::new(),
+            keymap: KeymapConfig::default(),
+            wip_limits: WipLimitsConfig::default(),
+            urgency: UrgencyConfig::default(),
+        }
+    }
+}
+
+impl Default for DefaultsConfig {
+    fn default() -> DefaultsConfig {
+        DefaultsConfig {
+            status: 1,
+            project: 0,
+            priority: NORMAL_PRIORITY,
+        }
+    }
+}
+
+impl Default for EstimatesConfig {
+    fn default() -> EstimatesConfig {
+        EstimatesConfig {
+            scale: "fibonacci".to_string(),
+        }
+    }
+}
+
+impl Default for ColumnsConfig {
+    fn default() -> ColumnsConfig {
+        ColumnsConfig {
+            tasks: vec![
+                "id".to_string(),
+                "project".to_string(),
+                "name".to_string(),
+                "assignees".to_string(),
+                "checklist".to_string(),
+                "description".to_string(),
+            ],
+            projects: vec![
+                "id".to_string(),
+                "reference".to_string(),
+                "name".to_string(),
+                "description".to_string(),
+            ],
+        }
+    }
+}
+
+impl Default for ThemeConfig {
+    fn default() -> ThemeConfig {
+        ThemeConfig {
+            name: "default".to_string(),
+            background: None,
+            highlight: None,
+            colors: HashMap::new(),
+        }
+    }
+}
+
+impl Default for UrgencyConfig {
+    fn default() -> UrgencyConfig {
+        UrgencyConfig {
+            priority: 6.0,
+            due: 12.0,
+            age: 2.0,
+            age_max: 365.0,
+            blocked: -5.0,
+            weight: 1.0,
+            tags: 1.0,
+            next_count: 10,
+        }
+    }
+}
+
+impl Default for KeymapConfig {
+    fn default() -> KeymapConfig {
+        KeymapConfig {
+            preset: "default".to_string(),
+            bindings: HashMap::new(),
+        }
+    }
+}
+
+impl WipLimitsConfig {
+    /// limit of the status column on the board of a project, None when there is none
+    pub fn limit(&self, project_id: i32, status_id: i32) -> Option<usize> {
+        let status = status_id.to_string();
+        self.projects
+            .get(&project_id.to_string())
+            .and_then(|limits| limits.get(&status))
+            .or_else(|| self.statuses.get(&status))
+            .copied()
+            .filter(|limit| *limit > 0)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let statuses = self.projects.values().flat_map(|limits| limits.keys());
+        for status in self.statuses.keys().chain(statuses) {
+            if status.parse::<i32>().map_or(true, |id| id < 1) {
+                return Err(format!(
+                    "config: wip_limits keys must be task status ids, got '{}'",
+                    status
+                ));
+            }
+        }
+        for project in self.projects.keys() {
+            if project.parse::<i32>().map_or(true, |id| id < 1) {
+                return Err(format!(
+                    "config: wip_limits.projects keys must be project ids, got '{}'",
+                    project
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    /// loads the user config file and the workspace override on top of it, then validates
+    /// the result. Missing files are not an error, rask just uses the defaults.
+    pub fn load() -> Result<Config, String> {
+        let mut merged = toml::Table::new();
+        let mut sources = vec![];
+        if let Some(path) = config_path() {
+            sources.push(path);
+        }
+        if let Ok(dir) = env::current_dir() {
+            sources.push(dir.join(WORKSPACE_CONFIG_FILE_NAME));
+        }
+
+        for path in sources.iter().filter(|p| p.exists()) {
+            log::info!("loading config from {}", path.display());
+            merge_tables(&mut merged, read_table(path)?);
+        }
+        Config::from_table(merged)
+    }
+
+    /// the config of a parsed file, validated
+    fn from_table(table: toml::Table) -> Result<Config, String> {
+        let config: Config = toml::Value::Table(table)
+            .try_into()
+            .map_err(|e: toml::de::Error| format!("invalid configuration: {}", e.message()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.tabs.is_empty() {
+            return Err("config: 'tabs' must list at least one tab".to_string());
+        }
+        for tab in &self.tabs {
+            check_one_of("tabs", tab, &TAB_NAMES)?;
+        }
+        if !self.tabs.contains(&self.start_tab) {
+            return Err(format!(
+                "config: start_tab '{}' is not one of the enabled tabs [{}]",
+                self.start_tab,
+                self.tabs.join(", ")
+            ));
+        }
+        if self.date_format.is_empty()
+            || StrftimeItems::new(&self.date_format).any(|item| item == Item::Error)
+        {
+            return Err(format!(
+                "config: date_format '{}' is not a valid strftime format",
+                self.date_format
+            ));
+        }
+        if self.defaults.status < 1 {
+            return Err(format!(
+                "config: defaults.status must be a task status id (>= 1), got {}",
+                self.defaults.status
+            ));
+        }
+        if self.defaults.project < 0 {
+            return Err(format!(
+                "config: defaults.project must be a project id or 0, got {}",
+                self.defaults.project
+            ));
+        }
+        if self.defaults.priority < 0 || self.defaults.priority >= PRIORITIES.len() as i32 {
+            return Err(format!(
+                "config: defaults.priority must be 0 (P0) to {} (P{}), got {}",
+                PRIORITIES.len() - 1,
+                PRIORITIES.len() - 1,
+                self.defaults.priority
+            ));
+        }
+        check_one_of("estimates.scale", &self.estimates.scale, &ESTIMATE_SCALES)?;
+        if self.columns.tasks.is_empty() || self.columns.projects.is_empty() {
+            return Err("config: columns.tasks and columns.projects can't be empty".to_string());
+        }
+        for col in &self.columns.tasks {
+            check_one_of("columns.tasks", col, &TASK_COLUMNS)?;
+        }
+        for col in &self.columns.projects {
+            check_one_of("columns.projects", col, &PROJECT_COLUMNS)?;
+        }
+        Theme::from_config(self)?;
+        check_one_of("keymap.preset", &self.keymap.preset, &KEYMAP_PRESETS)?;
+        Keymap::from_config(&self.keymap)?;
+        self.wip_limits.validate()?;
+        if self.urgency.age_max <= 0.0 {
+            return Err(format!(
+                "config: urgency.age_max must be a number of days above 0, got {}",
+                self.urgency.age_max
+            ));
+        }
+        if self.urgency.next_count < 1 {
+            return Err("config: urgency.next_count must be at least 1".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// resolved path of the sqlite file
+    pub fn db_file(&self) -> Option<PathBuf> {
+        self.db_path.as_ref().map(|p| expand_home(p))
+    }
+}
+
+/// installs the configuration used by the whole app
+pub fn init(config: Config) {
+    let _ = CONFIG.set(config);
+}
+
+/// active configuration, falling back to the defaults if `init` was never called
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("rask").join(CONFIG_FILE_NAME))
+}
+
+fn read_table(path: &Path) -> Result<toml::Table, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    content
+        .parse::<toml::Table>()
+        .map_err(|e| format!("failed to parse {}: {}", path.display(), e))
+}
+
+/// deep merges `overrides` into `base`, nested tables are merged key by key
+fn merge_tables(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_tbl)), toml::Value::Table(over_tbl)) => {
+                merge_tables(base_tbl, over_tbl)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn check_one_of(field: &str, value: &str, allowed: &[&str]) -> Result<(), String> {
+    if allowed.contains(&value) {
+        Ok(())
+    } else {
+        Err(format!(
+            "config: {} has unknown value '{}', expected one of: {}",
+            field,
+            value,
+            allowed.join(", ")
+        ))
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+pub fn parse_color(value: &str) -> Result<Color, String> {
+    Color::from_str(value).map_err(|_e| format!("unknown color '{}'", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// the config of the files `sources`, each one overriding the previous ones
+    fn load(sources: &[&str]) -> Result<Config, String> {
+        let mut merged = toml::Table::new();
+        for source in sources {
+            merge_tables(&mut merged, source.parse::<toml::Table>().unwrap());
+        }
+        Config::from_table(merged)
+    }
+
+    fn load_err(source: &str) -> String {
+        load(&[source]).unwrap_err()
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        assert!(Config::default().validate().is_ok());
+        let config = load(&[""]).unwrap();
+        assert_eq!(config.start_tab, "dashboard");
+        assert_eq!(config.tabs.len(), TAB_NAMES.len());
+        assert_eq!(config.defaults.priority, NORMAL_PRIORITY);
+    }
+
+    #[test]
+    fn workspace_file_overrides_key_by_key() {
+        let user =
+            "start_tab = \"tasks\"\n[defaults]\nstatus = 2\nproject = 4\n[urgency]\ndue = 20.0\n";
+        let workspace = "[defaults]\nproject = 7\n[urgency]\nnext_count = 3\n";
+        let config = load(&[user, workspace]).unwrap();
+        assert_eq!(config.start_tab, "tasks");
+        assert_eq!(config.defaults.status, 2);
+        assert_eq!(config.defaults.project, 7);
+        assert_eq!(config.urgency.due, 20.0);
+        assert_eq!(config.urgency.next_count, 3);
+        // keys set in no file keep their default
+        assert_eq!(config.urgency.priority, UrgencyConfig::default().priority);
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(load_err("colour = \"red\"").starts_with("invalid configuration"));
+        assert!(load_err("[defaults]\nsprint = 1").starts_with("invalid configuration"));
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        assert_eq!(
+            load_err("tabs = [\"tasks\", \"inbox\"]\nstart_tab = \"tasks\""),
+            "config: tabs has unknown value 'inbox', expected one of: dashboard, next, tasks, \
+             projects, sprints, calendar, timeline, metrics, contacts"
+        );
+        assert_eq!(
+            load_err("tabs = [\"tasks\"]"),
+            "config: start_tab 'dashboard' is not one of the enabled tabs [tasks]"
+        );
+        assert!(load_err("tabs = []").contains("at least one tab"));
+        assert!(load_err("date_format = \"%Y-%Q\"").contains("not a valid strftime format"));
+        assert!(load_err("[defaults]\nstatus = 0").contains("defaults.status"));
+        assert!(load_err("[defaults]\npriority = 4").contains("defaults.priority"));
+        assert!(load_err("[estimates]\nscale = \"days\"").contains("estimates.scale"));
+        assert!(load_err("[columns]\ntasks = [\"id\", \"size\"]").contains("columns.tasks"));
+        assert!(load_err("[keymap]\npreset = \"emacs\"").contains("keymap.preset"));
+        assert!(load_err("[keymap.bindings]\nfly = \"f\"").contains("keymap.bindings.fly"));
+        assert!(load_err("[wip_limits.statuses]\nwip = 3").contains("task status ids"));
+        assert!(load_err("[urgency]\nage_max = 0.0").contains("urgency.age_max"));
+        assert!(load_err("[urgency]\nnext_count = 0").contains("urgency.next_count"));
+    }
+
+    #[test]
+    fn project_wip_limits_override_status_limits() {
+        let config =
+            load(&["[wip_limits.statuses]\n2 = 3\n3 = 0\n[wip_limits.projects.5]\n2 = 1\n"])
+                .unwrap();
+        let limits = &config.wip_limits;
+        assert_eq!(limits.limit(1, 2), Some(3));
+        assert_eq!(limits.limit(5, 2), Some(1));
+        // 0 means no limit
+        assert_eq!(limits.limit(1, 3), None);
+        assert_eq!(limits.limit(1, 1), None);
+    }
+}
@@ -0,0 +1,289 @@
+use super::CtrObj;
+use crate::app::AppState;
+use crate::keymap::{Action, KeyContext};
+use crate::models::{
+    task::{Task, DUE_DATE_FORMAT},
+    task_assignee::TaskAssignee,
+    task_status::TaskStatus,
+    DbObj,
+};
+use crate::views::calendar::*;
+use crate::views::input::TextInput;
+use crate::views::theme::Theme;
+use crate::UtilFns;
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use ratatui::{layout::Rect, widgets::ListState, Frame};
+use std::collections::HashMap;
+use std::io::Error;
+
+/// month grid of the tasks by due date, with the tasks of the selected day
+#[derive(Debug, Clone)]
+pub struct CalendarCtrl {
+    pub selected: NaiveDate,
+    /// tasks due in the month of the selected day
+    pub tasks: Vec<HashMap<String, String>>,
+    /// (year, month) the tasks were read for
+    pub loaded_month: Option<(i32, u32)>,
+    pub done_id: i32,
+    /// the list of the selected day has the focus instead of the grid
+    pub day_focus: bool,
+    pub l_state: ListState,
+    /// details of the selected task are shown in a popup
+    pub show_task: bool,
+    /// new due date being typed for the selected task
+    pub reschedule: Option<TextInput>,
+}
+
+impl Default for CalendarCtrl {
+    fn default() -> CalendarCtrl {
+        CalendarCtrl {
+            selected: Local::now().date_naive(),
+            tasks: vec![],
+            loaded_month: None,
+            done_id: 0,
+            day_focus: false,
+            l_state: ListState::default(),
+            show_task: false,
+            reschedule: None,
+        }
+    }
+}
+
+impl UtilFns for CalendarCtrl {}
+
+impl CtrObj for CalendarCtrl {
+    fn init_data(&mut self) {
+        let month = (self.selected.year(), self.selected.month());
+        if self.loaded_month != Some(month) {
+            self.loaded_month = Some(month);
+            self.load_tasks();
+        }
+    }
+
+    fn display(&mut self, f: &mut Frame, area: Rect, theme: &Theme) -> Result<(), Error> {
+        calendar_view(self, f, area, theme);
+        Ok(())
+    }
+
+    fn key_context(&self) -> KeyContext {
+        match (&self.reschedule, self.show_task) {
+            (Some(_), _) => KeyContext::Form,
+            (None, true) => KeyContext::Popup,
+            (None, false) => KeyContext::Board,
+        }
+    }
+
+    fn action_handler(&mut self, action: &Action) -> AppState {
+        if self.reschedule.is_some() {
+            return self.reschedule_action_handler(action);
+        }
+        if self.show_task {
+            if let Action::Cancel | Action::Confirm = action {
+                self.show_task = false;
+            }
+            return AppState::MoveOn;
+        }
+        match self.day_focus {
+            true => self.day_action_handler(action),
+            false => self.grid_action_handler(action),
+        }
+    }
+}
+
+impl CalendarCtrl {
+    pub fn load_tasks(&mut self) {
+        let custom_query = format!(
+            "select t.*, p.name as 'project_name', ts.name as 'status_name', {} from task as t left join project as p on (t.project_id = p.id) LEFT JOIN task_status as ts ON (t.status = ts.id) WHERE t.due_date LIKE '{}-%' ORDER BY t.due_date, t.id",
+            TaskAssignee::names_column("t"),
+            self.selected.format("%Y-%m")
+        );
+        match Self::get_db_connection() {
+            Ok(conn) => {
+                self.done_id = TaskStatus::done_id(&conn);
+                match Task::query(&conn, custom_query) {
+                    Ok(list) => self.tasks = list,
+                    Err(e) => {
+                        //@TODO: show popup error
+                        log::error!("failed to read the tasks of the month: {}", e);
+                    }
+                }
+            }
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                //@TODO: show popup error
+            }
+        }
+        self.select_first_task();
+    }
+
+    /// tasks due on the selected day
+    pub fn day_tasks(&self) -> Vec<&HashMap<String, String>> {
+        let day = self.selected.format(DUE_DATE_FORMAT).to_string();
+        self.tasks
+            .iter()
+            .filter(|task| task.get("due_date") == Some(&day))
+            .collect()
+    }
+
+    pub fn selected_task(&self) -> Option<&HashMap<String, String>> {
+        self.l_state
+            .selected()
+            .and_then(|idx| self.day_tasks().get(idx).copied())
+    }
+
+    /// due before today and not done
+    pub fn is_overdue(&self, task: &HashMap<String, String>) -> bool {
+        let due = task
+            .get("due_date")
+            .and_then(|d| NaiveDate::parse_from_str(d, DUE_DATE_FORMAT).ok());
+        let status = task.get("status").and_then(|s| s.parse::<i32>().ok());
+        match due {
+            Some(due) => due < Local::now().date_naive() && status != Some(self.done_id),
+            None => false,
+        }
+    }
+
+    fn select_first_task(&mut self) {
+        self.l_state.select(match self.day_tasks().is_empty() {
+            true => None,
+            false => Some(0),
+        });
+    }
+
+    fn select_day(&mut self, day: NaiveDate) {
+        self.selected = day;
+        // a new month is read by init_data
+        self.select_first_task();
+    }
+
+    /// same day of the next or previous month, or its last day when shorter
+    fn shift_month(&mut self, months: i32) {
+        let month0 = self.selected.year() * 12 + self.selected.month0() as i32 + months;
+        let (year, month) = (month0.div_euclid(12), month0.rem_euclid(12) as u32 + 1);
+        let day = (1..=self.selected.day())
+            .rev()
+            .find_map(|day| NaiveDate::from_ymd_opt(year, month, day));
+        if let Some(day) = day {
+            self.select_day(day);
+        }
+    }
+
+    fn grid_action_handler(&mut self, action: &Action) -> AppState {
+        match action {
+            Action::Left => self.select_day(self.selected - Duration::days(1)),
+            Action::Right => self.select_day(self.selected + Duration::days(1)),
+            Action::Up => self.select_day(self.selected - Duration::days(7)),
+            Action::Down => self.select_day(self.selected + Duration::days(7)),
+            Action::MovePrev => self.shift_month(-1),
+            Action::MoveNext => self.shift_month(1),
+            Action::First => self.select_day(Local::now().date_naive()),
+            Action::Confirm => {
+                if !self.day_tasks().is_empty() {
+                    self.day_focus = true;
+                    self.select_first_task();
+                }
+            }
+            _ => return AppState::Running,
+        }
+        AppState::MoveOn
+    }
+
+    fn day_action_handler(&mut self, action: &Action) -> AppState {
+        let count = self.day_tasks().len();
+        match action {
+            Action::Up | Action::Down if count > 0 => {
+                let idx = self.l_state.selected().unwrap_or(0);
+                self.l_state.select(Some(match action {
+                    Action::Up => (idx + count - 1) % count,
+                    _ => (idx + 1) % count,
+                }));
+            }
+            Action::Confirm if self.selected_task().is_some() => self.show_task = true,
+            Action::MovePrev => self.shift_due_date(-1),
+            Action::MoveNext => self.shift_due_date(1),
+            Action::Reschedule => {
+                if let Some(task) = self.selected_task() {
+                    self.reschedule = Some(TextInput::new(
+                        task.get("due_date").map(|d| d.as_str()).unwrap_or(""),
+                    ));
+                }
+            }
+            Action::Cancel => self.day_focus = false,
+            _ => return AppState::Running,
+        }
+        AppState::MoveOn
+    }
+
+    fn reschedule_action_handler(&mut self, action: &Action) -> AppState {
+        match action {
+            Action::Cancel => self.reschedule = None,
+            Action::Confirm => {
+                let value = self
+                    .reschedule
+                    .take()
+                    .map(|input| input.value().trim().to_string())
+                    .unwrap_or_default();
+                match NaiveDate::parse_from_str(&value, DUE_DATE_FORMAT) {
+                    Ok(day) => self.set_due_date(day),
+                    Err(_) => {
+                        log::error!("invalid due date '{}', expected YYYY-MM-DD", value);
+                        //@TODO: show popup error
+                    }
+                }
+            }
+            Action::Insert(c) => {
+                if let Some(input) = self.reschedule.as_mut() {
+                    input.insert(*c);
+                }
+            }
+            action => {
+                if let Some(input) = self.reschedule.as_mut() {
+                    input.handle_action(action);
+                }
+            }
+        }
+        AppState::MoveOn
+    }
+
+    /// moves the due date of the selected task by some days
+    fn shift_due_date(&mut self, days: i64) {
+        self.set_due_date(self.selected + Duration::days(days));
+    }
+
+    /// sets the due date of the selected task and follows it to its new day
+    fn set_due_date(&mut self, day: NaiveDate) {
+        let id = match self
+            .selected_task()
+            .and_then(|task| task.get("id"))
+            .and_then(|id| id.parse::<i32>().ok())
+        {
+            Some(id) => id,
+            None => return,
+        };
+        let conn = match Self::get_db_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                //@TODO: show popup error
+                return;
+            }
+        };
+        let saved = Task::get_by_id(&conn, id).and_then(|mut task| {
+            task.due_date = day.format(DUE_DATE_FORMAT).to_string();
+            task.save(&conn)
+        });
+        if let Err(e) = saved {
+            log::error!("failed to reschedule task {}: {}", id, e);
+            //@TODO: show popup error
+            return;
+        }
+        self.selected = day;
+        self.load_tasks();
+        self.loaded_month = Some((day.year(), day.month()));
+        let idx = self
+            .day_tasks()
+            .iter()
+            .position(|task| task.get("id").and_then(|i| i.parse::<i32>().ok()) == Some(id));
+        self.l_state.select(idx);
+    }
+}
@@ -1,9 +1,12 @@
 use super::{CtrObj, CtrlActions};
 use crate::app::AppState;
-use crate::models::{person::Person, DbObj};
+use crate::keymap::{Action, KeyContext};
+use crate::models::{person::Person, task_assignee::TaskAssignee, DbObj};
+use crate::mouse::{MouseInput, MouseKind};
 use crate::views::contact::*;
-use crate::UtilFns;
-use crossterm::event::{KeyCode, KeyEvent};
+use crate::views::input::TextInput;
+use crate::views::theme::Theme;
+use crate::{identity, UtilFns};
 use ratatui::{layout::Rect, widgets::TableState, Frame};
 use rusqlite::Error as RuError;
 use std::io::Error;
@@ -14,9 +17,11 @@ pub struct ContactCtrl {
     pub action: CtrlActions,
     pub active_item: usize,
     pub field_idx: u32,
-    pub input: String,
+    pub input: TextInput,
     pub t_state: TableState,
     pub record_count: usize,
+    /// screen areas of the visible table rows, set while rendering
+    pub row_areas: Vec<(usize, Rect)>,
 }
 
 impl Default for ContactCtrl {
@@ -26,9 +31,10 @@ impl Default for ContactCtrl {
             action: CtrlActions::List,
             active_item: 0,
             field_idx: 0,
-            input: String::new(),
+            input: TextInput::default(),
             t_state: TableState::default(),
             record_count: 0,
+            row_areas: vec![],
         }
     }
 }
@@ -37,22 +43,37 @@ impl UtilFns for ContactCtrl {}
 
 impl CtrObj for ContactCtrl {
     fn init_data(&mut self) {}
-    fn display(&mut self, f: &mut Frame, area: Rect) -> Result<(), Error> {
+    fn display(&mut self, f: &mut Frame, area: Rect, theme: &Theme) -> Result<(), Error> {
         match self.action {
-            CtrlActions::List => list_view(self, f, area),
-            CtrlActions::Edit => edit_view(self, f, area),
-            CtrlActions::Del => del_view(self, f, area),
-            CtrlActions::Detail => detail_view(self, f, area),
+            CtrlActions::List => list_view(self, f, area, theme),
+            CtrlActions::Edit => edit_view(self, f, area, theme),
+            CtrlActions::Del => del_view(self, f, area, theme),
+            CtrlActions::Detail => detail_view(self, f, area, theme),
         }
         Ok(())
     }
 
-    fn key_event_handler(&mut self, key: &KeyEvent) -> AppState {
+    fn key_context(&self) -> KeyContext {
         match self.action {
-            CtrlActions::List => return self.list_key_event(key),
-            CtrlActions::Edit => return self.edit_key_event(key),
-            CtrlActions::Detail => return self.detail_key_event(key),
-            CtrlActions::Del => return self.del_key_event(key),
+            CtrlActions::List => KeyContext::List,
+            CtrlActions::Edit => KeyContext::Form,
+            CtrlActions::Detail | CtrlActions::Del => KeyContext::Popup,
+        }
+    }
+
+    fn action_handler(&mut self, action: &Action) -> AppState {
+        match self.action {
+            CtrlActions::List => self.list_action_handler(action),
+            CtrlActions::Edit => self.edit_action_handler(action),
+            CtrlActions::Detail => self.detail_action_handler(action),
+            CtrlActions::Del => self.del_action_handler(action),
+        }
+    }
+
+    fn mouse_handler(&mut self, input: &MouseInput) -> AppState {
+        match self.action {
+            CtrlActions::List => self.list_mouse_handler(input),
+            _ => AppState::Running,
         }
     }
 }
@@ -69,7 +90,7 @@ impl ContactCtrl {
             },
             Err(e) => {
                 //@TODO: show error popup
-                println!("error getting connection: {}", e.to_string());
+                log::error!("error getting connection: {}", e);
                 return Err(RuError::InvalidQuery);
             }
         }
@@ -77,9 +98,9 @@ impl ContactCtrl {
 
     pub fn update_field(&mut self) {
         match self.field_idx {
-            0 => self.table.first_name = self.input.clone(),
-            1 => self.table.last_name = self.input.clone(),
-            2 => self.table.email = self.input.clone(),
+            0 => self.table.first_name = self.input.value().to_string(),
+            1 => self.table.last_name = self.input.value().to_string(),
+            2 => self.table.email = self.input.value().to_string(),
             _ => {}
         }
     }
@@ -90,17 +111,20 @@ impl ContactCtrl {
                 Ok(conn) => {
                     match self.table.del(&conn) {
                         Ok(()) => {
+                            if let Err(e) = TaskAssignee::delete_for_person(&conn, self.table.id) {
+                                log::error!("failed to unassign tasks: {}", e);
+                            }
                             self.table = Person::default();
                             self.go_back();
                         }
                         Err(e) => {
                             //@TODO: show popup error
-                            println!("faile to save record {}", e.to_string());
+                            log::error!("failed to delete record: {}", e);
                         }
                     }
                 }
-                Err(_e) => {
-                    println!("ERROR NO DATABASE CONN");
+                Err(e) => {
+                    log::error!("no database connection: {}", e);
                     //@TODO: show popup error
                 }
             }
@@ -118,12 +142,12 @@ impl ContactCtrl {
                     }
                     Err(e) => {
                         //@TODO: show popup error
-                        println!("faile to save record {}", e.to_string());
+                        log::error!("failed to save record: {}", e);
                     }
                 }
             }
-            Err(_e) => {
-                println!("ERROR NO DATABASE CONN");
+            Err(e) => {
+                log::error!("no database connection: {}", e);
                 //@TODO: show popup error
             }
         }
@@ -179,41 +203,76 @@ impl ContactCtrl {
         }
     }
 
-    pub fn list_key_event(&mut self, key: &KeyEvent) -> AppState {
-        match key.code {
-            KeyCode::Char('n') => {
+    pub fn list_action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::New => {
                 self.action = CtrlActions::Edit;
                 self.table = Person::default();
+                self.set_active_field(0);
                 return AppState::MoveOn;
             }
-            KeyCode::Char('e') => {
+            Action::Edit => {
                 self.set_selected_record();
                 self.action = CtrlActions::Edit;
+                self.set_active_field(0);
                 return AppState::MoveOn;
             }
 
-            KeyCode::Char('d') => {
+            Action::Delete => {
                 self.set_selected_record();
                 self.action = CtrlActions::Del;
                 return AppState::MoveOn;
             }
 
-            KeyCode::Char('s') => {
+            Action::Show => {
                 self.set_selected_record();
                 self.action = CtrlActions::Detail;
                 return AppState::MoveOn;
             }
 
-            KeyCode::Up => {
+            Action::Up => {
                 self.previous_row();
                 return AppState::MoveOn;
             }
 
-            KeyCode::Down => {
+            Action::Down => {
                 self.next_row();
                 return AppState::MoveOn;
             }
 
+            Action::First => {
+                if self.record_count > 0 {
+                    self.t_state.select(Some(0));
+                }
+                return AppState::MoveOn;
+            }
+
+            Action::Last => {
+                if self.record_count > 0 {
+                    self.t_state.select(Some(self.record_count - 1));
+                }
+                return AppState::MoveOn;
+            }
+
+            Action::SetMe => {
+                if self.t_state.selected().is_some() {
+                    self.set_selected_record();
+                    match Self::get_db_connection() {
+                        Ok(conn) => {
+                            if let Err(e) = identity::set_current_user(&conn, self.table.id) {
+                                //@TODO: show popup error
+                                log::error!("failed to set current user: {}", e);
+                            }
+                        }
+                        Err(e) => {
+                            log::error!("no database connection: {}", e);
+                            //@TODO: show popup error
+                        }
+                    }
+                }
+                return AppState::MoveOn;
+            }
+
             _ => {
                 //println!("key pressed '{:?}'", key.code);
             }
@@ -222,19 +281,37 @@ impl ContactCtrl {
         AppState::Running
     }
 
+    pub fn list_mouse_handler(&mut self, input: &MouseInput) -> AppState {
+        match input.kind {
+            MouseKind::ScrollUp => self.list_action_handler(&Action::Up),
+            MouseKind::ScrollDown => self.list_action_handler(&Action::Down),
+            MouseKind::Click | MouseKind::DoubleClick => match input.hit(&self.row_areas) {
+                Some(idx) => {
+                    self.t_state.select(Some(idx));
+                    if input.kind == MouseKind::DoubleClick {
+                        return self.list_action_handler(&Action::Show);
+                    }
+                    AppState::MoveOn
+                }
+                None => AppState::Running,
+            },
+            _ => AppState::Running,
+        }
+    }
+
     pub fn go_back(&mut self) {
-        self.input = "".to_string();
+        self.input.clear();
         self.field_idx = 0;
         self.action = CtrlActions::List;
     }
 
-    pub fn del_key_event(&mut self, key: &KeyEvent) -> AppState {
-        match key.code {
-            KeyCode::Esc => {
+    pub fn del_action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::Cancel => {
                 self.go_back();
             }
 
-            KeyCode::Enter => {
+            Action::Confirm => {
                 self.del_record();
             }
 
@@ -245,14 +322,15 @@ impl ContactCtrl {
 
         AppState::MoveOn
     }
-    pub fn detail_key_event(&mut self, key: &KeyEvent) -> AppState {
-        match key.code {
-            KeyCode::Esc => {
+    pub fn detail_action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::Cancel => {
                 self.go_back();
             }
 
-            KeyCode::Enter => {
+            Action::Confirm => {
                 self.action = CtrlActions::Edit;
+                self.set_active_field(0);
             }
 
             _ => {
@@ -263,34 +341,30 @@ impl ContactCtrl {
         AppState::MoveOn
     }
 
-    pub fn edit_key_event(&mut self, key: &KeyEvent) -> AppState {
-        match key.code {
-            KeyCode::Tab => {
+    pub fn edit_action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::NextField => {
                 self.set_next_active();
             }
 
-            KeyCode::Esc => {
+            Action::PrevField => {
+                self.set_prev_active();
+            }
+
+            Action::Cancel => {
                 self.go_back();
                 //@TODO: reset all fields when to empty if we were creating a new project or to the
                 //fields original values of the selected project
             }
 
-            KeyCode::Backspace => {
-                self.input.pop();
-                self.update_field();
-            }
-
-            KeyCode::Enter => {
+            Action::Confirm => {
                 self.save_record();
             }
 
-            KeyCode::Char(c) => {
-                self.input.push(c);
-                self.update_field();
-            }
-
-            _ => {
-                //println!("key pressed '{:?}'", key.code);
+            action => {
+                if self.input.handle_action(&action) {
+                    self.update_field();
+                }
             }
         }
 
@@ -298,21 +372,25 @@ impl ContactCtrl {
     }
 
     pub fn set_next_active(&mut self) {
-        match self.action {
-            CtrlActions::Edit => {
-                self.field_idx = self.field_idx + 1;
-                if self.field_idx == 5 {
-                    self.field_idx = 0;
-                }
-                self.input = match self.field_idx {
-                    0 => self.table.first_name.clone(),
-                    1 => self.table.last_name.clone(),
-                    2 => self.table.email.clone(),
-                    _ => "".to_string(),
-                }
-            }
+        if let CtrlActions::Edit = self.action {
+            self.set_active_field((self.field_idx + 1) % 3);
+        }
+    }
 
-            _ => {}
+    pub fn set_prev_active(&mut self) {
+        if let CtrlActions::Edit = self.action {
+            self.set_active_field((self.field_idx + 3 - 1) % 3);
         }
     }
+
+    fn set_active_field(&mut self, field_idx: u32) {
+        self.field_idx = field_idx;
+        let value = match self.field_idx {
+            0 => self.table.first_name.clone(),
+            1 => self.table.last_name.clone(),
+            2 => self.table.email.clone(),
+            _ => "".to_string(),
+        };
+        self.input = TextInput::new(&value);
+    }
 }
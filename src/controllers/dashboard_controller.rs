@@ -1,9 +1,19 @@
+use super::task_controller::TaskCtrl;
 use super::{CtrObj, CtrlActions};
 use crate::app::AppState;
-use crate::models::{project::Project, task::Task, DbObj};
+use crate::burndown::{BurnChart, ChartKind};
+use crate::config;
+use crate::keymap::{Action, KeyContext};
+use crate::models::{
+    checklist_item::ChecklistItem, project::Project, sprint::Sprint, task::Task,
+    task::PRIORITIES, task_assignee::TaskAssignee, task_dependency::TaskDependency,
+    task_status::TaskStatus, task_tag::TaskTag, DbObj,
+};
+use crate::mouse::{MouseInput, MouseKind};
 use crate::views::dashboard::*;
+use crate::views::input::TextInput;
+use crate::views::theme::Theme;
 use crate::UtilFns;
-use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     layout::Rect,
     widgets::{ListState, TableState},
@@ -12,21 +22,59 @@ use ratatui::{
 use rusqlite::Error as RuError;
 use std::{collections::HashMap, io::Error};
 
+/// entries on top of the project list, the board of every task and the one of the tasks that
+/// belong to no project
+pub const ALL_PROJECTS: i32 = -1;
+pub const INBOX: i32 = 0;
+
 #[derive(Debug, Clone)]
 pub enum PopupDashboardType {
     NoPopup,
-    ProjectList,
+    WipLimit,
+}
+
+/// what the rows of the scrumboard group the tasks by, 'w' goes through them in this order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SwimlaneGrouping {
+    None,
+    Assignee,
+    /// the first tag of the task by name
+    Tag,
+    Parent,
+    Priority,
+}
+
+impl SwimlaneGrouping {
+    pub fn next(&self) -> SwimlaneGrouping {
+        match self {
+            SwimlaneGrouping::None => SwimlaneGrouping::Assignee,
+            SwimlaneGrouping::Assignee => SwimlaneGrouping::Tag,
+            SwimlaneGrouping::Tag => SwimlaneGrouping::Parent,
+            SwimlaneGrouping::Parent => SwimlaneGrouping::Priority,
+            SwimlaneGrouping::Priority => SwimlaneGrouping::None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SwimlaneGrouping::None => "none",
+            SwimlaneGrouping::Assignee => "assignee",
+            SwimlaneGrouping::Tag => "tag",
+            SwimlaneGrouping::Parent => "parent task",
+            SwimlaneGrouping::Priority => "priority",
+        }
+    }
 }
+
 #[derive(Debug, Clone)]
 pub struct DashboardCtrl {
     pub projects: Project,
     pub action: CtrlActions,
     pub active_item: usize,
-    pub field_idx: u32,
     pub scrum_col_focus: i32,
     pub scrum_col_count: usize,
     pub scrum_col_list: Vec<HashMap<String, String>>,
-    pub input: String,
+    pub input: TextInput,
     pub l_state: ListState,
     pub l_scrum_state: ListState,
     pub record_count: usize,
@@ -35,6 +83,36 @@ pub struct DashboardCtrl {
     pub task_status_vec: Vec<HashMap<String, String>>,
     pub show_popup: bool,
     pub popup_type: PopupDashboardType,
+    /// screen areas set while rendering: project list items, board columns and their cards
+    pub project_areas: Vec<(usize, Rect)>,
+    pub column_areas: Vec<(usize, Rect)>,
+    pub card_areas: Vec<Vec<(usize, Rect)>>,
+    /// task picked up with the mouse and the column it is dragged over
+    pub drag_task: Option<i32>,
+    pub drop_col: Option<usize>,
+    /// active sprint of the selected project, the board only shows its tasks
+    pub sprint: Option<Sprint>,
+    /// show every task of the project even when a sprint is active
+    pub whole_project: bool,
+    /// burndown/burnup chart shown instead of the board, for the project or one of its sprints
+    pub chart: Option<BurnChart>,
+    pub chart_scopes: Vec<Option<Sprint>>,
+    pub chart_scope: usize,
+    /// where the last export of the chart went
+    pub chart_msg: String,
+    /// task and status of a move into a full column, waiting for confirmation
+    pub pending_move: Option<(i32, i32)>,
+    /// tasks of the project in each status, sprint or not, counted against the WIP limits
+    pub status_counts: HashMap<i32, usize>,
+    /// swimlanes of the board, the focused one and the keys of those folded to their header
+    pub lanes_by: SwimlaneGrouping,
+    pub lane_focus: usize,
+    pub collapsed_lanes: Vec<String>,
+    pub lane_areas: Vec<(usize, Rect)>,
+    /// card opened on the board, shown with the detail view of the Tasks tab
+    pub task_detail: Option<TaskCtrl>,
+    /// projects ticked in the list, the board shows their tasks together
+    pub picked_projects: Vec<i32>,
 }
 
 impl Default for DashboardCtrl {
@@ -43,11 +121,10 @@ impl Default for DashboardCtrl {
             projects: Project::default(),
             action: CtrlActions::List,
             active_item: 0,
-            field_idx: 0,
             scrum_col_focus: 0,
             scrum_col_count: 0,
             scrum_col_list: vec![],
-            input: String::new(),
+            input: TextInput::default(),
             l_state: ListState::default(),
             l_scrum_state: ListState::default(),
             record_count: 0,
@@ -56,6 +133,25 @@ impl Default for DashboardCtrl {
             tasks_vec: vec![],
             task_status_vec: vec![],
             popup_type: PopupDashboardType::NoPopup,
+            project_areas: vec![],
+            column_areas: vec![],
+            card_areas: vec![],
+            drag_task: None,
+            drop_col: None,
+            sprint: None,
+            whole_project: false,
+            chart: None,
+            chart_scopes: vec![],
+            chart_scope: 0,
+            chart_msg: "".to_string(),
+            pending_move: None,
+            status_counts: HashMap::new(),
+            lanes_by: SwimlaneGrouping::None,
+            lane_focus: 0,
+            collapsed_lanes: vec![],
+            lane_areas: vec![],
+            task_detail: None,
+            picked_projects: vec![],
         }
     }
 }
@@ -65,7 +161,10 @@ impl UtilFns for DashboardCtrl {}
 impl CtrObj for DashboardCtrl {
     fn init_data(&mut self) {
         match self.project_list() {
-            Ok(list) => self.projects_vec = list.clone(),
+            Ok(list) => {
+                self.projects_vec = Self::pseudo_projects();
+                self.projects_vec.extend(list);
+            }
             Err(_e) => self.projects_vec = vec![],
         }
         match self.task_status() {
@@ -86,22 +185,61 @@ impl CtrObj for DashboardCtrl {
         }
     }
 
-    fn display(&mut self, f: &mut Frame, area: Rect) -> Result<(), Error> {
+    fn display(&mut self, f: &mut Frame, area: Rect, theme: &Theme) -> Result<(), Error> {
+        if self.task_detail.is_some() {
+            card_view(self, f, area, theme);
+            return Ok(());
+        }
         match self.action {
-            CtrlActions::List => list_view(self, f, area),
-            CtrlActions::Edit => {}
-            CtrlActions::Del => {}
-            CtrlActions::Detail => detail_view(self, f, area),
+            CtrlActions::List | CtrlActions::Edit | CtrlActions::Del => {
+                list_view(self, f, area, theme)
+            }
+            CtrlActions::Detail => detail_view(self, f, area, theme),
         }
         Ok(())
     }
 
-    fn key_event_handler(&mut self, key: &KeyEvent) -> AppState {
+    fn key_context(&self) -> KeyContext {
+        if let Some(task) = &self.task_detail {
+            return task.key_context();
+        }
         match self.action {
-            CtrlActions::List => return self.list_key_event(key),
-            CtrlActions::Edit => return self.edit_key_event(key),
-            CtrlActions::Detail => return self.detail_key_event(key),
-            CtrlActions::Del => return self.del_key_event(key),
+            CtrlActions::List if self.show_popup => KeyContext::Popup,
+            CtrlActions::List => KeyContext::Board,
+            CtrlActions::Edit => KeyContext::Form,
+            CtrlActions::Detail | CtrlActions::Del => KeyContext::Popup,
+        }
+    }
+
+    fn action_handler(&mut self, action: &Action) -> AppState {
+        if self.task_detail.is_some() {
+            return self.task_detail_action_handler(action);
+        }
+        match self.action {
+            CtrlActions::List => self.list_action_handler(action),
+            CtrlActions::Edit => self.edit_action_handler(action),
+            CtrlActions::Detail => self.detail_action_handler(action),
+            CtrlActions::Del => self.del_action_handler(action),
+        }
+    }
+
+    fn mouse_handler(&mut self, input: &MouseInput) -> AppState {
+        if let Some(task) = self.task_detail.as_mut() {
+            return task.mouse_handler(input);
+        }
+        match self.action {
+            CtrlActions::List if !self.show_popup => self.board_mouse_handler(input),
+            _ => AppState::Running,
+        }
+    }
+
+    fn editor_text(&self) -> Option<String> {
+        self.task_detail.as_ref().and_then(|task| task.editor_text())
+    }
+
+    fn set_editor_text(&mut self, text: &str) {
+        if let Some(task) = self.task_detail.as_mut() {
+            task.set_editor_text(text);
         }
     }
 }
@@ -119,7 +257,7 @@ impl DashboardCtrl {
             },
             Err(e) => {
                 //@TODO: show error popup
-                println!("error getting connection: {}", e.to_string());
+                log::error!("error getting connection: {}", e);
                 return Err(RuError::InvalidQuery);
             }
         }
@@ -140,47 +278,129 @@ impl DashboardCtrl {
             Err(e) => return Err(e),
         }
     }
-    pub fn project_tasks(
-        &mut self,
-        project_id: i32,
-    ) -> Result<Vec<HashMap<String, String>>, RuError> {
+    /// the "All projects" and "Inbox" entries of the project list
+    fn pseudo_projects() -> Vec<HashMap<String, String>> {
+        [(ALL_PROJECTS, "All projects"), (INBOX, "Inbox")]
+            .iter()
+            .map(|(id, name)| {
+                let mut entry = HashMap::new();
+                entry.insert("id".to_string(), id.to_string());
+                entry.insert("name".to_string(), name.to_string());
+                entry
+            })
+            .collect()
+    }
+
+    /// the project the board shows the tasks of, `INBOX` for the tasks without a project, None
+    /// when it shows several projects
+    pub fn single_project(&self) -> Option<i32> {
+        match (self.picked_projects.as_slice(), self.projects.id) {
+            ([id], _) => Some(*id),
+            ([], ALL_PROJECTS) => None,
+            ([], id) => Some(id),
+            _ => None,
+        }
+    }
+
+    pub fn project_tasks(&mut self) -> Result<Vec<HashMap<String, String>>, RuError> {
+        let project_filter = match (self.picked_projects.is_empty(), self.projects.id) {
+            (false, _) => format!(
+                "t.project_id IN ({})",
+                self.picked_projects
+                    .iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            (true, ALL_PROJECTS) => "1 = 1".to_string(),
+            (true, id) => format!("coalesce(t.project_id, 0) = {}", id),
+        };
+        let sprint_filter = match (&self.sprint, self.whole_project) {
+            (Some(sprint), false) => format!(" and t.sprint_id='{}'", sprint.id),
+            _ => "".to_string(),
+        };
+        let conn = Self::get_db_connection()?;
         let custom_query = format!(
-            "select id, name, status from task where project_id='{}' order by name",
-            project_id
+            "select t.id, t.name, t.status, t.weight, t.due_date, t.parent_id, t.priority, coalesce((select pt.name from task as pt where pt.id = t.parent_id), '') as 'parent_name', coalesce((select p.name from project as p where p.id = t.project_id), '') as 'project_name', {}, {}, {}, {}, {}, {} from task as t where {}{} order by t.position, t.name",
+            ChecklistItem::progress_column("t"),
+            TaskAssignee::initials_column("t"),
+            TaskAssignee::first_assignee_columns("t"),
+            TaskTag::names_column("t"),
+            TaskTag::first_tag_column("t"),
+            TaskDependency::blocked_column("t", TaskStatus::done_id(&conn)),
+            project_filter,
+            sprint_filter
         );
-        match Self::get_db_connection() {
-            Ok(conn) => return Task::query(&conn, custom_query),
-            Err(e) => return Err(e),
-        }
+        Task::query(&conn, custom_query)
     }
 
-    pub fn update_field(&mut self) {
-        match self.field_idx {
-            0 => { /* self.projects.project_id = self.input.parse().unwrap() */ }
-            1 => self.projects.name = self.input.clone(),
-            2 => self.projects.description = self.input.clone(),
-            _ => {}
+    /// reads the tasks of the board again
+    fn reload_tasks(&mut self) {
+        match self.project_tasks() {
+            Ok(tasks_list) => self.tasks_vec = tasks_list,
+            Err(e) => {
+                //@TODO: show popup error
+                log::error!("failed to read the tasks of the board: {}", e);
+                self.tasks_vec = vec![];
+            }
         }
+        let counts = self
+            .single_project()
+            .map(|project_id| {
+                Self::get_db_connection().and_then(|conn| Task::status_counts(&conn, project_id))
+            });
+        self.status_counts = match counts {
+            Some(Ok(counts)) => counts,
+            Some(Err(e)) => {
+                log::error!("failed to count the tasks of the project: {}", e);
+                HashMap::new()
+            }
+            None => HashMap::new(),
+        };
     }
 
     pub fn load_selected_project(&mut self, id: i32) {
-        match Self::get_db_connection() {
-            Ok(conn) => match Project::get_by_id(&conn, id) {
-                Ok(project_record) => self.projects = project_record,
+        if id <= 0 {
+            let name = match id {
+                ALL_PROJECTS => "All projects",
+                _ => "Inbox",
+            };
+            self.projects = Project {
+                id,
+                name: name.to_string(),
+                ..Project::default()
+            };
+            self.sprint = None;
+        } else {
+            match Self::get_db_connection() {
+                Ok(conn) => {
+                    match Project::get_by_id(&conn, id) {
+                        Ok(project_record) => self.projects = project_record,
+                        Err(_e) => {}
+                    }
+                    self.sprint = Sprint::active(&conn, id);
+                }
                 Err(_e) => {}
-            },
-            Err(_e) => {}
+            }
         }
-        if self.projects.id > 0 {
-            match self.project_tasks(self.projects.id) {
-                Ok(tasks_list) => {
-                    self.tasks_vec = tasks_list.clone();
-                }
-                Err(_e) => {
-                    self.tasks_vec = vec![];
-                }
+        // ticked projects are shown whole
+        if !self.picked_projects.is_empty() {
+            self.sprint = None;
+        }
+        self.reload_tasks();
+    }
+
+    /// ticks or unticks the highlighted project, "All projects" and "Inbox" untick them all
+    fn toggle_picked_project(&mut self) {
+        let id = self.projects.id;
+        match self.picked_projects.iter().position(|p| *p == id) {
+            Some(idx) => {
+                self.picked_projects.remove(idx);
             }
+            None if id > 0 => self.picked_projects.push(id),
+            None => self.picked_projects.clear(),
         }
+        self.load_selected_project(id);
     }
 
     pub fn previous_row(&mut self) {}
@@ -251,6 +471,17 @@ impl DashboardCtrl {
         }
     }
 
+    /// selects the project at (1 based) `position` in the project list
+    pub fn select_item(&mut self, position: usize) {
+        if self.record_count > 0 && position > 0 {
+            let itm = position.min(self.record_count) - 1;
+            self.l_state.select(Some(itm));
+            if let Some(id) = self.projects_vec[itm].get("id") {
+                self.load_selected_project(id.parse::<i32>().unwrap());
+            }
+        }
+    }
+
     pub fn next_item(&mut self) {
         if self.record_count > 0 {
             let itm = match self.l_state.selected() {
@@ -271,33 +502,357 @@ impl DashboardCtrl {
         }
     }
 
-    fn update_scrum_task(&mut self, task_id: i32, task_status: i32) {
-        match Self::get_db_connection() {
-            Ok(conn) => match Task::get_by_id(&conn, task_id) {
-                Ok(mut task) => {
-                    task.status = task_status;
-                    match task.save(&conn) {
-                        Ok(updated_task) => match self.project_tasks(updated_task.project_id) {
-                            Ok(tasks_list) => self.tasks_vec = tasks_list.clone(),
-                            Err(e) => {}
-                        },
-                        Err(e) => {}
+    /// key of the swimlane a task goes in, empty for the tasks without an assignee, tag or
+    /// parent
+    pub fn lane_key(&self, task: &HashMap<String, String>) -> String {
+        let key = match self.lanes_by {
+            SwimlaneGrouping::None => None,
+            SwimlaneGrouping::Assignee => task.get("assignee_id"),
+            SwimlaneGrouping::Tag => task.get("tag_name"),
+            SwimlaneGrouping::Parent => task.get("parent_id"),
+            // every task has a priority, P0 included
+            SwimlaneGrouping::Priority => {
+                return task.get("priority").cloned().unwrap_or_default();
+            }
+        };
+        match key {
+            Some(key) if key != "0" => key.clone(),
+            _ => "".to_string(),
+        }
+    }
+
+    /// key and name of the swimlanes of the board sorted by name, the tasks without an assignee,
+    /// tag or parent go in the last one
+    pub fn lanes(&self) -> Vec<(String, String)> {
+        let mut lanes: Vec<(String, String)> = vec![];
+        let mut rest = false;
+        for task in &self.tasks_vec {
+            let key = self.lane_key(task);
+            if key.is_empty() {
+                rest = true;
+            } else if !lanes.iter().any(|(k, _)| *k == key) {
+                let field = |name: &str| task.get(name).cloned().unwrap_or_default();
+                let name = match self.lanes_by {
+                    SwimlaneGrouping::Tag => key.clone(),
+                    SwimlaneGrouping::Parent => format!("#{} {}", key, field("parent_name")),
+                    SwimlaneGrouping::Priority => key
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|p| PRIORITIES.get(p))
+                        .map_or(key.clone(), |p| p.to_string()),
+                    _ => field("assignee_name"),
+                };
+                lanes.push((key, name));
+            }
+        }
+        lanes.sort_by_key(|(_, name)| name.to_lowercase());
+        if rest {
+            let name = match self.lanes_by {
+                SwimlaneGrouping::Tag => "No tag",
+                SwimlaneGrouping::Parent => "No parent",
+                _ => "Unassigned",
+            };
+            lanes.push(("".to_string(), name.to_string()));
+        }
+        lanes
+    }
+
+    pub fn lanes_on(&self) -> bool {
+        self.lanes_by != SwimlaneGrouping::None
+    }
+
+    pub fn is_collapsed(&self, key: &str) -> bool {
+        self.collapsed_lanes.iter().any(|k| k == key)
+    }
+
+    /// tasks of the board column at `col` (0 based), only those of the focused swimlane when the
+    /// board has some
+    pub fn column_tasks(&self, col: usize) -> Vec<HashMap<String, String>> {
+        let lane = match self.lanes_on() {
+            true => match self.lanes().get(self.lane_focus) {
+                Some((key, _)) if !self.is_collapsed(key) => Some(key.clone()),
+                _ => return vec![],
+            },
+            false => None,
+        };
+        match self.task_status_vec.get(col).and_then(|s| s.get("id")) {
+            Some(status_id) => self
+                .tasks_vec
+                .iter()
+                .filter(|task| task.get("status") == Some(status_id))
+                .filter(|task| lane.is_none() || lane == Some(self.lane_key(task)))
+                .cloned()
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// moves the focus to another swimlane, on the first or last card of the focused column
+    fn focus_lane(&mut self, lane: usize, last: bool) {
+        self.lane_focus = lane;
+        if self.scrum_col_focus > 0 {
+            self.scrum_col_list = self.column_tasks(self.scrum_col_focus as usize - 1);
+            self.scrum_col_count = self.scrum_col_list.len();
+        }
+        self.l_scrum_state.select(Some(match last {
+            true => self.scrum_col_count.saturating_sub(1),
+            false => 0,
+        }));
+    }
+
+    fn focus_column(&mut self, col: usize) {
+        self.scrum_col_focus = col as i32 + 1;
+        self.scrum_col_list = self.column_tasks(col);
+        self.scrum_col_count = self.scrum_col_list.len();
+        self.l_scrum_state.select(Some(0));
+    }
+
+    /// drops the dragged task on the column under the mouse and selects it there
+    fn drop_task(&mut self, task_id: i32, col: usize) {
+        let status_id = match self.task_status_vec.get(col).and_then(|s| s.get("id")) {
+            Some(id) => id.parse::<i32>().unwrap_or(0),
+            None => return,
+        };
+        if !self.update_scrum_task(task_id, status_id) {
+            return;
+        }
+        self.focus_column(col);
+        let position = self
+            .scrum_col_list
+            .iter()
+            .position(|task| task.get("id") == Some(&task_id.to_string()));
+        self.l_scrum_state.select(position.or(Some(0)));
+    }
+
+    pub fn board_mouse_handler(&mut self, input: &MouseInput) -> AppState {
+        match input.kind {
+            MouseKind::Click | MouseKind::DoubleClick => {
+                // a click in another swimlane focuses it and the column under the mouse, the
+                // cards of that lane are only known once it is drawn again
+                match input.hit(&self.lane_areas) {
+                    Some(lane) if lane != self.lane_focus => {
+                        let col = self
+                            .column_areas
+                            .iter()
+                            .find(|(_, area)| {
+                                input.column >= area.x && input.column < area.right()
+                            })
+                            .map(|(col, _)| *col);
+                        self.focus_lane(lane, false);
+                        if let Some(col) = col {
+                            self.focus_column(col);
+                        }
+                        return AppState::MoveOn;
                     }
+                    _ => {}
                 }
-                Err(e) => {}
-            },
-            Err(e) => {}
+                if let Some(idx) = input.hit(&self.project_areas) {
+                    self.record_count = self.projects_vec.len();
+                    self.scrum_col_focus = 0;
+                    self.l_scrum_state.select(None);
+                    self.select_item(idx + 1);
+                    return AppState::MoveOn;
+                }
+                if let Some(col) = input.hit(&self.column_areas) {
+                    self.focus_column(col);
+                    let card = self.card_areas.get(col).and_then(|areas| input.hit(areas));
+                    if let Some(idx) = card {
+                        self.l_scrum_state.select(Some(idx));
+                        self.drag_task = self
+                            .scrum_col_list
+                            .get(idx)
+                            .and_then(|task| task.get("id"))
+                            .and_then(|id| id.parse::<i32>().ok());
+                        if input.kind == MouseKind::DoubleClick {
+                            self.drag_task = None;
+                            self.open_card();
+                        }
+                    }
+                    return AppState::MoveOn;
+                }
+                AppState::Running
+            }
+            MouseKind::Drag => {
+                if self.drag_task.is_some() {
+                    self.drop_col = input.hit(&self.column_areas);
+                }
+                AppState::MoveOn
+            }
+            MouseKind::Release => {
+                let dragged = self.drag_task.take();
+                self.drop_col = None;
+                if let (Some(task_id), Some(col)) = (dragged, input.hit(&self.column_areas)) {
+                    if col as i32 + 1 != self.scrum_col_focus {
+                        self.drop_task(task_id, col);
+                    }
+                }
+                AppState::MoveOn
+            }
+            MouseKind::ScrollUp | MouseKind::ScrollDown => {
+                let up = input.kind == MouseKind::ScrollUp;
+                match input.hit(&self.column_areas) {
+                    Some(col) => {
+                        if col as i32 + 1 != self.scrum_col_focus {
+                            self.focus_column(col);
+                        }
+                        if up {
+                            self.previous_task();
+                        } else {
+                            self.next_task();
+                        }
+                    }
+                    None => {
+                        self.record_count = self.projects_vec.len();
+                        if up {
+                            self.previous_item();
+                        } else {
+                            self.next_item();
+                        }
+                    }
+                }
+                AppState::MoveOn
+            }
+        }
+    }
+
+    /// WIP limit of a status column on the board of the selected project. Limits belong to a
+    /// project, so boards showing several projects have none
+    pub fn wip_limit(&self, status_id: i32) -> Option<usize> {
+        self.single_project()
+            .and_then(|project_id| config::get().wip_limits.limit(project_id, status_id))
+    }
+
+    /// number of tasks of the project in a status, including those the sprint filter hides
+    pub fn status_count(&self, status_id: i32) -> usize {
+        self.status_counts.get(&status_id).copied().unwrap_or(0)
+    }
+
+    /// moves a task to another column, asking first when the column is full. false when the card
+    /// stays where it is for now
+    fn update_scrum_task(&mut self, task_id: i32, task_status: i32) -> bool {
+        let already_there = self.tasks_vec.iter().any(|task| {
+            task.get("id") == Some(&task_id.to_string())
+                && task.get("status") == Some(&task_status.to_string())
+        });
+        if let Some(limit) = self.wip_limit(task_status) {
+            if !already_there && self.status_count(task_status) >= limit {
+                self.pending_move = Some((task_id, task_status));
+                self.popup_type = PopupDashboardType::WipLimit;
+                self.show_popup = true;
+                return false;
+            }
+        }
+        self.move_scrum_task(task_id, task_status)
+    }
+
+    /// saves the task in its new status, the card stays in its column when that fails
+    fn move_scrum_task(&mut self, task_id: i32, task_status: i32) -> bool {
+        let moved = Self::get_db_connection().and_then(|conn| {
+            let mut task = Task::get_by_id(&conn, task_id)?;
+            if task.status != task_status {
+                // the card goes to the bottom of its new column
+                task.position = Task::next_position(&conn, task.project_id, task_status);
+            }
+            task.status = task_status;
+            task.save(&conn)
+        });
+        match moved {
+            Ok(_updated_task) => {
+                self.reload_tasks();
+                true
+            }
+            Err(e) => {
+                //@TODO: show popup error
+                log::error!("failed to move task {}: {}", task_id, e);
+                false
+            }
         }
     }
-    pub fn list_key_event(&mut self, key: &KeyEvent) -> AppState {
-        match key.code {
-            KeyCode::Char('n') => {
-                self.action = CtrlActions::Edit;
-                self.projects = Project::default();
+    /// opens the selected card in place of the board
+    fn open_card(&mut self) {
+        if let Some(id) = self.selected_card_id() {
+            let mut task = TaskCtrl::default();
+            task.open_detail(id);
+            if let CtrlActions::Detail = task.action {
+                self.task_detail = Some(task);
+            }
+        }
+    }
+
+    /// the opened card gets the keys until it goes back to its list, the board is then read
+    /// again as the task may have changed
+    fn task_detail_action_handler(&mut self, action: &Action) -> AppState {
+        let (state, closed) = match self.task_detail.as_mut() {
+            Some(task) => {
+                let state = task.action_handler(action);
+                (state, matches!(task.action, CtrlActions::List))
+            }
+            None => (AppState::Running, false),
+        };
+        if closed {
+            self.task_detail = None;
+            self.reload_tasks();
+        }
+        state
+    }
+
+    /// confirm popup shown before moving a card into a full column
+    fn wip_popup_action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::Confirm => {
+                if let Some((task_id, task_status)) = self.pending_move.take() {
+                    self.move_scrum_task(task_id, task_status);
+                }
+            }
+            Action::Cancel => self.pending_move = None,
+            _ => return AppState::MoveOn,
+        }
+        self.show_popup = false;
+        self.popup_type = PopupDashboardType::NoPopup;
+        AppState::MoveOn
+    }
+
+    pub fn list_action_handler(&mut self, action: &Action) -> AppState {
+        if self.show_popup {
+            return self.wip_popup_action_handler(action);
+        }
+        match *action {
+            Action::New => {
+                // quick add of a task to the focused column
+                if self.single_project().is_some() && !self.task_status_vec.is_empty() {
+                    if self.scrum_col_focus == 0 {
+                        self.focus_column(0);
+                    }
+                    self.input.clear();
+                    self.action = CtrlActions::Edit;
+                }
+                return AppState::MoveOn;
+            }
+
+            Action::Edit => {
+                if let Some(id) = self.selected_card_id() {
+                    let mut task = TaskCtrl::default();
+                    task.open_edit(id);
+                    if let CtrlActions::Edit = task.action {
+                        self.task_detail = Some(task);
+                    }
+                }
+                return AppState::MoveOn;
+            }
+
+            Action::Delete => {
+                if self.selected_card_id().is_some() {
+                    self.action = CtrlActions::Del;
+                }
+                return AppState::MoveOn;
+            }
+
+            Action::MoveUp | Action::MoveDown => {
+                self.move_card(if *action == Action::MoveUp { -1 } else { 1 });
                 return AppState::MoveOn;
             }
 
-            KeyCode::Char('P') => {
+            Action::MovePrev => {
                 if self.scrum_col_focus > 1 {
                     match self.l_scrum_state.selected() {
                         Some(idx) => {
@@ -321,7 +876,7 @@ impl DashboardCtrl {
                 return AppState::MoveOn;
             }
 
-            KeyCode::Char('N') => {
+            Action::MoveNext => {
                 if self.scrum_col_focus > 0 && self.scrum_col_focus < 3 {
                     match self.l_scrum_state.selected() {
                         Some(idx) => {
@@ -345,7 +900,7 @@ impl DashboardCtrl {
                 return AppState::MoveOn;
             }
 
-            KeyCode::Tab => {
+            Action::NextMenu | Action::PrevMenu => {
                 if self.scrum_col_focus == 0 {
                     return AppState::Running;
                 } else {
@@ -353,7 +908,7 @@ impl DashboardCtrl {
                 }
             }
 
-            KeyCode::Left => {
+            Action::Left => {
                 self.scrum_col_focus = match self.scrum_col_focus {
                     0 => 3,
                     _ => self.scrum_col_focus - 1,
@@ -368,7 +923,7 @@ impl DashboardCtrl {
                 return AppState::MoveOn;
             }
 
-            KeyCode::Right => {
+            Action::Right => {
                 self.scrum_col_focus = self.scrum_col_focus + 1;
                 if self.scrum_col_focus > 3 {
                     self.scrum_col_focus = 0;
@@ -379,9 +934,14 @@ impl DashboardCtrl {
                 return AppState::MoveOn;
             }
 
-            KeyCode::Up => {
+            Action::Up => {
                 if self.scrum_col_focus == 0 {
                     self.previous_item();
+                } else if self.lanes_on() && self.l_scrum_state.selected().unwrap_or(0) == 0 {
+                    // the first card goes up to the lane above
+                    if self.lane_focus > 0 {
+                        self.focus_lane(self.lane_focus - 1, true);
+                    }
                 } else {
                     self.previous_task()
                 }
@@ -389,15 +949,111 @@ impl DashboardCtrl {
                 return AppState::MoveOn;
             }
 
-            KeyCode::Down => {
+            Action::Down => {
                 if self.scrum_col_focus == 0 {
                     self.next_item();
+                } else if self.lanes_on()
+                    && self.l_scrum_state.selected().unwrap_or(0) + 1 >= self.scrum_col_count
+                {
+                    // the last card goes down to the lane below
+                    if self.lane_focus + 1 < self.lanes().len() {
+                        self.focus_lane(self.lane_focus + 1, false);
+                    }
                 } else {
                     self.next_task();
                 }
                 return AppState::MoveOn;
             }
 
+            Action::First | Action::Last => {
+                let last = matches!(action, Action::Last);
+                if self.scrum_col_focus == 0 {
+                    self.select_item(if last { self.record_count } else { 1 });
+                } else if self.scrum_col_count > 0 {
+                    self.l_scrum_state.select(Some(if last {
+                        self.scrum_col_count - 1
+                    } else {
+                        0
+                    }));
+                }
+                return AppState::MoveOn;
+            }
+
+            Action::Confirm => {
+                self.open_card();
+                return AppState::MoveOn;
+            }
+
+            Action::Chart => {
+                if self.projects.id > 0 && self.picked_projects.is_empty() {
+                    self.open_chart();
+                }
+                return AppState::MoveOn;
+            }
+
+            Action::Swimlanes => {
+                self.lanes_by = self.lanes_by.next();
+                self.collapsed_lanes = vec![];
+                self.focus_lane(0, false);
+                if self.scrum_col_focus == 0 {
+                    self.l_scrum_state.select(None);
+                }
+                return AppState::MoveOn;
+            }
+
+            Action::ToggleCheck if self.scrum_col_focus == 0 => {
+                self.toggle_picked_project();
+                return AppState::MoveOn;
+            }
+
+            Action::ToggleCheck => {
+                // folds the focused swimlane to its header, or unfolds it
+                if let Some((key, _)) = self.lanes().get(self.lane_focus).cloned() {
+                    match self.is_collapsed(&key) {
+                        true => self.collapsed_lanes.retain(|k| *k != key),
+                        false => self.collapsed_lanes.push(key),
+                    }
+                    let focused = self.scrum_col_focus > 0;
+                    self.focus_lane(self.lane_focus, false);
+                    if !focused {
+                        self.l_scrum_state.select(None);
+                    }
+                }
+                return AppState::MoveOn;
+            }
+
+            Action::ToggleSprint => {
+                self.whole_project = !self.whole_project;
+                self.reload_tasks();
+                return AppState::MoveOn;
+            }
+
+            Action::JumpTo(project_id) => {
+                let position = self
+                    .projects_vec
+                    .iter()
+                    .position(|p| p.get("id") == Some(&project_id.to_string()));
+                if let Some(idx) = position {
+                    self.record_count = self.projects_vec.len();
+                    self.scrum_col_focus = 0;
+                    self.l_scrum_state.select(None);
+                    self.select_item(idx + 1);
+                }
+                return AppState::MoveOn;
+            }
+
+            Action::SetStatus(status_id) => {
+                if self.scrum_col_focus > 0 {
+                    if let Some(idx) = self.l_scrum_state.selected() {
+                        if let Some(task) = self.scrum_col_list.get(idx) {
+                            let task_id = task.get("id").unwrap().parse::<i32>().unwrap();
+                            self.update_scrum_task(task_id, status_id);
+                        }
+                    }
+                }
+                return AppState::MoveOn;
+            }
+
             _ => {
                 //println!("key pressed '{:?}'", key.code);
             }
@@ -407,19 +1063,18 @@ impl DashboardCtrl {
     }
 
     pub fn go_back(&mut self) {
-        self.input = "".to_string();
-        self.field_idx = 0;
+        self.input.clear();
         self.action = CtrlActions::List;
     }
 
-    pub fn del_key_event(&mut self, key: &KeyEvent) -> AppState {
-        match key.code {
-            KeyCode::Esc => {
+    pub fn del_action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::Cancel => {
                 self.go_back();
             }
 
-            KeyCode::Enter => {
-                //self.del_task();
+            Action::Confirm => {
+                self.del_task();
             }
 
             _ => {
@@ -429,137 +1084,303 @@ impl DashboardCtrl {
 
         AppState::MoveOn
     }
-    pub fn detail_key_event(&mut self, key: &KeyEvent) -> AppState {
-        match key.code {
-            KeyCode::Esc => {
-                self.go_back();
-            }
+    /// shows the chart of the active sprint, or of the whole project when there is none
+    fn open_chart(&mut self) {
+        let sprints = match Self::get_db_connection() {
+            Ok(conn) => Sprint::for_project(&conn, self.projects.id).unwrap_or_default(),
+            Err(_e) => vec![],
+        };
+        self.chart_scopes = vec![None];
+        self.chart_scopes.extend(sprints.into_iter().map(Some));
+        let active = self.sprint.as_ref().map(|s| s.id);
+        self.chart_scope = self
+            .chart_scopes
+            .iter()
+            .position(|s| s.as_ref().map(|s| s.id) == active && !self.whole_project)
+            .unwrap_or(0);
+        self.chart_msg = "".to_string();
+        self.load_chart(ChartKind::Burndown);
+        self.action = CtrlActions::Detail;
+    }
 
-            KeyCode::Enter => {
-                self.action = CtrlActions::Edit;
+    fn load_chart(&mut self, kind: ChartKind) {
+        let sprint = self.chart_scopes.get(self.chart_scope).cloned().flatten();
+        match Self::get_db_connection() {
+            Ok(conn) => match BurnChart::build(&conn, &self.projects, sprint.as_ref(), None) {
+                Ok(mut chart) => {
+                    chart.kind = kind;
+                    self.chart = Some(chart);
+                }
+                Err(e) => {
+                    //@TODO: show popup error
+                    log::error!("failed to build chart: {}", e);
+                }
+            },
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                //@TODO: show popup error
             }
+        }
+    }
 
-            _ => {
-                //@NOTE we do nothing!!!!
+    /// writes the chart as text and svg in the current directory
+    fn export_chart(&mut self) {
+        if let Some(chart) = &self.chart {
+            let name = format!(
+                "{}-{}-{}",
+                chart.kind.name(),
+                chart
+                    .title
+                    .chars()
+                    .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                    .collect::<String>(),
+                chart.end
+            );
+            let txt = format!("{}.txt", name);
+            let svg = format!("{}.svg", name);
+            match std::fs::write(&txt, chart.to_ascii())
+                .and_then(|_| std::fs::write(&svg, chart.to_svg()))
+            {
+                Ok(()) => {
+                    log::info!("chart exported to {} and {}", txt, svg);
+                    self.chart_msg = format!("saved {} and {}", txt, svg);
+                }
+                Err(e) => {
+                    log::error!("failed to export chart: {}", e);
+                    self.chart_msg = format!("export failed: {}", e);
+                }
             }
         }
-
-        AppState::MoveOn
     }
 
-    pub fn popup_key_event(&mut self, key: &KeyEvent) -> AppState {
-        match key.code {
-            KeyCode::Up => {
-                self.previous_item();
-                return AppState::MoveOn;
+    pub fn detail_action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::Cancel => {
+                self.chart = None;
+                self.go_back();
             }
 
-            KeyCode::Down => {
-                self.next_item();
-                return AppState::MoveOn;
+            Action::ToggleCheck => {
+                if let Some(kind) = self.chart.as_ref().map(|c| c.kind.toggle()) {
+                    self.load_chart(kind);
+                }
             }
 
-            KeyCode::Esc => {
-                self.show_popup = false;
-                return AppState::MoveOn;
+            Action::Up | Action::Down => {
+                let count = self.chart_scopes.len().max(1);
+                self.chart_scope = match *action {
+                    Action::Up => (self.chart_scope + count - 1) % count,
+                    _ => (self.chart_scope + 1) % count,
+                };
+                let kind = self.chart.as_ref().map(|c| c.kind).unwrap_or(ChartKind::Burndown);
+                self.load_chart(kind);
             }
 
-            KeyCode::Enter => {
-                match self.l_state.selected() {
-                    Some(idx) => match self.project_list() {
-                        Ok(results) => {
-                            let project_id =
-                                results[idx].get("id").unwrap().parse::<i32>().unwrap();
-                            self.projects.id = project_id;
-                            self.show_popup = false;
-                            self.popup_type = PopupDashboardType::NoPopup;
-                        }
-                        Err(e) => {}
-                    },
-                    None => {}
-                }
-                return AppState::MoveOn;
+            Action::Export => {
+                self.export_chart();
             }
 
             _ => {
-                return AppState::MoveOn;
+                //@NOTE we do nothing!!!!
             }
         }
+
+        AppState::MoveOn
     }
-    pub fn edit_key_event(&mut self, key: &KeyEvent) -> AppState {
-        if self.show_popup {
-            return self.popup_key_event(key);
-        }
-        match key.code {
-            KeyCode::Tab => {
-                self.set_next_active();
-            }
 
-            KeyCode::Esc => {
+    /// typing the name of a task added to the focused column
+    pub fn edit_action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::Cancel => {
                 self.go_back();
-                //@TODO: reset all fields when to empty if we were creating a new task or to the
-                //fields original values of the selected task
             }
 
-            KeyCode::Backspace => {
-                self.input.pop();
-                self.update_field();
+            Action::Confirm => {
+                self.save_task();
             }
 
-            KeyCode::Enter => {
-                //self.save_task();
+            Action::Insert(c) => {
+                self.input.insert(c);
             }
 
-            KeyCode::Char(c) => {
-                match self.field_idx {
-                    0 => {
-                        // show popup with project lists to select from and set project_id to the
-                        // project.id from selected project
-                        if c == ' ' {
-                            match self.project_list() {
-                                Ok(results) => self.record_count = results.len(),
-                                Err(_e) => self.record_count = 0,
-                            }
-                            self.popup_type = PopupDashboardType::ProjectList;
-                            self.show_popup = true;
-                        }
+            action => {
+                self.input.handle_action(&action);
+            }
+        }
+
+        AppState::MoveOn
+    }
+
+    /// status of the focused column, the first one while the project list has the focus
+    fn focused_status(&self) -> Option<i32> {
+        let col = (self.scrum_col_focus - 1).max(0) as usize;
+        self.task_status_vec
+            .get(col)
+            .and_then(|s| s.get("id"))
+            .and_then(|id| id.parse::<i32>().ok())
+    }
+
+    /// id of the selected card
+    fn selected_card_id(&self) -> Option<i32> {
+        if self.scrum_col_focus == 0 {
+            return None;
+        }
+        self.l_scrum_state
+            .selected()
+            .and_then(|idx| self.scrum_col_list.get(idx))
+            .and_then(|task| task.get("id"))
+            .and_then(|id| id.parse::<i32>().ok())
+    }
+
+    /// adds the typed task at the bottom of the focused column, in the active sprint and in the
+    /// focused swimlane
+    pub fn save_task(&mut self) {
+        let name = self.input.value().trim().to_string();
+        let (status, project_id) =
+            match (name.is_empty(), self.focused_status(), self.single_project()) {
+                (false, Some(status), Some(project_id)) => (status, project_id),
+                _ => return self.go_back(),
+            };
+        let conn = match Self::get_db_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                //@TODO: show popup error
+                return;
+            }
+        };
+        let lane = self
+            .lanes()
+            .get(self.lane_focus)
+            .map(|(key, _)| key.clone())
+            .unwrap_or_default();
+        let lane_id = lane.parse::<i32>().ok();
+        let mut task = Task {
+            project_id,
+            name,
+            status,
+            position: Task::next_position(&conn, project_id, status),
+            priority: config::get().defaults.priority,
+            ..Task::default()
+        };
+        if let (Some(sprint), false) = (&self.sprint, self.whole_project) {
+            task.sprint_id = sprint.id;
+        }
+        match (self.lanes_by, lane_id) {
+            (SwimlaneGrouping::Parent, Some(parent_id)) => task.parent_id = parent_id,
+            (SwimlaneGrouping::Priority, Some(priority)) => task.priority = priority,
+            _ => {}
+        }
+        match task.save(&conn) {
+            Ok(task) => {
+                if let (SwimlaneGrouping::Assignee, Some(person_id)) = (self.lanes_by, lane_id) {
+                    if let Err(e) = TaskAssignee::set_for_task(&conn, task.id, &[person_id]) {
+                        //@TODO: show popup error
+                        log::error!("failed to save assignees: {}", e);
                     }
-                    _ => {
-                        self.input.push(c);
-                        self.update_field();
+                }
+                if self.lanes_by == SwimlaneGrouping::Tag && !lane.is_empty() {
+                    if let Err(e) = TaskTag::set_for_task(&conn, task.id, &[lane]) {
+                        //@TODO: show popup error
+                        log::error!("failed to save tags: {}", e);
                     }
                 }
+                self.go_back();
+                self.reload_tasks();
+                self.select_card(task.id);
             }
-
-            _ => {
-                //println!("key pressed '{:?}'", key.code);
+            Err(e) => {
+                //@TODO: show popup error
+                log::error!("failed to save record: {}", e);
             }
         }
+    }
 
-        AppState::MoveOn
+    /// focuses the card of task `task_id` in its column
+    fn select_card(&mut self, task_id: i32) {
+        let status = self
+            .tasks_vec
+            .iter()
+            .find(|task| task.get("id") == Some(&task_id.to_string()))
+            .and_then(|task| task.get("status").cloned());
+        let col = self
+            .task_status_vec
+            .iter()
+            .position(|s| s.get("id") == status.as_ref());
+        if let Some(col) = col {
+            self.focus_column(col);
+            let position = self
+                .scrum_col_list
+                .iter()
+                .position(|task| task.get("id") == Some(&task_id.to_string()));
+            self.l_scrum_state.select(position.or(Some(0)));
+        }
     }
 
-    pub fn set_next_active(&mut self) {
-        match self.action {
-            CtrlActions::Edit => {
-                self.field_idx = self.field_idx + 1;
-                if self.field_idx == 5 {
-                    self.field_idx = 0;
-                }
-                self.input = match self.field_idx {
-                    //0 => self.projects.project_id.to_string(),
-                    //1 => self.projects.parent_id.clone(),
-                    0 => "".to_string(),
-                    1 => "".to_string(),
-                    2 => self.projects.name.clone(),
-                    3 => self.projects.description.clone(),
-                    4 => "".to_string(),
-                    //4 => self.projects.status.clone(),
-                    _ => "".to_string(),
-                }
+    /// deletes the selected card with its checklist, comments, assignees and dependencies
+    pub fn del_task(&mut self) {
+        let id = match self.selected_card_id() {
+            Some(id) => id,
+            None => return self.go_back(),
+        };
+        match Self::get_db_connection().and_then(|conn| Task::get_by_id(&conn, id)) {
+            Ok(task) => {
+                let mut task_ctrl = TaskCtrl {
+                    task_table: task,
+                    ..TaskCtrl::default()
+                };
+                task_ctrl.del_task();
             }
+            Err(e) => {
+                //@TODO: show popup error
+                log::error!("failed to read task {}: {}", id, e);
+            }
+        }
+        self.go_back();
+        self.reload_tasks();
+        let count = self.column_tasks((self.scrum_col_focus - 1).max(0) as usize).len();
+        let idx = self.l_scrum_state.selected().unwrap_or(0);
+        self.l_scrum_state.select(Some(idx.min(count.saturating_sub(1))));
+    }
 
-            _ => {}
+    /// moves the selected card up or down its column, the other cards of the column keep their
+    /// order
+    fn move_card(&mut self, step: isize) {
+        let idx = match self.l_scrum_state.selected() {
+            Some(idx) if self.scrum_col_focus > 0 => idx,
+            _ => return,
+        };
+        let target = idx as isize + step;
+        if target < 0 || target as usize >= self.scrum_col_list.len() {
+            return;
+        }
+        let card_id = |task: &HashMap<String, String>| {
+            task.get("id").and_then(|id| id.parse::<i32>().ok()).unwrap_or(0)
+        };
+        let moved = card_id(&self.scrum_col_list[idx]);
+        let other = card_id(&self.scrum_col_list[target as usize]);
+        let status = self.scrum_col_list[idx].get("status").cloned();
+        // with swimlanes the neighbour may not be next to the card in the whole column
+        let mut ids: Vec<i32> = self
+            .tasks_vec
+            .iter()
+            .filter(|task| task.get("status") == status.as_ref())
+            .map(card_id)
+            .filter(|id| *id != moved)
+            .collect();
+        let at = ids.iter().position(|id| *id == other).unwrap_or(0);
+        ids.insert(if step < 0 { at } else { at + 1 }, moved);
+        match Self::get_db_connection().and_then(|conn| Task::set_positions(&conn, &ids)) {
+            Ok(()) => {
+                self.reload_tasks();
+                self.scrum_col_list = self.column_tasks(self.scrum_col_focus as usize - 1);
+                self.scrum_col_count = self.scrum_col_list.len();
+                self.l_scrum_state.select(Some(target as usize));
+            }
+            Err(e) => {
+                //@TODO: show popup error
+                log::error!("failed to reorder the cards: {}", e);
+            }
         }
     }
 }
@@ -1,35 +1,48 @@
 use super::{ControllerRegistry, CtrObj};
 use crate::app::AppState;
-use crate::views::footer::{dashboard_footer_ui, footer_ui};
-use crossterm::event::KeyEvent;
+use crate::keymap::{Action, KeyContext, Keymap};
+use crate::views::footer::footer_ui;
+use crate::views::theme::Theme;
 use ratatui::{layout::Rect, Frame};
 use std::io::Error;
 
+/// global actions worth repeating in the footer of every screen
+const FOOTER_GLOBAL_ACTIONS: [Action; 4] = [
+    Action::Help,
+    Action::Palette,
+    Action::NextMenu,
+    Action::Quit,
+];
+
 #[derive(Debug, Clone)]
 pub struct FooterCtrl {
     pub active_item: ControllerRegistry,
+    /// (keys, description) pairs shown in the footer
+    pub hints: Vec<(String, String)>,
 }
 
 impl Default for FooterCtrl {
     fn default() -> FooterCtrl {
         FooterCtrl {
             active_item: ControllerRegistry::Dashboard,
+            hints: vec![],
         }
     }
 }
 
 impl CtrObj for FooterCtrl {
     fn init_data(&mut self) {}
-    fn display(&mut self, f: &mut Frame, area: Rect) -> Result<(), Error> {
-        match self.active_item {
-            ControllerRegistry::Dashboard => dashboard_footer_ui(self, f, area),
-            _ => footer_ui(self, f, area),
-        }
+    fn display(&mut self, f: &mut Frame, area: Rect, theme: &Theme) -> Result<(), Error> {
+        footer_ui(self, f, area, theme);
 
         Ok(())
     }
 
-    fn key_event_handler(&mut self, _key: &KeyEvent) -> AppState {
+    fn key_context(&self) -> KeyContext {
+        KeyContext::Global
+    }
+
+    fn action_handler(&mut self, _action: &Action) -> AppState {
         //@NOTE footer ctrl is only used to disply footer content
         AppState::Running
     }
@@ -39,4 +52,29 @@ impl FooterCtrl {
     pub fn set_active_item(&mut self, item: ControllerRegistry) {
         self.active_item = item;
     }
+
+    /// rebuilds the footer hints from the bindings of the active context
+    pub fn set_hints(&mut self, keymap: &Keymap, ctx: KeyContext) {
+        let context_actions: Vec<Action> = keymap
+            .bindings
+            .get(&ctx)
+            .map(|binds| binds.iter().map(|(_, action)| *action).collect())
+            .unwrap_or_default();
+        let mut actions: Vec<Action> = vec![Action::Help];
+        context_actions
+            .iter()
+            .chain(FOOTER_GLOBAL_ACTIONS.iter())
+            .for_each(|action| {
+                if !actions.contains(action) {
+                    actions.push(*action);
+                }
+            });
+
+        self.hints = actions
+            .iter()
+            .map(|action| (keymap.keys_for(ctx, *action).join("|"), action))
+            .filter(|(keys, _)| !keys.is_empty())
+            .map(|(keys, action)| (keys, action.description().to_string()))
+            .collect();
+    }
 }
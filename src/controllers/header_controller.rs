@@ -1,14 +1,17 @@
 use super::{get_controller_from_registry, ControllerRegistry, CtrObj};
 use crate::app::AppState;
+use crate::config;
+use crate::keymap::{Action, KeyContext};
+use crate::mouse::{MouseInput, MouseKind};
 use crate::views::header::header_ui;
-use crossterm::event::{KeyCode, KeyEvent};
+use crate::views::theme::Theme;
 use ratatui::{layout::Rect, Frame};
 use std::io::Error;
 
 #[derive(Debug, Clone)]
 pub struct MenuItem {
     pub name: String,
-    ctrl: ControllerRegistry,
+    pub ctrl: ControllerRegistry,
 }
 
 #[derive(Debug, Clone)]
@@ -17,57 +20,101 @@ pub struct HeaderCtrl {
     pub title: String,
     pub active_item: usize,
     pub item_changed: bool,
+    /// screen area of each tab, set while rendering
+    pub tab_areas: Vec<(usize, Rect)>,
 }
 
 impl Default for HeaderCtrl {
     fn default() -> HeaderCtrl {
+        let cfg = config::get();
+        let menu: Vec<MenuItem> = cfg
+            .tabs
+            .iter()
+            .filter_map(|tab| MenuItem::from_tab_name(tab))
+            .collect();
+        let active_item = cfg
+            .tabs
+            .iter()
+            .position(|tab| *tab == cfg.start_tab)
+            .unwrap_or(0);
+
         HeaderCtrl {
-            menu: vec![
-                MenuItem {
-                    name: String::from("Dashboard"),
-                    ctrl: ControllerRegistry::Dashboard,
-                },
-                MenuItem {
-                    name: String::from("Tasks"),
-                    ctrl: ControllerRegistry::Task,
-                },
-                MenuItem {
-                    name: String::from("Projects"),
-                    ctrl: ControllerRegistry::Project,
-                },
-                //                MenuItem {
-                //                   name: String::from("Contacts"),
-                //                    ctrl: ControllerRegistry::Contact,
-                //                },
-            ],
+            menu,
             title: String::from("Rask, your task list manager"),
-            active_item: 0,
+            active_item,
             item_changed: false,
+            tab_areas: vec![],
         }
     }
 }
 
+impl MenuItem {
+    /// builds the menu entry for a tab name used in the config file
+    pub fn from_tab_name(tab: &str) -> Option<MenuItem> {
+        let (name, ctrl) = match tab {
+            "dashboard" => ("Dashboard", ControllerRegistry::Dashboard),
+            "next" => ("Next", ControllerRegistry::Next),
+            "tasks" => ("Tasks", ControllerRegistry::Task),
+            "projects" => ("Projects", ControllerRegistry::Project),
+            "sprints" => ("Sprints", ControllerRegistry::Sprint),
+            "calendar" => ("Calendar", ControllerRegistry::Calendar),
+            "timeline" => ("Timeline", ControllerRegistry::Timeline),
+            "metrics" => ("Metrics", ControllerRegistry::Metrics),
+            "contacts" => ("Contacts", ControllerRegistry::Contact),
+            _ => return None,
+        };
+        Some(MenuItem {
+            name: String::from(name),
+            ctrl,
+        })
+    }
+}
+
 impl CtrObj for HeaderCtrl {
     fn init_data(&mut self) {}
-    fn display(&mut self, f: &mut Frame, area: Rect) -> Result<(), Error> {
-        header_ui(self, f, area);
+    fn display(&mut self, f: &mut Frame, area: Rect, theme: &Theme) -> Result<(), Error> {
+        header_ui(self, f, area, theme);
         Ok(())
     }
 
-    fn key_event_handler(&mut self, key: &KeyEvent) -> AppState {
-        match key.code {
-            KeyCode::Char('q') => return AppState::Closing,
-            KeyCode::Tab => {
+    fn key_context(&self) -> KeyContext {
+        KeyContext::Global
+    }
+
+    fn action_handler(&mut self, action: &Action) -> AppState {
+        match action {
+            Action::Quit => return AppState::Closing,
+            Action::NextMenu => {
                 self.set_next_active();
                 return AppState::Running;
             }
+            Action::PrevMenu => {
+                self.set_prev_active();
+                return AppState::Running;
+            }
             _ => {
-                println!("key pressed '{:?}'", key.code);
+                log::trace!("unhandled action {:?}", action);
             }
         }
 
         AppState::Running
     }
+
+    fn mouse_handler(&mut self, input: &MouseInput) -> AppState {
+        if !matches!(input.kind, MouseKind::Click | MouseKind::DoubleClick) {
+            return AppState::Running;
+        }
+        match input.hit(&self.tab_areas) {
+            Some(idx) => {
+                if idx != self.active_item {
+                    self.active_item = idx;
+                    self.item_changed = true;
+                }
+                AppState::MoveOn
+            }
+            None => AppState::Running,
+        }
+    }
 }
 
 impl HeaderCtrl {
@@ -81,6 +128,28 @@ impl HeaderCtrl {
         self.item_changed = true;
     }
 
+    pub fn set_prev_active(&mut self) {
+        self.active_item = match self.active_item {
+            0 => self.menu.len() - 1,
+            _ => self.active_item - 1,
+        };
+        self.item_changed = true;
+    }
+
+    /// activates the menu item of `ctrl`, false when it isn't in the menu
+    pub fn select_type(&mut self, ctrl: ControllerRegistry) -> bool {
+        match self.menu.iter().position(|item| item.ctrl == ctrl) {
+            Some(idx) => {
+                if idx != self.active_item {
+                    self.active_item = idx;
+                    self.item_changed = true;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn controller_has_changed(&mut self) -> bool {
         self.item_changed
     }
@@ -0,0 +1,99 @@
+use super::CtrObj;
+use crate::app::AppState;
+use crate::keymap::{Action, KeyContext};
+use crate::models::{project::Project, DbObj};
+use crate::stats::{FlowStats, STATS_PERIODS};
+use crate::views::metrics::*;
+use crate::views::theme::Theme;
+use crate::UtilFns;
+use ratatui::{layout::Rect, Frame};
+use std::io::Error;
+
+/// flow metrics of a project or of all projects over one of `STATS_PERIODS`
+#[derive(Debug, Clone)]
+pub struct MetricsCtrl {
+    /// None first for all projects
+    pub projects: Vec<Option<Project>>,
+    pub project_idx: usize,
+    pub period_idx: usize,
+    pub stats: Option<FlowStats>,
+    /// projects and metrics are read once, when the tab opens
+    pub loaded: bool,
+}
+
+impl Default for MetricsCtrl {
+    fn default() -> MetricsCtrl {
+        MetricsCtrl {
+            projects: vec![None],
+            project_idx: 0,
+            period_idx: 1,
+            stats: None,
+            loaded: false,
+        }
+    }
+}
+
+impl UtilFns for MetricsCtrl {}
+
+impl CtrObj for MetricsCtrl {
+    fn init_data(&mut self) {
+        if !self.loaded {
+            self.loaded = true;
+            if let Ok(conn) = Self::get_db_connection() {
+                self.projects = vec![None];
+                self.projects.extend(
+                    Project::list(&conn, "id > 0 ORDER BY name".to_string())
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(Some),
+                );
+            }
+            self.load_stats();
+        }
+    }
+
+    fn display(&mut self, f: &mut Frame, area: Rect, theme: &Theme) -> Result<(), Error> {
+        metrics_view(self, f, area, theme);
+        Ok(())
+    }
+
+    fn key_context(&self) -> KeyContext {
+        KeyContext::Board
+    }
+
+    fn action_handler(&mut self, action: &Action) -> AppState {
+        let count = self.projects.len().max(1);
+        match *action {
+            Action::Up => self.project_idx = (self.project_idx + count - 1) % count,
+            Action::Down => self.project_idx = (self.project_idx + 1) % count,
+            Action::Left => self.period_idx = self.period_idx.saturating_sub(1),
+            Action::Right => self.period_idx = (self.period_idx + 1).min(STATS_PERIODS.len() - 1),
+            _ => return AppState::Running,
+        }
+        self.load_stats();
+        AppState::MoveOn
+    }
+}
+
+impl MetricsCtrl {
+    pub fn period(&self) -> i64 {
+        STATS_PERIODS[self.period_idx]
+    }
+
+    pub fn load_stats(&mut self) {
+        let project = self.projects.get(self.project_idx).cloned().flatten();
+        match Self::get_db_connection() {
+            Ok(conn) => match FlowStats::compute(&conn, project.as_ref(), self.period()) {
+                Ok(stats) => self.stats = Some(stats),
+                Err(e) => {
+                    //@TODO: show popup error
+                    log::error!("failed to compute metrics: {}", e);
+                }
+            },
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                //@TODO: show popup error
+            }
+        }
+    }
+}
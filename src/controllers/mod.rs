@@ -1,5 +1,7 @@
 use crate::app::AppState;
-use crossterm::event::KeyEvent;
+use crate::keymap::{Action, KeyContext};
+use crate::mouse::MouseInput;
+use crate::views::theme::Theme;
 use ratatui::{layout::Rect, Frame};
 use std::io::Error;
 
@@ -7,15 +9,37 @@ pub mod contact_controller;
 pub mod dashboard_controller;
 pub mod footer_controller;
 pub mod header_controller;
+pub mod metrics_controller;
+pub mod next_controller;
+pub mod calendar_controller;
+pub mod timeline_controller;
+pub mod palette_controller;
 pub mod project_controller;
+pub mod sprint_controller;
 pub mod task_controller;
 
 pub trait CtrObj: std::fmt::Debug {
-    fn display(&mut self, f: &mut Frame, area: Rect) -> Result<(), Error>;
+    fn display(&mut self, f: &mut Frame, area: Rect, theme: &Theme) -> Result<(), Error>;
 
-    fn key_event_handler(&mut self, key: &KeyEvent) -> AppState;
+    /// keymap context matching what the controller is currently showing
+    fn key_context(&self) -> KeyContext;
+
+    fn action_handler(&mut self, action: &Action) -> AppState;
+
+    /// reacts to clicks using the areas stored while rendering, MoveOn when the input was used
+    fn mouse_handler(&mut self, _input: &MouseInput) -> AppState {
+        AppState::Running
+    }
 
     fn init_data(&mut self);
+
+    /// text of the focused multi-line field, it can then be edited in $EDITOR
+    fn editor_text(&self) -> Option<String> {
+        None
+    }
+
+    /// stores the text coming back from $EDITOR in the focused field
+    fn set_editor_text(&mut self, _text: &str) {}
 }
 
 #[derive(Debug, Clone)]
@@ -26,21 +50,29 @@ pub enum CtrlActions {
     Detail,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ControllerRegistry {
     Project,
     Contact,
     Task,
     Dashboard,
+    Sprint,
+    Metrics,
+    Calendar,
+    Timeline,
+    Next,
 }
 
 pub fn get_controller_from_registry(reference: ControllerRegistry) -> Box<dyn CtrObj> {
     match reference {
-        ControllerRegistry::Dashboard => {
-            return Box::new(dashboard_controller::DashboardCtrl::default())
-        }
-        ControllerRegistry::Task => return Box::new(task_controller::TaskCtrl::default()),
-        ControllerRegistry::Project => return Box::new(project_controller::ProjectCtrl::default()),
-        ControllerRegistry::Contact => return Box::new(contact_controller::ContactCtrl::default()),
+        ControllerRegistry::Dashboard => Box::new(dashboard_controller::DashboardCtrl::default()),
+        ControllerRegistry::Task => Box::new(task_controller::TaskCtrl::default()),
+        ControllerRegistry::Project => Box::new(project_controller::ProjectCtrl::default()),
+        ControllerRegistry::Contact => Box::new(contact_controller::ContactCtrl::default()),
+        ControllerRegistry::Sprint => Box::new(sprint_controller::SprintCtrl::default()),
+        ControllerRegistry::Metrics => Box::new(metrics_controller::MetricsCtrl::default()),
+        ControllerRegistry::Calendar => Box::new(calendar_controller::CalendarCtrl::default()),
+        ControllerRegistry::Next => Box::new(next_controller::NextCtrl::default()),
+        ControllerRegistry::Timeline => Box::new(timeline_controller::TimelineCtrl::default()),
     }
 }
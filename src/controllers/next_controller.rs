@@ -0,0 +1,163 @@
+use super::task_controller::TaskCtrl;
+use super::{CtrObj, CtrlActions};
+use crate::app::AppState;
+use crate::config;
+use crate::keymap::{Action, KeyContext};
+use crate::mouse::{MouseInput, MouseKind};
+use crate::urgency::{next_tasks, NextTask};
+use crate::views::next::*;
+use crate::views::theme::Theme;
+use crate::UtilFns;
+use ratatui::{layout::Rect, widgets::TableState, Frame};
+use std::io::Error;
+
+/// the most urgent tasks of every project, see `urgency::next_tasks`
+#[derive(Debug, Clone, Default)]
+pub struct NextCtrl {
+    pub tasks: Vec<NextTask>,
+    pub t_state: TableState,
+    /// screen areas of the visible rows, set while rendering
+    pub row_areas: Vec<(usize, Rect)>,
+    /// task opened in place of the list, shown with the detail view of the Tasks tab
+    pub task_detail: Option<TaskCtrl>,
+    /// tasks are read when the tab opens and when an opened task is closed
+    pub loaded: bool,
+}
+
+impl UtilFns for NextCtrl {}
+
+impl CtrObj for NextCtrl {
+    fn init_data(&mut self) {
+        if !self.loaded {
+            self.loaded = true;
+            self.load_tasks();
+        }
+    }
+
+    fn display(&mut self, f: &mut Frame, area: Rect, theme: &Theme) -> Result<(), Error> {
+        match self.task_detail.as_mut() {
+            Some(task) => task.display(f, area, theme)?,
+            None => next_view(self, f, area, theme),
+        }
+        Ok(())
+    }
+
+    fn key_context(&self) -> KeyContext {
+        match &self.task_detail {
+            Some(task) => task.key_context(),
+            None => KeyContext::List,
+        }
+    }
+
+    fn action_handler(&mut self, action: &Action) -> AppState {
+        if self.task_detail.is_some() {
+            return self.task_detail_action_handler(action);
+        }
+        let count = self.tasks.len();
+        match *action {
+            Action::Up if count > 0 => {
+                let idx = self.t_state.selected().unwrap_or(0);
+                self.t_state.select(Some((idx + count - 1) % count));
+            }
+            Action::Down if count > 0 => {
+                let idx = self.t_state.selected().map_or(0, |idx| (idx + 1) % count);
+                self.t_state.select(Some(idx));
+            }
+            Action::First if count > 0 => self.t_state.select(Some(0)),
+            Action::Last if count > 0 => self.t_state.select(Some(count - 1)),
+            Action::Show => self.open_task(false),
+            Action::Edit => self.open_task(true),
+            _ => return AppState::Running,
+        }
+        AppState::MoveOn
+    }
+
+    fn mouse_handler(&mut self, input: &MouseInput) -> AppState {
+        if let Some(task) = self.task_detail.as_mut() {
+            return task.mouse_handler(input);
+        }
+        match input.kind {
+            MouseKind::ScrollUp => self.action_handler(&Action::Up),
+            MouseKind::ScrollDown => self.action_handler(&Action::Down),
+            MouseKind::Click | MouseKind::DoubleClick => match input.hit(&self.row_areas) {
+                Some(idx) => {
+                    self.t_state.select(Some(idx));
+                    if input.kind == MouseKind::DoubleClick {
+                        return self.action_handler(&Action::Show);
+                    }
+                    AppState::MoveOn
+                }
+                None => AppState::Running,
+            },
+            _ => AppState::Running,
+        }
+    }
+
+    fn editor_text(&self) -> Option<String> {
+        self.task_detail
+            .as_ref()
+            .and_then(|task| task.editor_text())
+    }
+
+    fn set_editor_text(&mut self, text: &str) {
+        if let Some(task) = self.task_detail.as_mut() {
+            task.set_editor_text(text);
+        }
+    }
+}
+
+impl NextCtrl {
+    pub fn load_tasks(&mut self) {
+        match Self::get_db_connection() {
+            Ok(conn) => match next_tasks(&conn, config::get().urgency.next_count) {
+                Ok(tasks) => self.tasks = tasks,
+                Err(e) => {
+                    //@TODO: show popup error
+                    log::error!("failed to read the next tasks: {}", e);
+                }
+            },
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                //@TODO: show popup error
+            }
+        }
+        let selected = match self.tasks.len() {
+            0 => None,
+            count => Some(self.t_state.selected().unwrap_or(0).min(count - 1)),
+        };
+        self.t_state.select(selected);
+    }
+
+    /// shows the selected task, or its form when `edit`
+    fn open_task(&mut self, edit: bool) {
+        let id = match self.t_state.selected().and_then(|idx| self.tasks.get(idx)) {
+            Some(task) => task.id,
+            None => return,
+        };
+        let mut task = TaskCtrl::default();
+        match edit {
+            true => task.open_edit(id),
+            false => task.open_detail(id),
+        }
+        if !matches!(task.action, CtrlActions::List) {
+            self.task_detail = Some(task);
+        }
+    }
+
+    /// the opened task gets the keys until it goes back to its list, the tasks are then read
+    /// again as their urgency may have changed
+    fn task_detail_action_handler(&mut self, action: &Action) -> AppState {
+        let (state, closed) = match self.task_detail.as_mut() {
+            Some(task) => {
+                let state = task.action_handler(action);
+                (state, matches!(task.action, CtrlActions::List))
+            }
+            None => (AppState::Running, false),
+        };
+        if closed {
+            self.task_detail = None;
+            self.load_tasks();
+        }
+        state
+    }
+}
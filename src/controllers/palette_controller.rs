@@ -0,0 +1,271 @@
+use super::header_controller::MenuItem;
+use super::{ControllerRegistry, CtrObj};
+use crate::app::AppState;
+use crate::keymap::{Action, KeyContext, Keymap, ACTIONS};
+use crate::models::{task::Task, DbObj};
+use crate::mouse::{MouseInput, MouseKind};
+use crate::views::input::TextInput;
+use crate::views::palette::palette_ui;
+use crate::views::theme::Theme;
+use crate::UtilFns;
+use ratatui::{layout::Rect, widgets::ListState, Frame};
+use std::io::Error;
+
+/// an entry of the command palette, switches to `tab` (if any) and then runs `action`
+#[derive(Debug, Clone)]
+pub struct PaletteCommand {
+    pub label: String,
+    pub keys: String,
+    pub tab: Option<ControllerRegistry>,
+    pub action: Option<Action>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PaletteCtrl {
+    pub visible: bool,
+    pub input: TextInput,
+    pub commands: Vec<PaletteCommand>,
+    pub filtered: Vec<usize>,
+    pub l_state: ListState,
+    pub selected: Option<PaletteCommand>,
+    /// popup and visible command areas, set while rendering
+    pub popup_area: Rect,
+    pub item_areas: Vec<(usize, Rect)>,
+}
+
+impl UtilFns for PaletteCtrl {}
+
+impl CtrObj for PaletteCtrl {
+    fn init_data(&mut self) {}
+
+    fn display(&mut self, f: &mut Frame, area: Rect, theme: &Theme) -> Result<(), Error> {
+        if self.visible {
+            palette_ui(self, f, area, theme);
+        }
+        Ok(())
+    }
+
+    fn key_context(&self) -> KeyContext {
+        KeyContext::Palette
+    }
+
+    fn action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::Up => self.move_selection(-1),
+            Action::Down => self.move_selection(1),
+            Action::Cancel => self.close(),
+            Action::Confirm => {
+                self.selected = self
+                    .l_state
+                    .selected()
+                    .and_then(|idx| self.filtered.get(idx))
+                    .map(|idx| self.commands[*idx].clone());
+                self.close();
+            }
+            action => {
+                if self.input.handle_action(&action) {
+                    self.filter();
+                }
+            }
+        }
+
+        AppState::MoveOn
+    }
+
+    fn mouse_handler(&mut self, input: &MouseInput) -> AppState {
+        match input.kind {
+            MouseKind::ScrollUp => self.move_selection(-1),
+            MouseKind::ScrollDown => self.move_selection(1),
+            MouseKind::Click | MouseKind::DoubleClick => match input.hit(&self.item_areas) {
+                Some(idx) => {
+                    self.l_state.select(Some(idx));
+                    if input.kind == MouseKind::DoubleClick {
+                        return self.action_handler(&Action::Confirm);
+                    }
+                }
+                None if !input.is_in(self.popup_area) => self.close(),
+                None => {}
+            },
+            _ => {}
+        }
+
+        AppState::MoveOn
+    }
+}
+
+impl PaletteCtrl {
+    /// builds the command list for the current screen and shows the palette
+    pub fn open(&mut self, keymap: &Keymap, ctx: KeyContext, menu: &[MenuItem]) {
+        let mut commands: Vec<PaletteCommand> = menu
+            .iter()
+            .map(|item| PaletteCommand {
+                label: format!("Go to {}", item.name),
+                keys: "".to_string(),
+                tab: Some(item.ctrl.clone()),
+                action: None,
+            })
+            .collect();
+        let has_tab = |ctrl: ControllerRegistry| menu.iter().any(|item| item.ctrl == ctrl);
+
+        if has_tab(ControllerRegistry::Task) {
+            commands.push(PaletteCommand {
+                label: "New task".to_string(),
+                keys: "".to_string(),
+                tab: Some(ControllerRegistry::Task),
+                action: Some(Action::New),
+            });
+        }
+        if has_tab(ControllerRegistry::Project) {
+            commands.push(PaletteCommand {
+                label: "New project".to_string(),
+                keys: "".to_string(),
+                tab: Some(ControllerRegistry::Project),
+                action: Some(Action::New),
+            });
+        }
+
+        ACTIONS
+            .iter()
+            .filter(|(action, _, _)| *action != Action::Palette)
+            .for_each(|(action, _, description)| {
+                commands.push(PaletteCommand {
+                    label: capitalize(description),
+                    keys: keymap.keys_for(ctx, *action).join(", "),
+                    tab: None,
+                    action: Some(*action),
+                })
+            });
+
+        if has_tab(ControllerRegistry::Dashboard) {
+            self.lookup("select id, name from project order by name")
+                .iter()
+                .for_each(|(id, name)| {
+                    commands.push(PaletteCommand {
+                        label: format!("Jump to project: {}", name),
+                        keys: "".to_string(),
+                        tab: Some(ControllerRegistry::Dashboard),
+                        action: Some(Action::JumpTo(*id)),
+                    })
+                });
+        }
+        if matches!(ctx, KeyContext::List | KeyContext::Board) {
+            self.lookup("select id, name from task_status order by id")
+                .iter()
+                .for_each(|(id, name)| {
+                    commands.push(PaletteCommand {
+                        label: format!("Change status to: {}", name),
+                        keys: "".to_string(),
+                        tab: None,
+                        action: Some(Action::SetStatus(*id)),
+                    })
+                });
+        }
+
+        self.commands = commands;
+        self.input.clear();
+        self.selected = None;
+        self.visible = true;
+        self.filter();
+    }
+
+    pub fn close(&mut self) {
+        self.visible = false;
+        self.input.clear();
+    }
+
+    /// command chosen by the user, if any, the palette forgets it once taken
+    pub fn take_selected(&mut self) -> Option<PaletteCommand> {
+        self.selected.take()
+    }
+
+    fn lookup(&self, sql: &str) -> Vec<(i32, String)> {
+        match Self::get_db_connection() {
+            Ok(conn) => match Task::query(&conn, sql.to_string()) {
+                Ok(rows) => rows
+                    .iter()
+                    .map(|row| {
+                        (
+                            row.get("id").unwrap().parse::<i32>().unwrap_or(0),
+                            row.get("name").unwrap().to_string(),
+                        )
+                    })
+                    .collect(),
+                Err(_e) => vec![],
+            },
+            Err(_e) => vec![],
+        }
+    }
+
+    /// keeps the commands matching the input, best matches first
+    pub fn filter(&mut self) {
+        let mut scored: Vec<(i32, usize)> = self
+            .commands
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, cmd)| fuzzy_score(self.input.value(), &cmd.label).map(|s| (s, idx)))
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        self.filtered = scored.iter().map(|(_, idx)| *idx).collect();
+        self.l_state.select(if self.filtered.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+
+    fn move_selection(&mut self, step: i32) {
+        let count = self.filtered.len() as i32;
+        if count > 0 {
+            let current = self.l_state.selected().unwrap_or(0) as i32;
+            self.l_state
+                .select(Some(((current + step + count) % count) as usize));
+        }
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// scores `text` against `pattern` when all pattern chars appear in order (case insensitive),
+/// consecutive matches and matches at the start of words score higher
+pub fn fuzzy_score(pattern: &str, text: &str) -> Option<i32> {
+    let pattern: Vec<char> = pattern
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    if pattern.is_empty() {
+        return Some(0);
+    }
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut p_idx = 0;
+    let mut last_match: Option<usize> = None;
+
+    for (t_idx, c) in text.iter().enumerate() {
+        if p_idx < pattern.len() && *c == pattern[p_idx] {
+            score += 1;
+            if t_idx == 0 || !text[t_idx - 1].is_alphanumeric() {
+                score += 8;
+            }
+            match last_match {
+                Some(last) if last + 1 == t_idx => score += 5,
+                Some(last) => score -= (t_idx - last - 1).min(5) as i32,
+                None => score -= t_idx.min(10) as i32,
+            }
+            last_match = Some(t_idx);
+            p_idx += 1;
+        }
+    }
+
+    if p_idx == pattern.len() {
+        Some(score)
+    } else {
+        None
+    }
+}
@@ -1,9 +1,14 @@
 use super::{CtrObj, CtrlActions};
 use crate::app::AppState;
+use crate::keymap::{Action, KeyContext};
 use crate::models::{project::Project, DbObj};
+use crate::mouse::{MouseInput, MouseKind};
+use crate::views::input::TextInput;
+use crate::views::markdown;
 use crate::views::project::*;
+use crate::views::theme::Theme;
+use crate::views::FORM_FIELD_WIDTH;
 use crate::UtilFns;
-use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{layout::Rect, widgets::TableState, Frame};
 use rusqlite::Error as RuError;
 use std::io::Error;
@@ -24,10 +29,14 @@ pub struct ProjectCtrl {
     pub action: CtrlActions,
     pub active_item: usize,
     pub field_idx: u32,
-    pub input: String,
+    pub input: TextInput,
     pub t_state: TableState,
     pub record_count: usize,
     pub show_popup: bool,
+    /// screen areas of the visible table rows, set while rendering
+    pub row_areas: Vec<(usize, Rect)>,
+    /// checkbox of the description selected in the detail view
+    pub checkbox_idx: usize,
 }
 
 impl Default for ProjectCtrl {
@@ -37,10 +46,12 @@ impl Default for ProjectCtrl {
             action: CtrlActions::List,
             active_item: 0,
             field_idx: 0,
-            input: String::new(),
+            input: TextInput::default(),
             t_state: TableState::default(),
             record_count: 0,
             show_popup: false,
+            row_areas: vec![],
+            checkbox_idx: 0,
         }
     }
 }
@@ -50,27 +61,64 @@ impl UtilFns for ProjectCtrl {}
 impl CtrObj for ProjectCtrl {
     fn init_data(&mut self) {}
 
-    fn display(&mut self, f: &mut Frame, area: Rect) -> Result<(), Error> {
+    fn display(&mut self, f: &mut Frame, area: Rect, theme: &Theme) -> Result<(), Error> {
         match self.action {
-            CtrlActions::List => list_view(self, f, area),
-            CtrlActions::Edit => edit_view(self, f, area),
-            CtrlActions::Del => del_view(self, f, area),
-            CtrlActions::Detail => detail_view(self, f, area),
+            CtrlActions::List => list_view(self, f, area, theme),
+            CtrlActions::Edit => edit_view(self, f, area, theme),
+            CtrlActions::Del => del_view(self, f, area, theme),
+            CtrlActions::Detail => detail_view(self, f, area, theme),
         }
         Ok(())
     }
 
-    fn key_event_handler(&mut self, key: &KeyEvent) -> AppState {
+    fn key_context(&self) -> KeyContext {
         match self.action {
-            CtrlActions::List => return self.list_key_event(key),
-            CtrlActions::Edit => return self.edit_key_event(key),
-            CtrlActions::Detail => return self.detail_key_event(key),
-            CtrlActions::Del => return self.del_key_event(key),
+            CtrlActions::List => KeyContext::List,
+            CtrlActions::Edit => KeyContext::Form,
+            CtrlActions::Detail | CtrlActions::Del => KeyContext::Popup,
         }
     }
+
+    fn action_handler(&mut self, action: &Action) -> AppState {
+        match self.action {
+            CtrlActions::List => self.list_action_handler(action),
+            CtrlActions::Edit => self.edit_action_handler(action),
+            CtrlActions::Detail => self.detail_action_handler(action),
+            CtrlActions::Del => self.del_action_handler(action),
+        }
+    }
+
+    fn mouse_handler(&mut self, input: &MouseInput) -> AppState {
+        match self.action {
+            CtrlActions::List => self.list_mouse_handler(input),
+            _ => AppState::Running,
+        }
+    }
+
+    fn editor_text(&self) -> Option<String> {
+        match self.action {
+            CtrlActions::Edit if self.input.is_multiline() => Some(self.input.value().to_string()),
+            _ => None,
+        }
+    }
+
+    fn set_editor_text(&mut self, text: &str) {
+        self.input = TextInput::multiline(text, FORM_FIELD_WIDTH);
+        self.update_field();
+    }
 }
 
 impl ProjectCtrl {
+    pub fn get_creator_name(&self) -> String {
+        match Self::get_db_connection() {
+            Ok(conn) => match self.project_table.get_creator(&conn) {
+                Some(p) => p.full_name(),
+                None => "--".to_string(),
+            },
+            Err(_e) => "--".to_string(),
+        }
+    }
+
     pub fn project_list(&mut self) -> Result<Vec<Project>, RuError> {
         match Self::get_db_connection() {
             Ok(conn) => match Project::list(&conn, "".to_string()) {
@@ -82,7 +130,7 @@ impl ProjectCtrl {
             },
             Err(e) => {
                 //@TODO: show error popup
-                println!("error getting connection: {}", e.to_string());
+                log::error!("error getting connection: {}", e);
                 return Err(RuError::InvalidQuery);
             }
         }
@@ -90,11 +138,11 @@ impl ProjectCtrl {
 
     pub fn update_field(&mut self) {
         match self.field_idx {
-            0 => self.project_table.reference = self.input.clone(),
-            1 => self.project_table.name = self.input.clone(),
-            2 => self.project_table.description = self.input.clone(),
-            3 => self.project_table.start_date = self.input.clone(),
-            4 => self.project_table.end_date = self.input.clone(),
+            0 => self.project_table.reference = self.input.value().to_string(),
+            1 => self.project_table.name = self.input.value().to_string(),
+            2 => self.project_table.description = self.input.value().to_string(),
+            3 => self.project_table.start_date = self.input.value().to_string(),
+            4 => self.project_table.end_date = self.input.value().to_string(),
             _ => {}
         }
     }
@@ -110,12 +158,12 @@ impl ProjectCtrl {
                         }
                         Err(e) => {
                             //@TODO: show popup error
-                            println!("faile to save record {}", e.to_string());
+                            log::error!("failed to delete record: {}", e);
                         }
                     }
                 }
-                Err(_e) => {
-                    println!("ERROR NO DATABASE CONN");
+                Err(e) => {
+                    log::error!("no database connection: {}", e);
                     //@TODO: show popup error
                 }
             }
@@ -133,12 +181,12 @@ impl ProjectCtrl {
                     }
                     Err(e) => {
                         //@TODO: show popup error
-                        println!("faile to save record {}", e.to_string());
+                        log::error!("failed to save record: {}", e);
                     }
                 }
             }
-            Err(_e) => {
-                println!("ERROR NO DATABASE CONN");
+            Err(e) => {
+                log::error!("no database connection: {}", e);
                 //@TODO: show popup error
             }
         }
@@ -194,41 +242,58 @@ impl ProjectCtrl {
         }
     }
 
-    pub fn list_key_event(&mut self, key: &KeyEvent) -> AppState {
-        match key.code {
-            KeyCode::Char('n') => {
+    pub fn list_action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::New => {
                 self.action = CtrlActions::Edit;
                 self.project_table = Project::default();
+                self.set_active_field(0);
                 return AppState::MoveOn;
             }
-            KeyCode::Char('e') => {
+            Action::Edit => {
                 self.set_selected_record();
                 self.action = CtrlActions::Edit;
+                self.set_active_field(0);
                 return AppState::MoveOn;
             }
 
-            KeyCode::Char('d') => {
+            Action::Delete => {
                 self.set_selected_record();
                 self.action = CtrlActions::Del;
                 return AppState::MoveOn;
             }
 
-            KeyCode::Char('s') => {
+            Action::Show => {
                 self.set_selected_record();
                 self.action = CtrlActions::Detail;
+                self.checkbox_idx = 0;
                 return AppState::MoveOn;
             }
 
-            KeyCode::Up => {
+            Action::Up => {
                 self.previous_row();
                 return AppState::MoveOn;
             }
 
-            KeyCode::Down => {
+            Action::Down => {
                 self.next_row();
                 return AppState::MoveOn;
             }
 
+            Action::First => {
+                if self.record_count > 0 {
+                    self.t_state.select(Some(0));
+                }
+                return AppState::MoveOn;
+            }
+
+            Action::Last => {
+                if self.record_count > 0 {
+                    self.t_state.select(Some(self.record_count - 1));
+                }
+                return AppState::MoveOn;
+            }
+
             _ => {
                 //println!("key pressed '{:?}'", key.code);
             }
@@ -237,19 +302,55 @@ impl ProjectCtrl {
         AppState::Running
     }
 
+    pub fn list_mouse_handler(&mut self, input: &MouseInput) -> AppState {
+        match input.kind {
+            MouseKind::ScrollUp => self.list_action_handler(&Action::Up),
+            MouseKind::ScrollDown => self.list_action_handler(&Action::Down),
+            MouseKind::Click | MouseKind::DoubleClick => match input.hit(&self.row_areas) {
+                Some(idx) => {
+                    self.t_state.select(Some(idx));
+                    if input.kind == MouseKind::DoubleClick {
+                        return self.list_action_handler(&Action::Show);
+                    }
+                    AppState::MoveOn
+                }
+                None => AppState::Running,
+            },
+            _ => AppState::Running,
+        }
+    }
+
+    /// checks or unchecks the selected checkbox of the description and saves the project
+    pub fn toggle_checkbox(&mut self) {
+        self.project_table.description =
+            markdown::toggle_checkbox(&self.project_table.description, self.checkbox_idx);
+        match Self::get_db_connection() {
+            Ok(conn) => {
+                if let Err(e) = self.project_table.save(&conn) {
+                    //@TODO: show popup error
+                    log::error!("failed to save record: {}", e);
+                }
+            }
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                //@TODO: show popup error
+            }
+        }
+    }
+
     pub fn go_back(&mut self) {
-        self.input = "".to_string();
+        self.input.clear();
         self.field_idx = 0;
         self.action = CtrlActions::List;
     }
 
-    pub fn del_key_event(&mut self, key: &KeyEvent) -> AppState {
-        match key.code {
-            KeyCode::Esc => {
+    pub fn del_action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::Cancel => {
                 self.go_back();
             }
 
-            KeyCode::Enter => {
+            Action::Confirm => {
                 self.del_project();
             }
 
@@ -260,14 +361,29 @@ impl ProjectCtrl {
 
         AppState::MoveOn
     }
-    pub fn detail_key_event(&mut self, key: &KeyEvent) -> AppState {
-        match key.code {
-            KeyCode::Esc => {
+    pub fn detail_action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::Cancel => {
                 self.go_back();
             }
 
-            KeyCode::Enter => {
+            Action::Confirm => {
                 self.action = CtrlActions::Edit;
+                self.set_active_field(0);
+            }
+
+            Action::Up | Action::Down => {
+                let count = markdown::checkboxes(&self.project_table.description).len();
+                if count > 0 {
+                    self.checkbox_idx = match *action {
+                        Action::Up => (self.checkbox_idx + count - 1) % count,
+                        _ => (self.checkbox_idx + 1) % count,
+                    };
+                }
+            }
+
+            Action::ToggleCheck => {
+                self.toggle_checkbox();
             }
 
             _ => {
@@ -278,34 +394,30 @@ impl ProjectCtrl {
         AppState::MoveOn
     }
 
-    pub fn edit_key_event(&mut self, key: &KeyEvent) -> AppState {
-        match key.code {
-            KeyCode::Tab => {
+    pub fn edit_action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::NextField => {
                 self.set_next_active();
             }
 
-            KeyCode::Esc => {
+            Action::PrevField => {
+                self.set_prev_active();
+            }
+
+            Action::Cancel => {
                 self.go_back();
                 //@TODO: reset all fields when to empty if we were creating a new project or to the
                 //fields original values of the selected project
             }
 
-            KeyCode::Backspace => {
-                self.input.pop();
-                self.update_field();
-            }
-
-            KeyCode::Enter => {
+            Action::Confirm => {
                 self.save_project();
             }
 
-            KeyCode::Char(c) => {
-                self.input.push(c);
-                self.update_field();
-            }
-
-            _ => {
-                //println!("key pressed '{:?}'", key.code);
+            action => {
+                if self.input.handle_action(&action) {
+                    self.update_field();
+                }
             }
         }
 
@@ -313,23 +425,30 @@ impl ProjectCtrl {
     }
 
     pub fn set_next_active(&mut self) {
-        match self.action {
-            CtrlActions::Edit => {
-                self.field_idx = self.field_idx + 1;
-                if self.field_idx == 5 {
-                    self.field_idx = 0;
-                }
-                self.input = match self.field_idx {
-                    0 => self.project_table.reference.clone(),
-                    1 => self.project_table.name.clone(),
-                    2 => self.project_table.description.clone(),
-                    3 => self.project_table.start_date.clone(),
-                    4 => self.project_table.end_date.clone(),
-                    _ => "".to_string(),
-                }
-            }
+        if let CtrlActions::Edit = self.action {
+            self.set_active_field((self.field_idx + 1) % 5);
+        }
+    }
 
-            _ => {}
+    pub fn set_prev_active(&mut self) {
+        if let CtrlActions::Edit = self.action {
+            self.set_active_field((self.field_idx + 5 - 1) % 5);
         }
     }
+
+    fn set_active_field(&mut self, field_idx: u32) {
+        self.field_idx = field_idx;
+        let value = match self.field_idx {
+            0 => self.project_table.reference.clone(),
+            1 => self.project_table.name.clone(),
+            2 => self.project_table.description.clone(),
+            3 => self.project_table.start_date.clone(),
+            4 => self.project_table.end_date.clone(),
+            _ => "".to_string(),
+        };
+        self.input = match self.field_idx {
+            2 => TextInput::multiline(&value, FORM_FIELD_WIDTH),
+            _ => TextInput::new(&value),
+        };
+    }
 }
@@ -0,0 +1,706 @@
+use super::{CtrObj, CtrlActions};
+use crate::app::AppState;
+use crate::keymap::{Action, KeyContext};
+use crate::models::{
+    project::Project,
+    sprint::{Sprint, SPRINT_DATE_FORMAT},
+    task::Task,
+    task_status::TaskStatus,
+    DbObj,
+};
+use crate::mouse::{MouseInput, MouseKind};
+use crate::views::input::TextInput;
+use crate::views::sprint::*;
+use crate::views::theme::Theme;
+use crate::views::FORM_FIELD_WIDTH;
+use crate::UtilFns;
+use chrono::{Duration, Local, NaiveDate};
+use ratatui::{
+    layout::Rect,
+    widgets::{ListState, TableState},
+    Frame,
+};
+use rusqlite::Error as RuError;
+use std::{collections::HashMap, io::Error};
+
+/// the two lists of the planning screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlanningFocus {
+    Backlog,
+    Sprint,
+}
+
+#[derive(Debug, Clone)]
+pub struct SprintCtrl {
+    pub sprint_table: Sprint,
+    pub action: CtrlActions,
+    pub field_idx: u32,
+    pub input: TextInput,
+    pub t_state: TableState,
+    pub l_state: ListState,
+    pub record_count: usize,
+    pub show_popup: bool,
+    /// screen areas of the visible table rows and of the project popup, set while rendering
+    pub row_areas: Vec<(usize, Rect)>,
+    pub popup_areas: Vec<(usize, Rect)>,
+    /// the Del screen asks to close the sprint instead of deleting it
+    pub closing: bool,
+    /// planning screen: unplanned tasks of the project and tasks of the sprint
+    pub backlog: Vec<HashMap<String, String>>,
+    pub planned: Vec<HashMap<String, String>>,
+    pub backlog_state: ListState,
+    pub planned_state: ListState,
+    pub focus: PlanningFocus,
+    /// why the last save of the form failed, shown under the fields
+    pub form_error: Option<String>,
+}
+
+impl Default for SprintCtrl {
+    fn default() -> SprintCtrl {
+        SprintCtrl {
+            sprint_table: Sprint::default(),
+            action: CtrlActions::List,
+            field_idx: 0,
+            input: TextInput::default(),
+            t_state: TableState::default(),
+            l_state: ListState::default(),
+            record_count: 0,
+            show_popup: false,
+            row_areas: vec![],
+            popup_areas: vec![],
+            closing: false,
+            backlog: vec![],
+            planned: vec![],
+            backlog_state: ListState::default(),
+            planned_state: ListState::default(),
+            focus: PlanningFocus::Backlog,
+            form_error: None,
+        }
+    }
+}
+
+impl UtilFns for SprintCtrl {}
+
+impl CtrObj for SprintCtrl {
+    fn init_data(&mut self) {}
+
+    fn display(&mut self, f: &mut Frame, area: Rect, theme: &Theme) -> Result<(), Error> {
+        match self.action {
+            CtrlActions::List => list_view(self, f, area, theme),
+            CtrlActions::Edit => edit_view(self, f, area, theme),
+            CtrlActions::Del => del_view(self, f, area, theme),
+            CtrlActions::Detail => planning_view(self, f, area, theme),
+        }
+        Ok(())
+    }
+
+    fn key_context(&self) -> KeyContext {
+        match self.action {
+            CtrlActions::List => KeyContext::List,
+            CtrlActions::Edit if self.show_popup => KeyContext::Popup,
+            CtrlActions::Edit => KeyContext::Form,
+            CtrlActions::Detail => KeyContext::Board,
+            CtrlActions::Del => KeyContext::Popup,
+        }
+    }
+
+    fn action_handler(&mut self, action: &Action) -> AppState {
+        match self.action {
+            CtrlActions::List => self.list_action_handler(action),
+            CtrlActions::Edit => self.edit_action_handler(action),
+            CtrlActions::Detail => self.planning_action_handler(action),
+            CtrlActions::Del => self.del_action_handler(action),
+        }
+    }
+
+    fn mouse_handler(&mut self, input: &MouseInput) -> AppState {
+        match self.action {
+            CtrlActions::List => self.list_mouse_handler(input),
+            CtrlActions::Edit if self.show_popup => self.popup_mouse_handler(input),
+            _ => AppState::Running,
+        }
+    }
+
+    fn editor_text(&self) -> Option<String> {
+        match self.action {
+            CtrlActions::Edit if self.input.is_multiline() && !self.show_popup => {
+                Some(self.input.value().to_string())
+            }
+            _ => None,
+        }
+    }
+
+    fn set_editor_text(&mut self, text: &str) {
+        self.input = TextInput::multiline(text, FORM_FIELD_WIDTH);
+        self.update_field();
+    }
+}
+
+impl SprintCtrl {
+    /// sprints with their project name and points, open sprints first
+    pub fn sprint_list(&mut self) -> Result<Vec<HashMap<String, String>>, RuError> {
+        let custom_query = format!(
+            "SELECT s.*, p.name AS 'project_name', {} FROM sprint AS s LEFT JOIN project AS p ON (s.project_id = p.id) ORDER BY s.closed, s.start_date, s.id",
+            Sprint::points_column("s")
+        );
+        match Self::get_db_connection() {
+            Ok(conn) => match Task::query(&conn, custom_query) {
+                Ok(list) => {
+                    self.record_count = list.len();
+                    Ok(list)
+                }
+                Err(e) => Err(e),
+            },
+            Err(e) => {
+                //@TODO: show error popup
+                log::error!("error getting connection: {}", e);
+                Err(RuError::InvalidQuery)
+            }
+        }
+    }
+
+    pub fn project_list(&mut self) -> Result<Vec<HashMap<String, String>>, RuError> {
+        let custom_query = "select id, name from project order by name".to_string();
+        match Self::get_db_connection() {
+            Ok(conn) => Task::query(&conn, custom_query),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn get_project_name(&self) -> String {
+        match Self::get_db_connection() {
+            Ok(conn) => match Project::get_by_id(&conn, self.sprint_table.project_id) {
+                Ok(p) => p.name,
+                Err(_e) => "--".to_string(),
+            },
+            Err(_e) => "--".to_string(),
+        }
+    }
+
+    /// where the unfinished tasks go when the selected sprint is closed
+    pub fn carry_over_name(&self) -> String {
+        match Self::get_db_connection() {
+            Ok(conn) => match self.sprint_table.next(&conn) {
+                Some(sprint) => sprint.name,
+                None => "the backlog".to_string(),
+            },
+            Err(_e) => "--".to_string(),
+        }
+    }
+
+    /// loads both lists of the planning screen, unfinished tasks without a sprint make the
+    /// backlog
+    pub fn load_planning(&mut self) {
+        match Self::get_db_connection() {
+            Ok(conn) => {
+                let columns = "t.id, t.name, t.weight, t.status";
+                let backlog_query = format!(
+                    "SELECT {} FROM task AS t WHERE t.project_id = {} AND t.sprint_id = 0 AND t.status <> {} ORDER BY t.status DESC, t.name",
+                    columns,
+                    self.sprint_table.project_id,
+                    TaskStatus::done_id(&conn)
+                );
+                let planned_query = format!(
+                    "SELECT {} FROM task AS t WHERE t.sprint_id = {} ORDER BY t.status DESC, t.name",
+                    columns, self.sprint_table.id
+                );
+                self.backlog = Task::query(&conn, backlog_query).unwrap_or_default();
+                self.planned = Task::query(&conn, planned_query).unwrap_or_default();
+            }
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                //@TODO: show popup error
+            }
+        }
+        for (state, len) in [
+            (&mut self.backlog_state, self.backlog.len()),
+            (&mut self.planned_state, self.planned.len()),
+        ] {
+            state.select(match len {
+                0 => None,
+                _ => Some(state.selected().unwrap_or(0).min(len - 1)),
+            });
+        }
+    }
+
+    /// moves the selected task of the focused list to the other one
+    pub fn move_selected_task(&mut self) {
+        let (list, state, sprint_id) = match self.focus {
+            PlanningFocus::Backlog => (&self.backlog, &self.backlog_state, self.sprint_table.id),
+            PlanningFocus::Sprint => (&self.planned, &self.planned_state, 0),
+        };
+        let task_id = match state.selected().and_then(|idx| list.get(idx)) {
+            Some(task) => task.get("id").unwrap().parse::<i32>().unwrap(),
+            None => return,
+        };
+        match Self::get_db_connection() {
+            Ok(conn) => match Task::get_by_id(&conn, task_id) {
+                Ok(mut task) => {
+                    task.sprint_id = sprint_id;
+                    if let Err(e) = task.save(&conn) {
+                        //@TODO: show popup error
+                        log::error!("failed to save record: {}", e);
+                    }
+                }
+                Err(e) => {
+                    log::error!("failed to load task {}: {}", task_id, e);
+                }
+            },
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                //@TODO: show popup error
+            }
+        }
+        self.load_planning();
+    }
+
+    pub fn update_field(&mut self) {
+        match self.field_idx {
+            1 => self.sprint_table.name = self.input.value().to_string(),
+            2 => self.sprint_table.goal = self.input.value().to_string(),
+            3 => self.sprint_table.start_date = self.input.value().to_string(),
+            4 => self.sprint_table.end_date = self.input.value().to_string(),
+            _ => {}
+        }
+    }
+
+    pub fn del_sprint(&mut self) {
+        if self.sprint_table.id > 0 {
+            match Self::get_db_connection() {
+                Ok(conn) => {
+                    match self.sprint_table.del(&conn) {
+                        Ok(()) => {
+                            if let Err(e) = Sprint::release_tasks(&conn, self.sprint_table.id) {
+                                log::error!("failed to move tasks to the backlog: {}", e);
+                            }
+                            self.sprint_table = Sprint::default();
+                            self.go_back();
+                        }
+                        Err(e) => {
+                            //@TODO: show popup error
+                            log::error!("failed to delete record: {}", e);
+                        }
+                    }
+                }
+                Err(e) => {
+                    log::error!("no database connection: {}", e);
+                    //@TODO: show popup error
+                }
+            }
+        }
+    }
+
+    pub fn close_sprint(&mut self) {
+        match Self::get_db_connection() {
+            Ok(conn) => match self.sprint_table.close(&conn) {
+                Ok(next) => {
+                    log::info!(
+                        "closed sprint '{}', unfinished tasks moved to {}",
+                        self.sprint_table.name,
+                        next.map(|s| s.name).unwrap_or("the backlog".to_string())
+                    );
+                    self.go_back();
+                }
+                Err(e) => {
+                    //@TODO: show popup error
+                    log::error!("failed to close sprint: {}", e);
+                }
+            },
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                //@TODO: show popup error
+            }
+        }
+    }
+
+    pub fn save_sprint(&mut self) {
+        self.form_error = None;
+        if self.sprint_table.project_id == 0 || self.sprint_table.name.trim().is_empty() {
+            self.form_error = Some("a sprint needs a project and a name".to_string());
+            return;
+        }
+        for date in [&self.sprint_table.start_date, &self.sprint_table.end_date] {
+            if NaiveDate::parse_from_str(date, SPRINT_DATE_FORMAT).is_err() {
+                self.form_error = Some(format!(
+                    "invalid sprint date '{}', expected YYYY-MM-DD",
+                    date
+                ));
+                return;
+            }
+        }
+        if let (Some(start), Some(end)) = (self.sprint_table.start(), self.sprint_table.end()) {
+            if start > end {
+                self.form_error = Some(format!(
+                    "the starting date {} is after the ending date {}",
+                    start, end
+                ));
+                return;
+            }
+        }
+
+        match Self::get_db_connection() {
+            Ok(conn) => {
+                match self.sprint_table.save(&conn) {
+                    Ok(sprint) => {
+                        self.sprint_table = sprint.clone();
+                        self.go_back();
+                    }
+                    Err(e) => {
+                        //@TODO: show popup error
+                        log::error!("failed to save record: {}", e);
+                    }
+                }
+            }
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                //@TODO: show popup error
+            }
+        }
+    }
+
+    /// a new sprint starts today and lasts two weeks
+    fn new_sprint(&mut self) -> Sprint {
+        let today = Local::now().date_naive();
+        Sprint {
+            name: format!("Sprint {}", self.record_count + 1),
+            start_date: today.format(SPRINT_DATE_FORMAT).to_string(),
+            end_date: (today + Duration::days(13))
+                .format(SPRINT_DATE_FORMAT)
+                .to_string(),
+            ..Sprint::default()
+        }
+    }
+
+    pub fn previous_row(&mut self) {
+        if self.record_count > 0 {
+            let r = match self.t_state.selected() {
+                Some(idx) => {
+                    if idx == 0 {
+                        self.record_count - 1
+                    } else {
+                        idx - 1
+                    }
+                }
+                None => 0,
+            };
+            self.t_state.select(Some(r));
+        }
+    }
+
+    pub fn next_row(&mut self) {
+        if self.record_count > 0 {
+            let r = match self.t_state.selected() {
+                Some(idx) => {
+                    if idx >= self.record_count - 1 {
+                        0
+                    } else {
+                        idx + 1
+                    }
+                }
+                None => 0,
+            };
+            self.t_state.select(Some(r));
+        }
+    }
+
+    /// loads the selected sprint, false when there is none
+    pub fn set_selected_record(&mut self) -> bool {
+        let selected = match (self.t_state.selected(), self.sprint_list()) {
+            (Some(idx), Ok(sprints)) => sprints
+                .get(idx)
+                .and_then(|s| s.get("id"))
+                .and_then(|id| id.parse::<i32>().ok()),
+            _ => None,
+        };
+        match (selected, Self::get_db_connection()) {
+            (Some(id), Ok(conn)) => match Sprint::get_by_id(&conn, id) {
+                Ok(sprint) => {
+                    self.sprint_table = sprint;
+                    true
+                }
+                Err(_e) => false,
+            },
+            _ => {
+                //@TODO: show message "no record selected"
+                false
+            }
+        }
+    }
+
+    pub fn list_action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::New => {
+                self.action = CtrlActions::Edit;
+                self.sprint_table = self.new_sprint();
+                self.set_active_field(0);
+                return AppState::MoveOn;
+            }
+            Action::Edit => {
+                if self.set_selected_record() {
+                    self.action = CtrlActions::Edit;
+                    self.set_active_field(0);
+                }
+                return AppState::MoveOn;
+            }
+
+            Action::Delete | Action::CloseSprint => {
+                if self.set_selected_record() {
+                    self.closing = matches!(action, Action::CloseSprint);
+                    if !(self.closing && self.sprint_table.closed) {
+                        self.action = CtrlActions::Del;
+                    }
+                }
+                return AppState::MoveOn;
+            }
+
+            Action::Show => {
+                if self.set_selected_record() {
+                    self.action = CtrlActions::Detail;
+                    self.focus = PlanningFocus::Backlog;
+                    self.load_planning();
+                }
+                return AppState::MoveOn;
+            }
+
+            Action::Up => {
+                self.previous_row();
+                return AppState::MoveOn;
+            }
+
+            Action::Down => {
+                self.next_row();
+                return AppState::MoveOn;
+            }
+
+            Action::First => {
+                if self.record_count > 0 {
+                    self.t_state.select(Some(0));
+                }
+                return AppState::MoveOn;
+            }
+
+            Action::Last => {
+                if self.record_count > 0 {
+                    self.t_state.select(Some(self.record_count - 1));
+                }
+                return AppState::MoveOn;
+            }
+
+            _ => {}
+        }
+
+        AppState::Running
+    }
+
+    pub fn list_mouse_handler(&mut self, input: &MouseInput) -> AppState {
+        match input.kind {
+            MouseKind::ScrollUp => self.list_action_handler(&Action::Up),
+            MouseKind::ScrollDown => self.list_action_handler(&Action::Down),
+            MouseKind::Click | MouseKind::DoubleClick => match input.hit(&self.row_areas) {
+                Some(idx) => {
+                    self.t_state.select(Some(idx));
+                    if input.kind == MouseKind::DoubleClick {
+                        return self.list_action_handler(&Action::Show);
+                    }
+                    AppState::MoveOn
+                }
+                None => AppState::Running,
+            },
+            _ => AppState::Running,
+        }
+    }
+
+    pub fn go_back(&mut self) {
+        self.input.clear();
+        self.field_idx = 0;
+        self.closing = false;
+        self.form_error = None;
+        self.action = CtrlActions::List;
+    }
+
+    pub fn del_action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::Cancel => {
+                self.go_back();
+            }
+
+            Action::Confirm if self.closing => {
+                self.close_sprint();
+            }
+
+            Action::Confirm => {
+                self.del_sprint();
+            }
+
+            _ => {}
+        }
+
+        AppState::MoveOn
+    }
+
+    /// length and selection of the focused planning list
+    fn focused_list(&mut self) -> (usize, &mut ListState) {
+        match self.focus {
+            PlanningFocus::Backlog => (self.backlog.len(), &mut self.backlog_state),
+            PlanningFocus::Sprint => (self.planned.len(), &mut self.planned_state),
+        }
+    }
+
+    pub fn planning_action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::Cancel => {
+                self.go_back();
+            }
+
+            Action::Confirm | Action::MoveNext | Action::MovePrev => {
+                self.move_selected_task();
+            }
+
+            Action::Left | Action::Right => {
+                self.focus = match self.focus {
+                    PlanningFocus::Backlog => PlanningFocus::Sprint,
+                    PlanningFocus::Sprint => PlanningFocus::Backlog,
+                };
+            }
+
+            Action::Up | Action::Down | Action::First | Action::Last => {
+                let (len, state) = self.focused_list();
+                if len > 0 {
+                    let idx = state.selected().unwrap_or(0);
+                    state.select(Some(match *action {
+                        Action::Up => (idx + len - 1) % len,
+                        Action::Down => (idx + 1) % len,
+                        Action::First => 0,
+                        _ => len - 1,
+                    }));
+                }
+            }
+
+            Action::NextMenu | Action::PrevMenu => {
+                return AppState::Running;
+            }
+
+            _ => {}
+        }
+
+        AppState::MoveOn
+    }
+
+    pub fn popup_action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::Up | Action::Down if self.record_count > 0 => {
+                let idx = self.l_state.selected().unwrap_or(0);
+                self.l_state.select(Some(match *action {
+                    Action::Up => (idx + self.record_count - 1) % self.record_count,
+                    _ => (idx + 1) % self.record_count,
+                }));
+            }
+
+            Action::Cancel => {
+                self.show_popup = false;
+            }
+
+            Action::Confirm => {
+                if let (Some(idx), Ok(results)) = (self.l_state.selected(), self.project_list()) {
+                    if let Some(project) = results.get(idx) {
+                        self.sprint_table.project_id =
+                            project.get("id").unwrap().parse::<i32>().unwrap();
+                        self.show_popup = false;
+                    }
+                }
+            }
+
+            _ => {}
+        }
+
+        AppState::MoveOn
+    }
+
+    pub fn popup_mouse_handler(&mut self, input: &MouseInput) -> AppState {
+        match input.kind {
+            MouseKind::ScrollUp => self.popup_action_handler(&Action::Up),
+            MouseKind::ScrollDown => self.popup_action_handler(&Action::Down),
+            MouseKind::Click | MouseKind::DoubleClick => {
+                if let Some(idx) = input.hit(&self.popup_areas) {
+                    self.l_state.select(Some(idx));
+                    if input.kind == MouseKind::DoubleClick {
+                        return self.popup_action_handler(&Action::Confirm);
+                    }
+                }
+                AppState::MoveOn
+            }
+            _ => AppState::MoveOn,
+        }
+    }
+
+    pub fn edit_action_handler(&mut self, action: &Action) -> AppState {
+        if self.show_popup {
+            return self.popup_action_handler(action);
+        }
+        match *action {
+            Action::NextField => {
+                self.set_next_active();
+            }
+
+            Action::PrevField => {
+                self.set_prev_active();
+            }
+
+            Action::Cancel => {
+                self.go_back();
+            }
+
+            Action::Confirm => {
+                self.save_sprint();
+            }
+
+            Action::Insert(' ') if self.field_idx == 0 => {
+                // the project is picked from a popup
+                match self.project_list() {
+                    Ok(results) => self.record_count = results.len(),
+                    Err(_e) => self.record_count = 0,
+                }
+                self.l_state.select(match self.record_count {
+                    0 => None,
+                    _ => Some(0),
+                });
+                self.show_popup = true;
+            }
+
+            action => {
+                if self.field_idx > 0 && self.input.handle_action(&action) {
+                    self.update_field();
+                }
+            }
+        }
+
+        AppState::MoveOn
+    }
+
+    pub fn set_next_active(&mut self) {
+        if let CtrlActions::Edit = self.action {
+            self.set_active_field((self.field_idx + 1) % 5);
+        }
+    }
+
+    pub fn set_prev_active(&mut self) {
+        if let CtrlActions::Edit = self.action {
+            self.set_active_field((self.field_idx + 5 - 1) % 5);
+        }
+    }
+
+    fn set_active_field(&mut self, field_idx: u32) {
+        self.field_idx = field_idx;
+        let value = match self.field_idx {
+            1 => self.sprint_table.name.clone(),
+            2 => self.sprint_table.goal.clone(),
+            3 => self.sprint_table.start_date.clone(),
+            4 => self.sprint_table.end_date.clone(),
+            _ => "".to_string(),
+        };
+        self.input = match self.field_idx {
+            2 => TextInput::multiline(&value, FORM_FIELD_WIDTH),
+            _ => TextInput::new(&value),
+        };
+    }
+}
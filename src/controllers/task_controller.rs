@@ -1,9 +1,22 @@
 use super::{CtrObj, CtrlActions};
 use crate::app::AppState;
-use crate::models::{task::Task, DbObj};
+use crate::config;
+use crate::estimate::EstimateScale;
+use crate::keymap::{Action, KeyContext};
+use crate::models::{
+    checklist_item::ChecklistItem, comment::Comment, person::Person, task::Task, task::PRIORITIES,
+    task_assignee::TaskAssignee, task_dependency::TaskDependency, task_status::TaskStatus,
+    task_tag::TaskTag, DbObj,
+};
+use crate::mouse::{MouseInput, MouseKind};
+use crate::urgency;
+use crate::views::input::TextInput;
+use crate::views::markdown;
 use crate::views::task::*;
-use crate::UtilFns;
-use crossterm::event::{KeyCode, KeyEvent};
+use crate::views::theme::Theme;
+use crate::views::FORM_FIELD_WIDTH;
+use crate::{current_user_id, UtilFns};
+use chrono::Local;
 use ratatui::{
     layout::Rect,
     widgets::{ListState, TableState},
@@ -17,6 +30,45 @@ pub enum PopupTaskType {
     NoPopup,
     ProjectList,
     TaskStatusList,
+    AssigneeList,
+    EstimateList,
+    DependencyList,
+    ParentList,
+    PriorityList,
+}
+/// which tasks the list shows, `m` goes through them in this order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TaskFilter {
+    All,
+    AssignedToMe,
+    CreatedByMe,
+}
+
+impl TaskFilter {
+    pub fn next(&self) -> TaskFilter {
+        match self {
+            TaskFilter::All => TaskFilter::AssignedToMe,
+            TaskFilter::AssignedToMe => TaskFilter::CreatedByMe,
+            TaskFilter::CreatedByMe => TaskFilter::All,
+        }
+    }
+
+    /// sql condition on the tasks aliased `t`, None when every task is listed
+    pub fn condition(&self) -> Option<String> {
+        match self {
+            TaskFilter::All => None,
+            TaskFilter::AssignedToMe => Some(TaskAssignee::assigned_filter("t", current_user_id())),
+            TaskFilter::CreatedByMe => Some(format!("t.created_by = {}", current_user_id())),
+        }
+    }
+}
+
+/// what the text typed in the detail view is for
+#[derive(Debug, Clone, PartialEq)]
+pub enum DetailInput {
+    ChecklistItem,
+    /// id of the comment being edited, 0 for a new one
+    Comment(i32),
 }
 #[derive(Debug, Clone)]
 pub struct TaskCtrl {
@@ -24,12 +76,33 @@ pub struct TaskCtrl {
     pub action: CtrlActions,
     pub active_item: usize,
     pub field_idx: u32,
-    pub input: String,
+    pub input: TextInput,
     pub t_state: TableState,
     pub l_state: ListState,
     pub record_count: usize,
     pub show_popup: bool,
     pub popup_type: PopupTaskType,
+    /// screen areas of the visible table rows and popup items, set while rendering
+    pub row_areas: Vec<(usize, Rect)>,
+    pub popup_areas: Vec<(usize, Rect)>,
+    /// item selected in the detail view: description checkboxes, then checklist items, then
+    /// comments
+    pub detail_idx: usize,
+    pub checklist: Vec<ChecklistItem>,
+    /// comment thread of the task, see `Comment::thread`
+    pub comments: Vec<HashMap<String, String>>,
+    pub comment_state: ListState,
+    /// what is being typed in the detail view, if anything
+    pub detail_input: Option<(DetailInput, TextInput)>,
+    /// `Person` ids assigned to the task, saved with it
+    pub assignees: Vec<i32>,
+    /// ids of the tasks this one depends on, saved with it
+    pub depends_on: Vec<i32>,
+    /// tags of the task as typed in the form, comma separated, saved with it
+    pub tags: String,
+    pub filter: TaskFilter,
+    /// why the form couldn't be saved, shown under it until the next try
+    pub form_error: Option<String>,
 }
 
 impl Default for TaskCtrl {
@@ -39,12 +112,24 @@ impl Default for TaskCtrl {
             action: CtrlActions::List,
             active_item: 0,
             field_idx: 0,
-            input: String::new(),
+            input: TextInput::default(),
             t_state: TableState::default(),
             l_state: ListState::default(),
             record_count: 0,
             show_popup: false,
             popup_type: PopupTaskType::NoPopup,
+            row_areas: vec![],
+            popup_areas: vec![],
+            detail_idx: 0,
+            checklist: vec![],
+            comments: vec![],
+            comment_state: ListState::default(),
+            detail_input: None,
+            assignees: vec![],
+            depends_on: vec![],
+            tags: "".to_string(),
+            filter: TaskFilter::All,
+            form_error: None,
         }
     }
 }
@@ -54,45 +139,97 @@ impl UtilFns for TaskCtrl {}
 impl CtrObj for TaskCtrl {
     fn init_data(&mut self) {}
 
-    fn display(&mut self, f: &mut Frame, area: Rect) -> Result<(), Error> {
+    fn display(&mut self, f: &mut Frame, area: Rect, theme: &Theme) -> Result<(), Error> {
         match self.action {
-            CtrlActions::List => list_view(self, f, area),
-            CtrlActions::Edit => edit_view(self, f, area),
-            CtrlActions::Del => del_view(self, f, area),
-            CtrlActions::Detail => detail_view(self, f, area),
+            CtrlActions::List => list_view(self, f, area, theme),
+            CtrlActions::Edit => edit_view(self, f, area, theme),
+            CtrlActions::Del => del_view(self, f, area, theme),
+            CtrlActions::Detail => detail_view(self, f, area, theme),
         }
         Ok(())
     }
 
-    fn key_event_handler(&mut self, key: &KeyEvent) -> AppState {
+    fn key_context(&self) -> KeyContext {
+        match self.action {
+            CtrlActions::List => KeyContext::List,
+            CtrlActions::Edit if self.show_popup => KeyContext::Popup,
+            CtrlActions::Edit => KeyContext::Form,
+            CtrlActions::Detail if self.detail_input.is_some() => KeyContext::Form,
+            CtrlActions::Detail | CtrlActions::Del => KeyContext::Popup,
+        }
+    }
+
+    fn action_handler(&mut self, action: &Action) -> AppState {
+        match self.action {
+            CtrlActions::List => self.list_action_handler(action),
+            CtrlActions::Edit => self.edit_action_handler(action),
+            CtrlActions::Detail => self.detail_action_handler(action),
+            CtrlActions::Del => self.del_action_handler(action),
+        }
+    }
+
+    fn mouse_handler(&mut self, input: &MouseInput) -> AppState {
+        match self.action {
+            CtrlActions::List => self.list_mouse_handler(input),
+            CtrlActions::Edit if self.show_popup => self.popup_mouse_handler(input),
+            _ => AppState::Running,
+        }
+    }
+
+    fn editor_text(&self) -> Option<String> {
         match self.action {
-            CtrlActions::List => return self.list_key_event(key),
-            CtrlActions::Edit => return self.edit_key_event(key),
-            CtrlActions::Detail => return self.detail_key_event(key),
-            CtrlActions::Del => return self.del_key_event(key),
+            CtrlActions::Edit if self.input.is_multiline() && !self.show_popup => {
+                Some(self.input.value().to_string())
+            }
+            _ => None,
         }
     }
+
+    fn set_editor_text(&mut self, text: &str) {
+        self.input = TextInput::multiline(text, FORM_FIELD_WIDTH);
+        self.update_field();
+    }
 }
 
 impl TaskCtrl {
     pub fn task_list(&mut self) -> Result<Vec<HashMap<String, String>>, RuError> {
-        let custom_query = "select t.*, p.name as 'project_name', ts.name as 'status_name' from task as t left join project as p on (t.project_id = p.id) LEFT JOIN task_status as ts ON (t.status = ts.id)".to_string();
         match Self::get_db_connection() {
-            Ok(conn) => match Task::query(&conn, custom_query) {
-                Ok(list) => {
-                    self.record_count = list.len();
-                    Ok(list)
+            Ok(conn) => {
+                match Task::query(&conn, self.task_list_query(TaskStatus::done_id(&conn))) {
+                    Ok(mut list) => {
+                        self.record_count = list.len();
+                        // done tasks have no urgency
+                        let now = Local::now();
+                        for row in list.iter_mut() {
+                            let urgency = match row.get("is_done").map(|d| d.as_str()) {
+                                Some("0") => urgency::row_score(row, &now)
+                                    .map(|u| format!("{:.1}", u))
+                                    .unwrap_or_default(),
+                                _ => "".to_string(),
+                            };
+                            row.insert("urgency".to_string(), urgency);
+                        }
+                        Ok(list)
+                    }
+                    Err(e) => Err(e),
                 }
-                Err(e) => Err(e),
-            },
+            }
             Err(e) => {
                 //@TODO: show error popup
-                println!("error getting connection: {}", e.to_string());
+                log::error!("error getting connection: {}", e);
                 return Err(RuError::InvalidQuery);
             }
         }
     }
 
+    fn task_list_query(&self, done_id: i32) -> String {
+        let mut custom_query = format!("select t.*, p.name as 'project_name', ts.name as 'status_name', {}, {}, {}, {}, {}, {}, (t.status = {}) as 'is_done' from task as t left join project as p on (t.project_id = p.id) LEFT JOIN task_status as ts ON (t.status = ts.id)", ChecklistItem::progress_column("t"), Comment::count_column("t"), TaskAssignee::names_column("t"), TaskDependency::ids_column("t"), TaskDependency::blocked_column("t", done_id), TaskTag::names_column("t"), done_id);
+        if let Some(condition) = self.filter.condition() {
+            custom_query.push_str(&format!(" WHERE {}", condition));
+        }
+        custom_query
+    }
+
     pub fn project_list(&mut self) -> Result<Vec<HashMap<String, String>>, RuError> {
         let custom_query = "select id, name from project order by name".to_string();
         match Self::get_db_connection() {
@@ -109,18 +246,224 @@ impl TaskCtrl {
         }
     }
 
+    pub fn person_list(&self) -> Result<Vec<Person>, RuError> {
+        match Self::get_db_connection() {
+            Ok(conn) => Person::list(&conn, "".to_string()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// names of the people assigned to the task being shown or edited
+    pub fn get_assignee_names(&self) -> String {
+        match self.person_list() {
+            Ok(people) => {
+                let names: Vec<String> = people
+                    .iter()
+                    .filter(|p| self.assignees.contains(&p.id))
+                    .map(|p| p.full_name())
+                    .collect();
+                match names.is_empty() {
+                    true => "--".to_string(),
+                    false => names.join(", "),
+                }
+            }
+            Err(_e) => "--".to_string(),
+        }
+    }
+
+    pub fn load_assignees(&mut self) {
+        self.assignees = match Self::get_db_connection() {
+            Ok(conn) => match TaskAssignee::person_ids(&conn, self.task_table.id) {
+                Ok(ids) => ids,
+                Err(e) => {
+                    log::error!("failed to load assignees: {}", e);
+                    vec![]
+                }
+            },
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                vec![]
+            }
+        };
+    }
+
+    pub fn load_tags(&mut self) {
+        self.tags = match Self::get_db_connection() {
+            Ok(conn) => match TaskTag::names(&conn, self.task_table.id) {
+                Ok(names) => names.join(", "),
+                Err(e) => {
+                    log::error!("failed to load tags: {}", e);
+                    "".to_string()
+                }
+            },
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                "".to_string()
+            }
+        };
+    }
+
+    /// the other tasks of the project, those the task can depend on
+    pub fn dependency_list(&self) -> Result<Vec<Task>, RuError> {
+        match Self::get_db_connection() {
+            Ok(conn) => Task::list(
+                &conn,
+                format!(
+                    "project_id={} AND id<>{} ORDER BY id",
+                    self.task_table.project_id, self.task_table.id
+                ),
+            ),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// ids and names of the tasks the task being shown or edited depends on
+    pub fn get_dependency_names(&self) -> String {
+        match self.dependency_list() {
+            Ok(tasks) => {
+                let names: Vec<String> = tasks
+                    .iter()
+                    .filter(|t| self.depends_on.contains(&t.id))
+                    .map(|t| format!("#{} {}", t.id, t.name))
+                    .collect();
+                match names.is_empty() {
+                    true => "--".to_string(),
+                    false => names.join(", "),
+                }
+            }
+            Err(_e) => "--".to_string(),
+        }
+    }
+
+    /// id and name of the parent task (epic) of the task being shown or edited
+    pub fn get_parent_name(&self) -> String {
+        if self.task_table.parent_id == 0 {
+            return "--".to_string();
+        }
+        match Self::get_db_connection()
+            .and_then(|conn| Task::get_by_id(&conn, self.task_table.parent_id))
+        {
+            Ok(parent) => format!("#{} {}", parent.id, parent.name),
+            Err(_e) => "--".to_string(),
+        }
+    }
+
+    pub fn load_dependencies(&mut self) {
+        self.depends_on = match Self::get_db_connection() {
+            Ok(conn) => match TaskDependency::depends_on_ids(&conn, self.task_table.id) {
+                Ok(ids) => ids,
+                Err(e) => {
+                    log::error!("failed to load dependencies: {}", e);
+                    vec![]
+                }
+            },
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                vec![]
+            }
+        };
+    }
+
+    /// adds or removes the task highlighted in the dependency popup
+    fn toggle_dependency(&mut self) {
+        if let (Some(idx), Ok(tasks)) = (self.l_state.selected(), self.dependency_list()) {
+            if let Some(task) = tasks.get(idx) {
+                self.form_error = None;
+                match self.depends_on.iter().position(|id| *id == task.id) {
+                    Some(pos) => {
+                        self.depends_on.remove(pos);
+                    }
+                    None => match self.dependency_error(task.id) {
+                        Some(error) => self.form_error = Some(error),
+                        None => self.depends_on.push(task.id),
+                    },
+                }
+            }
+        }
+    }
+
+    /// why the task can't depend on `depends_on_id`: it is the task itself or waits for it
+    fn dependency_error(&self, depends_on_id: i32) -> Option<String> {
+        let task_id = self.task_table.id;
+        if depends_on_id == task_id {
+            return Some("a task can't depend on itself".to_string());
+        }
+        match Self::get_db_connection()
+            .and_then(|conn| TaskDependency::creates_cycle(&conn, task_id, depends_on_id))
+        {
+            Ok(false) => None,
+            Ok(true) => Some(format!(
+                "#{} already waits for this task, depending on it would make a loop",
+                depends_on_id
+            )),
+            Err(e) => {
+                log::error!("failed to check the dependencies: {}", e);
+                Some("failed to check the dependencies".to_string())
+            }
+        }
+    }
+
+    /// why `parent_id` can't be the parent of the task: it is the task itself or one of its
+    /// subtasks
+    fn parent_error(&self, parent_id: i32) -> Option<String> {
+        let task_id = self.task_table.id;
+        if parent_id != 0 && parent_id == task_id {
+            return Some("a task can't be its own parent".to_string());
+        }
+        match Self::get_db_connection()
+            .and_then(|conn| Task::parent_creates_cycle(&conn, task_id, parent_id))
+        {
+            Ok(false) => None,
+            Ok(true) => Some(format!(
+                "#{} is a subtask of this task, it can't be its parent",
+                parent_id
+            )),
+            Err(e) => {
+                log::error!("failed to check the parent: {}", e);
+                Some("failed to check the parent".to_string())
+            }
+        }
+    }
+
+    /// assigns or unassigns the person highlighted in the assignee popup
+    fn toggle_assignee(&mut self) {
+        if let (Some(idx), Ok(people)) = (self.l_state.selected(), self.person_list()) {
+            if let Some(person) = people.get(idx) {
+                match self.assignees.iter().position(|id| *id == person.id) {
+                    Some(pos) => {
+                        self.assignees.remove(pos);
+                    }
+                    None => self.assignees.push(person.id),
+                }
+            }
+        }
+    }
+
     pub fn update_field(&mut self) {
         match self.field_idx {
-            0 => self.task_table.project_id = self.input.parse().unwrap(),
-            1 => self.task_table.name = self.input.clone(),
-            2 => self.task_table.description = self.input.clone(),
-            3 => self.task_table.weight = self.input.parse().unwrap_or_default(),
-            4 => self.task_table.status = self.input.parse().unwrap(),
+            0 => self.task_table.project_id = self.input.value().parse().unwrap(),
+            1 => self.task_table.name = self.input.value().to_string(),
+            2 => self.task_table.description = self.input.value().to_string(),
+            3 => self.task_table.weight = self.input.value().parse().unwrap_or_default(),
+            4 => self.task_table.status = self.input.value().parse().unwrap(),
+            6 => self.task_table.due_date = self.input.value().trim().to_string(),
+            7 => self.task_table.start_date = self.input.value().trim().to_string(),
+            11 => self.tags = self.input.value().to_string(),
 
             _ => {}
         }
     }
 
+    pub fn get_creator_name(&self) -> String {
+        match Self::get_db_connection() {
+            Ok(conn) => match self.task_table.get_creator(&conn) {
+                Some(p) => p.full_name(),
+                None => "--".to_string(),
+            },
+            Err(_e) => "--".to_string(),
+        }
+    }
+
     pub fn get_status_name(&self) -> String {
         match Self::get_db_connection() {
             Ok(conn) => match self.task_table.get_status(&conn) {
@@ -147,17 +490,37 @@ impl TaskCtrl {
                 Ok(conn) => {
                     match self.task_table.del(&conn) {
                         Ok(()) => {
+                            if let Err(e) =
+                                ChecklistItem::delete_for_task(&conn, self.task_table.id)
+                            {
+                                log::error!("failed to delete checklist: {}", e);
+                            }
+                            if let Err(e) = Comment::delete_for_task(&conn, self.task_table.id) {
+                                log::error!("failed to delete comments: {}", e);
+                            }
+                            if let Err(e) = TaskAssignee::delete_for_task(&conn, self.task_table.id)
+                            {
+                                log::error!("failed to delete assignees: {}", e);
+                            }
+                            if let Err(e) =
+                                TaskDependency::delete_for_task(&conn, self.task_table.id)
+                            {
+                                log::error!("failed to delete dependencies: {}", e);
+                            }
+                            if let Err(e) = TaskTag::delete_for_task(&conn, self.task_table.id) {
+                                log::error!("failed to delete tags: {}", e);
+                            }
                             self.task_table = Task::default();
                             self.go_back();
                         }
                         Err(e) => {
                             //@TODO: show popup error
-                            println!("faile to save record {}", e.to_string());
+                            log::error!("failed to delete record: {}", e);
                         }
                     }
                 }
-                Err(_e) => {
-                    println!("ERROR NO DATABASE CONN");
+                Err(e) => {
+                    log::error!("no database connection: {}", e);
                     //@TODO: show popup error
                 }
             }
@@ -165,22 +528,72 @@ impl TaskCtrl {
     }
     pub fn save_task(&mut self) {
         //@TODO : validate fields before saving a show apropriate messages
+        self.form_error = None;
+        if !self.task_table.due_date.is_empty() && self.task_table.due().is_none() {
+            self.form_error = Some(format!(
+                "invalid due date '{}', expected YYYY-MM-DD",
+                self.task_table.due_date
+            ));
+            return;
+        }
+        if !self.task_table.start_date.is_empty() && self.task_table.start().is_none() {
+            self.form_error = Some(format!(
+                "invalid start date '{}', expected YYYY-MM-DD",
+                self.task_table.start_date
+            ));
+            return;
+        }
+        if let (Some(start), Some(due)) = (self.task_table.start(), self.task_table.due()) {
+            if start > due {
+                self.form_error = Some(format!(
+                    "the start date {} is after the due date {}",
+                    start, due
+                ));
+                return;
+            }
+        }
+        if let Some(error) = self
+            .depends_on
+            .iter()
+            .find_map(|id| self.dependency_error(*id))
+            .or_else(|| self.parent_error(self.task_table.parent_id))
+        {
+            self.form_error = Some(error);
+            return;
+        }
 
         match Self::get_db_connection() {
             Ok(conn) => {
                 match self.task_table.save(&conn) {
                     Ok(task) => {
+                        if let Err(e) = TaskAssignee::set_for_task(&conn, task.id, &self.assignees)
+                        {
+                            //@TODO: show popup error
+                            log::error!("failed to save assignees: {}", e);
+                        }
+                        if let Err(e) =
+                            TaskDependency::set_for_task(&conn, task.id, &self.depends_on)
+                        {
+                            //@TODO: show popup error
+                            log::error!("failed to save dependencies: {}", e);
+                        }
+                        if let Err(e) =
+                            TaskTag::set_for_task(&conn, task.id, &TaskTag::parse(&self.tags))
+                        {
+                            //@TODO: show popup error
+                            log::error!("failed to save tags: {}", e);
+                        }
                         self.task_table = task.clone();
                         self.go_back();
                     }
                     Err(e) => {
                         //@TODO: show popup error
-                        println!("faile to save record {}", e.to_string());
+                        log::error!("failed to save record: {}", e);
                     }
                 }
             }
-            Err(_e) => {
-                println!("ERROR NO DATABASE CONN");
+            Err(e) => {
+                log::error!("no database connection: {}", e);
                 //@TODO: show popup error
             }
         }
@@ -256,7 +669,9 @@ impl TaskCtrl {
                 match self.task_list() {
                     Ok(tasks) => {
                         self.task_table = Task::from_hashmap(&mut tasks[idx].clone()).unwrap();
-                        //.clone();
+                        self.load_assignees();
+                        self.load_dependencies();
+                        self.load_tags();
                     }
                     Err(_e) => {
                         //@TODO: show error message
@@ -269,41 +684,112 @@ impl TaskCtrl {
         }
     }
 
-    pub fn list_key_event(&mut self, key: &KeyEvent) -> AppState {
-        match key.code {
-            KeyCode::Char('n') => {
+    /// detail view of the task being shown, with its checklist and comments
+    fn show_detail(&mut self) {
+        self.action = CtrlActions::Detail;
+        self.detail_idx = 0;
+        self.load_checklist();
+        self.load_comments();
+    }
+
+    /// shows the task `id` on its own, used by the scrumboard to open a card in place
+    pub fn open_detail(&mut self, id: i32) {
+        match Self::get_db_connection().and_then(|conn| Task::get_by_id(&conn, id)) {
+            Ok(task) => {
+                self.task_table = task;
+                self.load_assignees();
+                self.load_dependencies();
+                self.load_tags();
+                self.show_detail();
+            }
+            Err(e) => {
+                //@TODO: show popup error
+                log::error!("failed to read task {}: {}", id, e);
+            }
+        }
+    }
+
+    /// the form of the task `id`, used by the scrumboard to edit a card in place
+    pub fn open_edit(&mut self, id: i32) {
+        self.open_detail(id);
+        if let CtrlActions::Detail = self.action {
+            self.action = CtrlActions::Edit;
+            self.set_active_field(0);
+        }
+    }
+
+    pub fn list_action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::New => {
                 self.action = CtrlActions::Edit;
                 self.task_table = Task::default();
+                self.task_table.project_id = config::get().defaults.project;
+                self.task_table.status = config::get().defaults.status;
+                self.task_table.priority = config::get().defaults.priority;
+                self.assignees = vec![];
+                self.depends_on = vec![];
+                self.tags = "".to_string();
+                self.set_active_field(0);
                 return AppState::MoveOn;
             }
-            KeyCode::Char('e') => {
+            Action::Edit => {
                 self.set_selected_record();
                 self.action = CtrlActions::Edit;
+                self.set_active_field(0);
                 return AppState::MoveOn;
             }
 
-            KeyCode::Char('d') => {
+            Action::Delete => {
                 self.set_selected_record();
                 self.action = CtrlActions::Del;
                 return AppState::MoveOn;
             }
 
-            KeyCode::Char('s') => {
+            Action::Show => {
                 self.set_selected_record();
-                self.action = CtrlActions::Detail;
+                self.show_detail();
                 return AppState::MoveOn;
             }
 
-            KeyCode::Up => {
+            Action::Up => {
                 self.previous_row();
                 return AppState::MoveOn;
             }
 
-            KeyCode::Down => {
+            Action::Down => {
                 self.next_row();
                 return AppState::MoveOn;
             }
 
+            Action::First => {
+                if self.record_count > 0 {
+                    self.t_state.select(Some(0));
+                }
+                return AppState::MoveOn;
+            }
+
+            Action::Last => {
+                if self.record_count > 0 {
+                    self.t_state.select(Some(self.record_count - 1));
+                }
+                return AppState::MoveOn;
+            }
+
+            Action::ToggleMine => {
+                self.filter = self.filter.next();
+                self.t_state.select(None);
+                return AppState::MoveOn;
+            }
+
+            Action::SetStatus(status_id) => {
+                if self.t_state.selected().is_some() {
+                    self.set_selected_record();
+                    self.task_table.status = status_id;
+                    self.save_task();
+                }
+                return AppState::MoveOn;
+            }
+
             _ => {
                 //println!("key pressed '{:?}'", key.code);
             }
@@ -312,19 +798,56 @@ impl TaskCtrl {
         AppState::Running
     }
 
+    pub fn list_mouse_handler(&mut self, input: &MouseInput) -> AppState {
+        match input.kind {
+            MouseKind::ScrollUp => self.list_action_handler(&Action::Up),
+            MouseKind::ScrollDown => self.list_action_handler(&Action::Down),
+            MouseKind::Click | MouseKind::DoubleClick => match input.hit(&self.row_areas) {
+                Some(idx) => {
+                    self.t_state.select(Some(idx));
+                    if input.kind == MouseKind::DoubleClick {
+                        return self.list_action_handler(&Action::Show);
+                    }
+                    AppState::MoveOn
+                }
+                None => AppState::Running,
+            },
+            _ => AppState::Running,
+        }
+    }
+
+    /// checks or unchecks the selected checkbox of the description and saves the task
+    pub fn toggle_checkbox(&mut self) {
+        self.task_table.description =
+            markdown::toggle_checkbox(&self.task_table.description, self.detail_idx);
+        match Self::get_db_connection() {
+            Ok(conn) => {
+                if let Err(e) = self.task_table.save(&conn) {
+                    //@TODO: show popup error
+                    log::error!("failed to save record: {}", e);
+                }
+            }
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                //@TODO: show popup error
+            }
+        }
+    }
+
     pub fn go_back(&mut self) {
-        self.input = "".to_string();
+        self.input.clear();
         self.field_idx = 0;
+        self.form_error = None;
         self.action = CtrlActions::List;
     }
 
-    pub fn del_key_event(&mut self, key: &KeyEvent) -> AppState {
-        match key.code {
-            KeyCode::Esc => {
+    pub fn del_action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::Cancel => {
                 self.go_back();
             }
 
-            KeyCode::Enter => {
+            Action::Confirm => {
                 self.del_task();
             }
 
@@ -335,14 +858,71 @@ impl TaskCtrl {
 
         AppState::MoveOn
     }
-    pub fn detail_key_event(&mut self, key: &KeyEvent) -> AppState {
-        match key.code {
-            KeyCode::Esc => {
+    pub fn detail_action_handler(&mut self, action: &Action) -> AppState {
+        if self.detail_input.is_some() {
+            return self.detail_input_handler(action);
+        }
+        let item_idx = self.checklist_item_idx();
+        let comment_idx = self.comment_idx();
+
+        match *action {
+            Action::Cancel => {
                 self.go_back();
             }
 
-            KeyCode::Enter => {
+            Action::Confirm => {
                 self.action = CtrlActions::Edit;
+                self.set_active_field(0);
+            }
+
+            Action::Up | Action::Down => {
+                let count = self.detail_items();
+                if count > 0 {
+                    self.detail_idx = match *action {
+                        Action::Up => (self.detail_idx + count - 1) % count,
+                        _ => (self.detail_idx + 1) % count,
+                    };
+                }
+                self.comment_state.select(self.comment_idx());
+            }
+
+            Action::ToggleCheck => match (item_idx, comment_idx) {
+                (Some(idx), _) => self.toggle_checklist_item(idx),
+                (None, None) => self.toggle_checkbox(),
+                _ => {}
+            },
+
+            Action::New => {
+                self.detail_input = Some((DetailInput::ChecklistItem, TextInput::default()));
+            }
+
+            Action::Comment => {
+                self.detail_input = Some((
+                    DetailInput::Comment(0),
+                    TextInput::multiline("", FORM_FIELD_WIDTH),
+                ));
+            }
+
+            Action::Edit => {
+                if let Some(comment) = comment_idx.and_then(|idx| self.own_comment(idx)) {
+                    self.detail_input = Some((
+                        DetailInput::Comment(comment.id),
+                        TextInput::multiline(&comment.body, FORM_FIELD_WIDTH),
+                    ));
+                }
+            }
+
+            Action::Delete => match (item_idx, comment_idx) {
+                (Some(idx), _) => self.delete_checklist_item(idx),
+                (_, Some(idx)) => self.delete_comment(idx),
+                _ => {}
+            },
+
+            Action::MovePrev | Action::MoveNext => {
+                if let Some(idx) = item_idx {
+                    let step = if *action == Action::MovePrev { -1 } else { 1 };
+                    self.move_checklist_item(idx, step);
+                }
             }
 
             _ => {
@@ -353,96 +933,391 @@ impl TaskCtrl {
         AppState::MoveOn
     }
 
-    pub fn popup_key_event(&mut self, key: &KeyEvent) -> AppState {
-        match key.code {
-            KeyCode::Up => {
+    /// keys typed while adding a checklist item or writing a comment
+    fn detail_input_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::Cancel => {
+                self.detail_input = None;
+            }
+
+            Action::Confirm => {
+                if let Some((target, input)) = self.detail_input.take() {
+                    let text = input.value().trim().to_string();
+                    match target {
+                        _ if text.is_empty() => {}
+                        DetailInput::ChecklistItem => self.add_checklist_item(&text),
+                        DetailInput::Comment(id) => self.save_comment(id, &text),
+                    }
+                }
+            }
+
+            action => {
+                if let Some((_, input)) = self.detail_input.as_mut() {
+                    input.handle_action(&action);
+                }
+            }
+        }
+
+        AppState::MoveOn
+    }
+
+    fn description_checkboxes(&self) -> usize {
+        markdown::checkboxes(&self.task_table.description).len()
+    }
+
+    /// index in `checklist` of the selected checkbox, None when it is one of the description
+    fn checklist_item_idx(&self) -> Option<usize> {
+        self.detail_idx
+            .checked_sub(self.description_checkboxes())
+            .filter(|idx| *idx < self.checklist.len())
+    }
+
+    /// number of selectable items in the detail view
+    fn detail_items(&self) -> usize {
+        self.description_checkboxes() + self.checklist.len() + self.comments.len()
+    }
+
+    /// index in `comments` of the selected item, None when it isn't a comment
+    fn comment_idx(&self) -> Option<usize> {
+        self.detail_idx
+            .checked_sub(self.description_checkboxes() + self.checklist.len())
+            .filter(|idx| *idx < self.comments.len())
+    }
+
+    /// the comment at `idx`, only when the current user wrote it
+    fn own_comment(&self, idx: usize) -> Option<Comment> {
+        let mut row = self.comments.get(idx)?.clone();
+        Comment::from_hashmap(&mut row)
+            .ok()
+            .filter(|comment| comment.author_id == current_user_id())
+    }
+
+    pub fn load_comments(&mut self) {
+        self.comments = match Self::get_db_connection() {
+            Ok(conn) => match Comment::thread(&conn, self.task_table.id) {
+                Ok(rows) => rows,
+                Err(e) => {
+                    log::error!("failed to load comments: {}", e);
+                    vec![]
+                }
+            },
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                vec![]
+            }
+        };
+        self.detail_idx = self.detail_idx.min(self.detail_items().saturating_sub(1));
+        self.comment_state.select(self.comment_idx());
+    }
+
+    /// adds a comment when `id` is 0, otherwise changes the text of the current user's comment
+    pub fn save_comment(&mut self, id: i32, body: &str) {
+        let comment = match id {
+            0 => Some(Comment::new(
+                0,
+                self.task_table.id,
+                current_user_id(),
+                body.to_string(),
+                0,
+                0,
+            )),
+            _ => self
+                .comments
+                .iter()
+                .position(|row| row.get("id") == Some(&id.to_string()))
+                .and_then(|idx| self.own_comment(idx))
+                .map(|mut comment| {
+                    comment.body = body.to_string();
+                    comment
+                }),
+        };
+        if let Some(comment) = comment {
+            match Self::get_db_connection() {
+                Ok(conn) => {
+                    if let Err(e) = comment.save(&conn) {
+                        //@TODO: show popup error
+                        log::error!("failed to save comment: {}", e);
+                    }
+                }
+                Err(e) => {
+                    log::error!("no database connection: {}", e);
+                    //@TODO: show popup error
+                }
+            }
+        }
+        self.load_comments();
+        if id == 0 {
+            // the thread is oldest first, select the new comment
+            self.detail_idx = self.detail_items().saturating_sub(1);
+            self.comment_state.select(self.comment_idx());
+        }
+    }
+
+    pub fn delete_comment(&mut self, idx: usize) {
+        if let Some(mut comment) = self.own_comment(idx) {
+            match Self::get_db_connection() {
+                Ok(conn) => {
+                    if let Err(e) = comment.del(&conn) {
+                        //@TODO: show popup error
+                        log::error!("failed to delete comment: {}", e);
+                    }
+                }
+                Err(e) => {
+                    log::error!("no database connection: {}", e);
+                    //@TODO: show popup error
+                }
+            }
+            self.load_comments();
+        }
+    }
+
+    pub fn load_checklist(&mut self) {
+        self.checklist = match Self::get_db_connection() {
+            Ok(conn) => match ChecklistItem::for_task(&conn, self.task_table.id) {
+                Ok(items) => items,
+                Err(e) => {
+                    log::error!("failed to load checklist: {}", e);
+                    vec![]
+                }
+            },
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                vec![]
+            }
+        };
+    }
+
+    /// saves `items` and reloads the checklist
+    fn save_checklist_items(&mut self, items: &[ChecklistItem]) {
+        match Self::get_db_connection() {
+            Ok(conn) => items.iter().for_each(|item| {
+                if let Err(e) = item.save(&conn) {
+                    //@TODO: show popup error
+                    log::error!("failed to save checklist item: {}", e);
+                }
+            }),
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                //@TODO: show popup error
+            }
+        }
+        self.load_checklist();
+    }
+
+    pub fn add_checklist_item(&mut self, text: &str) {
+        let position = self
+            .checklist
+            .iter()
+            .map(|item| item.position)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let item = ChecklistItem::new(0, self.task_table.id, text.to_string(), false, position);
+        self.save_checklist_items(&[item]);
+        self.detail_idx = self.description_checkboxes() + self.checklist.len().saturating_sub(1);
+    }
+
+    pub fn toggle_checklist_item(&mut self, idx: usize) {
+        let mut item = self.checklist[idx].clone();
+        item.done = !item.done;
+        self.save_checklist_items(&[item]);
+    }
+
+    /// swaps the item with the one `step` rows away, positions are renumbered in display order
+    pub fn move_checklist_item(&mut self, idx: usize, step: isize) {
+        let other = idx as isize + step;
+        if other < 0 || other >= self.checklist.len() as isize {
+            return;
+        }
+        let mut items = self.checklist.clone();
+        items.swap(idx, other as usize);
+        items
+            .iter_mut()
+            .enumerate()
+            .for_each(|(position, item)| item.position = position as i32 + 1);
+        self.save_checklist_items(&items);
+        self.detail_idx = (self.detail_idx as isize + step) as usize;
+    }
+
+    pub fn delete_checklist_item(&mut self, idx: usize) {
+        let mut item = self.checklist[idx].clone();
+        match Self::get_db_connection() {
+            Ok(conn) => {
+                if let Err(e) = item.del(&conn) {
+                    //@TODO: show popup error
+                    log::error!("failed to delete checklist item: {}", e);
+                }
+            }
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                //@TODO: show popup error
+            }
+        }
+        self.load_checklist();
+        self.detail_idx = self.detail_idx.min(self.detail_items().saturating_sub(1));
+    }
+
+    pub fn popup_action_handler(&mut self, action: &Action) -> AppState {
+        match *action {
+            Action::Up => {
                 self.previous_item();
-                return AppState::MoveOn;
             }
 
-            KeyCode::Down => {
+            Action::Down => {
                 self.next_item();
-                return AppState::MoveOn;
             }
 
-            KeyCode::Esc => {
-                self.show_popup = false;
-                return AppState::MoveOn;
+            Action::Cancel => {
+                self.close_popup();
             }
 
-            KeyCode::Enter => {
+            Action::ToggleCheck => match self.popup_type {
+                PopupTaskType::AssigneeList => self.toggle_assignee(),
+                PopupTaskType::DependencyList => self.toggle_dependency(),
+                _ => {}
+            },
+
+            Action::Confirm => {
                 match self.popup_type {
-                    PopupTaskType::ProjectList => match self.l_state.selected() {
-                        Some(idx) => match self.project_list() {
-                            Ok(results) => {
-                                let project_id =
-                                    results[idx].get("id").unwrap().parse::<i32>().unwrap();
-                                self.task_table.project_id = project_id;
-                                self.show_popup = false;
-                                self.popup_type = PopupTaskType::NoPopup;
+                    PopupTaskType::ProjectList => {
+                        if let Some(idx) = self.l_state.selected() {
+                            match self.project_list() {
+                                Ok(results) => {
+                                    if let Some(project_id) = results
+                                        .get(idx)
+                                        .and_then(|row| row.get("id"))
+                                        .and_then(|id| id.parse::<i32>().ok())
+                                    {
+                                        self.task_table.project_id = project_id;
+                                        self.close_popup();
+                                    }
+                                }
+                                Err(e) => log::error!("failed to load the projects: {}", e),
                             }
-                            Err(e) => {}
-                        },
-                        None => {}
-                    },
-                    PopupTaskType::TaskStatusList => match self.l_state.selected() {
-                        Some(idx) => match self.task_status_list() {
-                            Ok(results) => {
-                                let status_id =
-                                    results[idx].get("id").unwrap().parse::<i32>().unwrap();
-                                self.task_table.status = status_id;
-                                self.show_popup = false;
-                                self.popup_type = PopupTaskType::NoPopup;
+                        }
+                    }
+                    PopupTaskType::EstimateList => {
+                        let choices = EstimateScale::configured().choices().unwrap_or_default();
+                        if let Some((_, weight)) =
+                            self.l_state.selected().and_then(|idx| choices.get(idx))
+                        {
+                            self.task_table.weight = *weight;
+                            self.close_popup();
+                        }
+                    }
+                    PopupTaskType::AssigneeList => {
+                        self.toggle_assignee();
+                        self.close_popup();
+                    }
+                    PopupTaskType::DependencyList => {
+                        self.toggle_dependency();
+                        self.close_popup();
+                    }
+                    PopupTaskType::ParentList => {
+                        // the first entry is for no parent
+                        if let (Some(idx), Ok(tasks)) =
+                            (self.l_state.selected(), self.dependency_list())
+                        {
+                            let parent_id = match idx {
+                                0 => 0,
+                                _ => tasks.get(idx - 1).map(|t| t.id).unwrap_or(0),
+                            };
+                            self.form_error = self.parent_error(parent_id);
+                            if self.form_error.is_none() {
+                                self.task_table.parent_id = parent_id;
                             }
-                            Err(e) => {}
-                        },
-                        None => {}
-                    },
+                        }
+                        self.close_popup();
+                    }
+                    PopupTaskType::PriorityList => {
+                        if let Some(idx) = self.l_state.selected() {
+                            self.task_table.priority = idx as i32;
+                        }
+                        self.close_popup();
+                    }
+                    PopupTaskType::TaskStatusList => {
+                        if let Some(idx) = self.l_state.selected() {
+                            match self.task_status_list() {
+                                Ok(results) => {
+                                    if let Some(status_id) = results
+                                        .get(idx)
+                                        .and_then(|row| row.get("id"))
+                                        .and_then(|id| id.parse::<i32>().ok())
+                                    {
+                                        self.task_table.status = status_id;
+                                        self.close_popup();
+                                    }
+                                }
+                                Err(e) => log::error!("failed to load the task statuses: {}", e),
+                            }
+                        }
+                    }
                     _ => {}
                 }
-
-                return AppState::MoveOn;
             }
 
-            _ => {
-                return AppState::MoveOn;
+            _ => {}
+        }
+        AppState::MoveOn
+    }
+    /// the popups share `l_state`, its selection goes with the popup so the next one can't
+    /// start past the end of its list
+    fn close_popup(&mut self) {
+        self.show_popup = false;
+        self.popup_type = PopupTaskType::NoPopup;
+        self.l_state.select(None);
+    }
+
+    pub fn popup_mouse_handler(&mut self, input: &MouseInput) -> AppState {
+        match input.kind {
+            MouseKind::ScrollUp => self.popup_action_handler(&Action::Up),
+            MouseKind::ScrollDown => self.popup_action_handler(&Action::Down),
+            MouseKind::Click | MouseKind::DoubleClick => {
+                if let Some(idx) = input.hit(&self.popup_areas) {
+                    self.l_state.select(Some(idx));
+                    if input.kind == MouseKind::DoubleClick {
+                        return self.popup_action_handler(&Action::Confirm);
+                    }
+                }
+                AppState::MoveOn
             }
+            _ => AppState::MoveOn,
         }
     }
-    pub fn edit_key_event(&mut self, key: &KeyEvent) -> AppState {
+
+    pub fn edit_action_handler(&mut self, action: &Action) -> AppState {
         if self.show_popup {
-            return self.popup_key_event(key);
+            return self.popup_action_handler(action);
         }
-        match key.code {
-            KeyCode::Tab => {
+        match *action {
+            Action::NextField => {
                 self.set_next_active();
             }
 
-            KeyCode::Esc => {
+            Action::PrevField => {
+                self.set_prev_active();
+            }
+
+            Action::Cancel => {
                 self.go_back();
                 //@TODO: reset all fields when to empty if we were creating a new task or to the
                 //fields original values of the selected task
             }
 
-            KeyCode::Backspace => {
-                self.input.pop();
-                self.update_field();
-            }
-
-            KeyCode::Enter => {
+            Action::Confirm => {
                 self.save_task();
             }
 
-            KeyCode::Char(c) => {
+            Action::Insert(c) => {
                 match self.field_idx {
                     0 => {
                         // show popup with project lists to select from and set project_id to the
                         // project.id from selected project
                         if c == ' ' {
-                            match self.project_list() {
-                                Ok(results) => self.record_count = results.len(),
-                                Err(_e) => self.record_count = 0,
-                            }
+                            let results = self.project_list().unwrap_or_default();
+                            self.record_count = results.len();
+                            self.l_state
+                                .select(row_of_id(&results, self.task_table.project_id));
                             self.popup_type = PopupTaskType::ProjectList;
                             self.show_popup = true;
                         }
@@ -452,50 +1327,161 @@ impl TaskCtrl {
                         // show popup with project lists to select from and set project_id to the
                         // project.id from selected project
                         if c == ' ' {
-                            match self.task_status_list() {
+                            let results = self.task_status_list().unwrap_or_default();
+                            self.record_count = results.len();
+                            self.l_state
+                                .select(row_of_id(&results, self.task_table.status));
+                            self.popup_type = PopupTaskType::TaskStatusList;
+                            self.show_popup = true;
+                        }
+                    }
+
+                    3 if self.picks_estimate() => {
+                        // estimates on a scale are picked from a popup, hours are typed
+                        if c == ' ' {
+                            let choices = EstimateScale::configured().choices().unwrap_or_default();
+                            self.record_count = choices.len();
+                            self.l_state.select(
+                                choices
+                                    .iter()
+                                    .position(|(_, weight)| *weight == self.task_table.weight)
+                                    .or(Some(0)),
+                            );
+                            self.popup_type = PopupTaskType::EstimateList;
+                            self.show_popup = true;
+                        }
+                    }
+
+                    5 => {
+                        // people are picked from a popup, several can be assigned
+                        if c == ' ' {
+                            match self.person_list() {
                                 Ok(results) => self.record_count = results.len(),
                                 Err(_e) => self.record_count = 0,
                             }
-                            self.popup_type = PopupTaskType::TaskStatusList;
+                            self.l_state.select(match self.record_count {
+                                0 => None,
+                                _ => Some(0),
+                            });
+                            self.popup_type = PopupTaskType::AssigneeList;
+                            self.show_popup = true;
+                        }
+                    }
+
+                    8 => {
+                        // other tasks of the project are picked from a popup, several can be
+                        // picked
+                        if c == ' ' {
+                            match self.dependency_list() {
+                                Ok(results) => self.record_count = results.len(),
+                                Err(_e) => self.record_count = 0,
+                            }
+                            self.l_state.select(match self.record_count {
+                                0 => None,
+                                _ => Some(0),
+                            });
+                            self.popup_type = PopupTaskType::DependencyList;
+                            self.show_popup = true;
+                        }
+                    }
+
+                    9 => {
+                        // the parent (epic) is another task of the project, picked from a popup
+                        if c == ' ' {
+                            let tasks = self.dependency_list().unwrap_or_default();
+                            self.record_count = tasks.len() + 1;
+                            self.l_state.select(Some(
+                                tasks
+                                    .iter()
+                                    .position(|t| t.id == self.task_table.parent_id)
+                                    .map_or(0, |idx| idx + 1),
+                            ));
+                            self.popup_type = PopupTaskType::ParentList;
+                            self.show_popup = true;
+                        }
+                    }
+
+                    10 => {
+                        if c == ' ' {
+                            self.record_count = PRIORITIES.len();
+                            self.l_state.select(Some(self.task_table.priority as usize));
+                            self.popup_type = PopupTaskType::PriorityList;
                             self.show_popup = true;
                         }
                     }
 
                     _ => {
-                        self.input.push(c);
+                        self.input.insert(c);
                         self.update_field();
                     }
                 }
             }
 
-            _ => {
-                //println!("key pressed '{:?}'", key.code);
+            action => {
+                // project, status, assignee, dependency, parent, priority and estimate fields are
+                // picked from a popup, not typed
+                let picked = [0, 4, 5, 8, 9, 10].contains(&self.field_idx)
+                    || (self.field_idx == 3 && self.picks_estimate());
+                if !picked && self.input.handle_action(&action) {
+                    self.update_field();
+                }
             }
         }
 
         AppState::MoveOn
     }
 
+    /// the estimate field opens a picker unless the scale is typed
+    pub fn picks_estimate(&self) -> bool {
+        EstimateScale::configured().choices().is_some()
+    }
+
     pub fn set_next_active(&mut self) {
-        match self.action {
-            CtrlActions::Edit => {
-                self.field_idx = self.field_idx + 1;
-                if self.field_idx == 5 {
-                    self.field_idx = 0;
-                }
-                self.input = match self.field_idx {
-                    //0 => self.task_table.project_id.to_string(),
-                    //1 => self.task_table.parent_id.clone(),
-                    0 => "".to_string(),
-                    1 => self.task_table.name.clone(),
-                    2 => self.task_table.description.clone(),
-                    3 => self.task_table.weight.to_string(),
-                    4 => "".to_string(),
-                    _ => "".to_string(),
-                }
-            }
+        if let CtrlActions::Edit = self.action {
+            self.set_active_field((self.field_idx + 1) % 12);
+        }
+    }
 
-            _ => {}
+    pub fn set_prev_active(&mut self) {
+        if let CtrlActions::Edit = self.action {
+            self.set_active_field((self.field_idx + 12 - 1) % 12);
         }
     }
+
+    fn set_active_field(&mut self, field_idx: u32) {
+        self.field_idx = field_idx;
+        let value = match self.field_idx {
+            //0 => self.task_table.project_id.to_string(),
+            //1 => self.task_table.parent_id.clone(),
+            0 => "".to_string(),
+            1 => self.task_table.name.clone(),
+            2 => self.task_table.description.clone(),
+            3 if !self.picks_estimate() && self.task_table.weight > 0 => {
+                self.task_table.weight.to_string()
+            }
+            3 => "".to_string(),
+            4 => "".to_string(),
+            6 => self.task_table.due_date.clone(),
+            7 => self.task_table.start_date.clone(),
+            11 => self.tags.clone(),
+            _ => "".to_string(),
+        };
+        self.input = match self.field_idx {
+            2 => TextInput::multiline(&value, FORM_FIELD_WIDTH),
+            _ => TextInput::new(&value),
+        };
+    }
+}
+
+/// row of `rows` holding the record `id`, the first row when none does and None when there are
+/// no rows, so a popup never starts on an index left by another one
+fn row_of_id(rows: &[HashMap<String, String>], id: i32) -> Option<usize> {
+    match rows.is_empty() {
+        true => None,
+        false => Some(
+            rows.iter()
+                .position(|row| row.get("id") == Some(&id.to_string()))
+                .unwrap_or(0),
+        ),
+    }
 }
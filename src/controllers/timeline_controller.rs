@@ -0,0 +1,280 @@
+use super::CtrObj;
+use crate::app::AppState;
+use crate::keymap::{Action, KeyContext};
+use crate::models::{
+    project::Project, task::Task, task::DUE_DATE_FORMAT, task_dependency::TaskDependency,
+    task_status::TaskStatus, DbObj,
+};
+use crate::views::theme::Theme;
+use crate::views::timeline::*;
+use crate::UtilFns;
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use ratatui::{layout::Rect, Frame};
+use rusqlite::{Connection, Error as RuError};
+use std::io::Error;
+
+/// how much time one column of the timeline stands for, 'z' goes through them in this order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimelineZoom {
+    Day,
+    Week,
+    Month,
+}
+
+impl TimelineZoom {
+    pub fn next(&self) -> TimelineZoom {
+        match self {
+            TimelineZoom::Day => TimelineZoom::Week,
+            TimelineZoom::Week => TimelineZoom::Month,
+            TimelineZoom::Month => TimelineZoom::Day,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimelineZoom::Day => "day",
+            TimelineZoom::Week => "week",
+            TimelineZoom::Month => "month",
+        }
+    }
+
+    /// first day of the column `day` falls in
+    pub fn column_start(&self, day: NaiveDate) -> NaiveDate {
+        match self {
+            TimelineZoom::Day => day,
+            TimelineZoom::Week => day - Duration::days(day.weekday().num_days_from_monday() as i64),
+            TimelineZoom::Month => day.with_day(1).unwrap_or(day),
+        }
+    }
+
+    /// first day of the column `columns` columns after the one starting on `start`
+    pub fn add_columns(&self, start: NaiveDate, columns: i64) -> NaiveDate {
+        match self {
+            TimelineZoom::Day => start + Duration::days(columns),
+            TimelineZoom::Week => start + Duration::days(columns * 7),
+            TimelineZoom::Month => {
+                let month0 = start.year() as i64 * 12 + start.month0() as i64 + columns;
+                NaiveDate::from_ymd_opt(
+                    month0.div_euclid(12) as i32,
+                    month0.rem_euclid(12) as u32 + 1,
+                    1,
+                )
+                .unwrap_or(start)
+            }
+        }
+    }
+}
+
+/// a bar of the timeline, a project or a task
+#[derive(Debug, Clone)]
+pub struct TimelineRow {
+    pub id: i32,
+    pub label: String,
+    pub start: Option<NaiveDate>,
+    /// last day, included
+    pub end: Option<NaiveDate>,
+    pub done: bool,
+    /// ids of the rows this one waits for
+    pub depends_on: Vec<i32>,
+}
+
+impl TimelineRow {
+    pub fn is_overdue(&self) -> bool {
+        match self.end {
+            Some(end) => !self.done && end < Local::now().date_naive(),
+            None => false,
+        }
+    }
+}
+
+/// projects, or the tasks of one of them, as bars on a time axis
+#[derive(Debug, Clone)]
+pub struct TimelineCtrl {
+    /// None shows one row per project
+    pub project: Option<Project>,
+    pub rows: Vec<TimelineRow>,
+    pub selected: usize,
+    /// index of the project row to select when going back to the projects
+    pub project_idx: usize,
+    pub zoom: TimelineZoom,
+    /// first day of the leftmost column
+    pub origin: NaiveDate,
+    /// number of columns that fit on screen, set while rendering
+    pub visible_columns: i64,
+    /// rows are read once, when the tab opens or the project changes
+    pub loaded: bool,
+}
+
+impl Default for TimelineCtrl {
+    fn default() -> TimelineCtrl {
+        TimelineCtrl {
+            project: None,
+            rows: vec![],
+            selected: 0,
+            project_idx: 0,
+            zoom: TimelineZoom::Week,
+            origin: Local::now().date_naive(),
+            visible_columns: 0,
+            loaded: false,
+        }
+    }
+}
+
+impl UtilFns for TimelineCtrl {}
+
+impl CtrObj for TimelineCtrl {
+    fn init_data(&mut self) {
+        if !self.loaded {
+            self.loaded = true;
+            self.load_rows();
+            self.fit_origin();
+        }
+    }
+
+    fn display(&mut self, f: &mut Frame, area: Rect, theme: &Theme) -> Result<(), Error> {
+        timeline_view(self, f, area, theme);
+        Ok(())
+    }
+
+    fn key_context(&self) -> KeyContext {
+        KeyContext::Board
+    }
+
+    fn action_handler(&mut self, action: &Action) -> AppState {
+        let count = self.rows.len().max(1);
+        let step = (self.visible_columns / 4).max(1);
+        match action {
+            Action::Up => self.selected = (self.selected + count - 1) % count,
+            Action::Down => self.selected = (self.selected + 1) % count,
+            Action::Left => self.origin = self.zoom.add_columns(self.origin, -step),
+            Action::Right => self.origin = self.zoom.add_columns(self.origin, step),
+            Action::First => self.center_on(Local::now().date_naive()),
+            Action::Zoom => {
+                self.zoom = self.zoom.next();
+                match self.rows.get(self.selected).and_then(|row| row.start) {
+                    Some(start) => self.center_on(start),
+                    None => self.fit_origin(),
+                }
+            }
+            Action::Confirm if self.project.is_none() => self.open_project(),
+            Action::Cancel if self.project.is_some() => {
+                self.project = None;
+                self.load_rows();
+                self.selected = self.project_idx.min(self.rows.len().saturating_sub(1));
+                self.fit_origin();
+            }
+            _ => return AppState::Running,
+        }
+        AppState::MoveOn
+    }
+}
+
+impl TimelineCtrl {
+    pub fn load_rows(&mut self) {
+        let conn = match Self::get_db_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                log::error!("no database connection: {}", e);
+                //@TODO: show popup error
+                return;
+            }
+        };
+        let done_id = TaskStatus::done_id(&conn);
+        let rows = match &self.project {
+            Some(project) => Self::task_rows(&conn, project.id, done_id),
+            None => Self::project_rows(&conn, done_id),
+        };
+        match rows {
+            Ok(rows) => self.rows = rows,
+            Err(e) => {
+                //@TODO: show popup error
+                log::error!("failed to read the timeline: {}", e);
+            }
+        }
+    }
+
+    /// a task starts on its start date and ends on its due date, a task with only one of them
+    /// lasts a day
+    fn task_rows(
+        conn: &Connection,
+        project_id: i32,
+        done_id: i32,
+    ) -> Result<Vec<TimelineRow>, RuError> {
+        let dependencies = TaskDependency::for_project(conn, project_id)?;
+        let tasks = Task::list(
+            conn,
+            format!(
+                "project_id={} ORDER BY coalesce(nullif(start_date, ''), nullif(due_date, ''), '9999'), id",
+                project_id
+            ),
+        )?;
+        Ok(tasks
+            .iter()
+            .map(|task| TimelineRow {
+                id: task.id,
+                label: format!("#{} {}", task.id, task.name),
+                start: task.start().or_else(|| task.due()),
+                end: task.due().or_else(|| task.start()),
+                done: task.status == done_id,
+                depends_on: dependencies
+                    .iter()
+                    .filter(|d| d.task_id == task.id)
+                    .map(|d| d.depends_on_id)
+                    .collect(),
+            })
+            .collect())
+    }
+
+    /// a project spans its own dates, or those of its tasks when it has none
+    fn project_rows(conn: &Connection, done_id: i32) -> Result<Vec<TimelineRow>, RuError> {
+        let projects = Project::list(conn, "id > 0 ORDER BY name".to_string())?;
+        let mut rows = vec![];
+        for project in projects {
+            let tasks = Self::task_rows(conn, project.id, done_id)?;
+            let parse = |date: &str| NaiveDate::parse_from_str(date, DUE_DATE_FORMAT).ok();
+            rows.push(TimelineRow {
+                id: project.id,
+                label: project.name.clone(),
+                start: parse(&project.start_date)
+                    .or_else(|| tasks.iter().filter_map(|t| t.start).min()),
+                end: parse(&project.end_date).or_else(|| tasks.iter().filter_map(|t| t.end).max()),
+                done: !tasks.is_empty() && tasks.iter().all(|t| t.done),
+                depends_on: vec![],
+            });
+        }
+        Ok(rows)
+    }
+
+    /// shows the tasks of the selected project
+    fn open_project(&mut self) {
+        let id = match self.rows.get(self.selected) {
+            Some(row) => row.id,
+            None => return,
+        };
+        match Self::get_db_connection().and_then(|conn| Project::get_by_id(&conn, id)) {
+            Ok(project) => {
+                self.project_idx = self.selected;
+                self.project = Some(project);
+                self.selected = 0;
+                self.load_rows();
+                self.fit_origin();
+            }
+            Err(e) => {
+                //@TODO: show popup error
+                log::error!("failed to read project {}: {}", id, e);
+            }
+        }
+    }
+
+    /// starts the timeline a column before the earliest bar, or a little before today
+    pub fn fit_origin(&mut self) {
+        let first = self.rows.iter().filter_map(|row| row.start).min();
+        let day = first.unwrap_or_else(|| Local::now().date_naive());
+        self.origin = self.zoom.add_columns(self.zoom.column_start(day), -1);
+    }
+
+    fn center_on(&mut self, day: NaiveDate) {
+        let half = (self.visible_columns / 2).max(1);
+        self.origin = self.zoom.add_columns(self.zoom.column_start(day), -half);
+    }
+}
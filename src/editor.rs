@@ -0,0 +1,37 @@
+use std::env;
+use std::fs;
+use std::io::Error;
+use std::process::{self, Command};
+
+/// editor command, `$VISUAL` then `$EDITOR` then vi
+fn editor_command() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// writes `text` to a temp file, opens it in the user's editor and returns the saved text.
+/// the terminal has to be released before calling this
+pub fn edit_text(text: &str) -> Result<String, Error> {
+    let path = env::temp_dir().join(format!("rask-{}.md", process::id()));
+    fs::write(&path, format!("{}\n", text))?;
+
+    let command = editor_command();
+    let mut parts = command.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    log::debug!("editing {} with '{}'", path.display(), command);
+
+    let result = match Command::new(program).args(parts).arg(&path).status() {
+        Ok(status) if status.success() => fs::read_to_string(&path)
+            .map(|edited| edited.replace("\r\n", "\n").trim_end().to_string()),
+        Ok(status) => Err(Error::other(format!(
+            "'{}' exited with {}",
+            command, status
+        ))),
+        Err(e) => Err(e),
+    };
+    let _ = fs::remove_file(&path);
+    result
+}
@@ -0,0 +1,71 @@
+use crate::config;
+
+pub const ESTIMATE_SCALES: [&str; 3] = ["fibonacci", "tshirt", "hours"];
+const FIBONACCI: [i32; 7] = [1, 2, 3, 5, 8, 13, 21];
+const TSHIRT: [(&str, i32); 6] = [
+    ("XS", 1),
+    ("S", 2),
+    ("M", 3),
+    ("L", 5),
+    ("XL", 8),
+    ("XXL", 13),
+];
+
+/// how the weight of a task is picked and shown, set with `estimates.scale`. a weight of 0 means
+/// the task isn't estimated
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EstimateScale {
+    Fibonacci,
+    TShirt,
+    Hours,
+}
+
+impl EstimateScale {
+    pub fn from_name(name: &str) -> Option<EstimateScale> {
+        match name {
+            "fibonacci" => Some(EstimateScale::Fibonacci),
+            "tshirt" => Some(EstimateScale::TShirt),
+            "hours" => Some(EstimateScale::Hours),
+            _ => None,
+        }
+    }
+
+    pub fn configured() -> EstimateScale {
+        EstimateScale::from_name(&config::get().estimates.scale).unwrap_or(EstimateScale::Fibonacci)
+    }
+
+    /// label and weight of the values offered in the picker, None when the weight is typed
+    pub fn choices(&self) -> Option<Vec<(String, i32)>> {
+        let values: Vec<(String, i32)> = match self {
+            EstimateScale::Fibonacci => FIBONACCI.iter().map(|v| (v.to_string(), *v)).collect(),
+            EstimateScale::TShirt => TSHIRT.iter().map(|(l, v)| (l.to_string(), *v)).collect(),
+            EstimateScale::Hours => return None,
+        };
+        let mut choices = vec![("--".to_string(), 0)];
+        choices.extend(values);
+        Some(choices)
+    }
+
+    pub fn label(&self, weight: i32) -> String {
+        if weight <= 0 {
+            return "--".to_string();
+        }
+        match self {
+            EstimateScale::Fibonacci => weight.to_string(),
+            EstimateScale::TShirt => TSHIRT
+                .iter()
+                .find(|(_, v)| *v == weight)
+                .map(|(l, _)| l.to_string())
+                .unwrap_or(weight.to_string()),
+            EstimateScale::Hours => format!("{}h", weight),
+        }
+    }
+
+    /// a sum of weights, ex: "13 pts" or "6h"
+    pub fn total_label(&self, total: i32) -> String {
+        match self {
+            EstimateScale::Hours => format!("{}h", total),
+            _ => format!("{} pts", total),
+        }
+    }
+}
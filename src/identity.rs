@@ -0,0 +1,127 @@
+use crate::config;
+use crate::models::{person::Person, DbObj};
+use crate::UtilFns;
+use rusqlite::{params, Connection, Error, OptionalExtension};
+use std::process::Command;
+use std::sync::Mutex;
+
+/// `setting` row holding the id of the `Person` picked in the contacts tab
+const CURRENT_USER_SETTING: &str = "current_user";
+
+/// resolved once and then kept until `set_current_user` changes it
+static CURRENT_USER: Mutex<Option<i32>> = Mutex::new(None);
+
+#[derive(Debug, Clone)]
+struct Identity;
+
+impl UtilFns for Identity {}
+
+/// `Person` id of whoever is using rask, 0 when unknown
+pub fn current_user_id() -> i32 {
+    let mut cached = CURRENT_USER.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(id) = *cached {
+        return id;
+    }
+    let id = match Identity::get_db_connection() {
+        Ok(conn) => resolve(&conn),
+        Err(e) => {
+            log::error!("no database connection: {}", e);
+            0
+        }
+    };
+    log::info!("current user is person {}", id);
+    *cached = Some(id);
+    id
+}
+
+/// remembers `person_id` as the current user in the database
+pub fn set_current_user(conn: &Connection, person_id: i32) -> Result<(), Error> {
+    conn.execute(
+        "INSERT OR REPLACE INTO setting (name, value) VALUES (?, ?)",
+        params![CURRENT_USER_SETTING, person_id.to_string()],
+    )?;
+    *CURRENT_USER.lock().unwrap_or_else(|e| e.into_inner()) = Some(person_id);
+    Ok(())
+}
+
+/// the configured `user_email` wins, then the person picked in the contacts tab and then the
+/// email of `git config`, creating that person if needed
+fn resolve(conn: &Connection) -> i32 {
+    if let Some(email) = &config::get().user_email {
+        return match person_by_email(conn, email) {
+            Some(person) => person.id,
+            None => {
+                log::warn!("no contact has the configured user_email '{}'", email);
+                0
+            }
+        };
+    }
+    if let Some(id) = stored_user(conn) {
+        return id;
+    }
+    match git_config("user.email") {
+        Some(email) => match person_by_email(conn, &email) {
+            Some(person) => person.id,
+            None => create_git_user(conn, &email),
+        },
+        None => 0,
+    }
+}
+
+fn stored_user(conn: &Connection) -> Option<i32> {
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM setting WHERE name = ?",
+            params![CURRENT_USER_SETTING],
+            |row| row.get(0),
+        )
+        .optional()
+        .unwrap_or_else(|e| {
+            log::error!("failed to read the current user: {}", e);
+            None
+        });
+    value
+        .and_then(|v| v.parse::<i32>().ok())
+        .filter(|id| Person::get_by_id(conn, *id).is_ok())
+}
+
+fn person_by_email(conn: &Connection, email: &str) -> Option<Person> {
+    Person::list(
+        conn,
+        format!(
+            "lower(email) = lower('{}') ORDER BY id",
+            email.replace('\'', "''")
+        ),
+    )
+    .ok()
+    .and_then(|people| people.into_iter().next())
+}
+
+/// adds a contact named after `git config user.name` for `email`
+fn create_git_user(conn: &Connection, email: &str) -> i32 {
+    let name = git_config("user.name").unwrap_or_default();
+    let (first_name, last_name) = match name.split_once(' ') {
+        Some((first, last)) => (first.to_string(), last.trim().to_string()),
+        None => (name.clone(), "".to_string()),
+    };
+    let person = Person::new(0, first_name, last_name, email.to_string());
+    match person.save(conn) {
+        Ok(person) => {
+            log::info!("added contact {} for git user {}", person.id, email);
+            person.id
+        }
+        Err(e) => {
+            log::error!("failed to add contact for git user {}: {}", email, e);
+            0
+        }
+    }
+}
+
+fn git_config(key: &str) -> Option<String> {
+    let output = Command::new("git").args(["config", key]).output().ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    match output.status.success() && !value.is_empty() {
+        true => Some(value),
+        false => None,
+    }
+}
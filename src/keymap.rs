@@ -0,0 +1,827 @@
+use crate::config::KeymapConfig;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::fmt;
+
+/// everything a user can ask rask to do from the keyboard, controllers only handle actions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    NextMenu,
+    PrevMenu,
+    ToggleDebug,
+    Up,
+    Down,
+    Left,
+    Right,
+    First,
+    Last,
+    New,
+    Edit,
+    Delete,
+    Show,
+    Confirm,
+    Cancel,
+    NextField,
+    PrevField,
+    DeleteBack,
+    DeleteForward,
+    DeleteWord,
+    DeleteToStart,
+    NewLine,
+    OpenEditor,
+    ToggleCheck,
+    Comment,
+    ToggleMine,
+    SetMe,
+    MoveNext,
+    MovePrev,
+    MoveUp,
+    MoveDown,
+    CloseSprint,
+    ToggleSprint,
+    Chart,
+    Export,
+    Reschedule,
+    Zoom,
+    Swimlanes,
+    Help,
+    Palette,
+    /// a character typed in a form field, never bound in the keymap
+    Insert(char),
+    /// select a project by id, sent by the command palette
+    JumpTo(i32),
+    /// change the status of the selected item, sent by the command palette
+    SetStatus(i32),
+}
+
+/// which set of bindings is active, depends on what the current controller is showing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyContext {
+    Global,
+    List,
+    Form,
+    Popup,
+    Board,
+    Palette,
+}
+
+/// bindable actions with the name used in the config file and a short description
+pub const ACTIONS: [(Action, &str, &str); 41] = [
+    (Action::Quit, "quit", "quit rask"),
+    (Action::NextMenu, "next_menu", "go to next menu item"),
+    (Action::PrevMenu, "prev_menu", "go to previous menu item"),
+    (
+        Action::ToggleDebug,
+        "toggle_debug",
+        "toggle the debug log pane",
+    ),
+    (Action::Up, "up", "move up"),
+    (Action::Down, "down", "move down"),
+    (Action::Left, "left", "move left"),
+    (Action::Right, "right", "move right"),
+    (Action::First, "first", "go to first item"),
+    (Action::Last, "last", "go to last item"),
+    (Action::New, "new", "create new record"),
+    (Action::Edit, "edit", "edit selected record"),
+    (Action::Delete, "delete", "delete selected record"),
+    (Action::Show, "show", "show selected record"),
+    (Action::Confirm, "confirm", "confirm / save"),
+    (Action::Cancel, "cancel", "cancel / go back"),
+    (Action::NextField, "next_field", "go to next field"),
+    (Action::PrevField, "prev_field", "go to previous field"),
+    (
+        Action::DeleteBack,
+        "delete_back",
+        "delete previous character",
+    ),
+    (
+        Action::DeleteForward,
+        "delete_forward",
+        "delete character under the cursor",
+    ),
+    (
+        Action::DeleteWord,
+        "delete_word",
+        "delete word before the cursor",
+    ),
+    (
+        Action::DeleteToStart,
+        "delete_to_start",
+        "delete from the cursor to the start of the field",
+    ),
+    (
+        Action::NewLine,
+        "new_line",
+        "insert a line break in a description",
+    ),
+    (
+        Action::OpenEditor,
+        "open_editor",
+        "edit the description in $EDITOR",
+    ),
+    (
+        Action::ToggleCheck,
+        "toggle_check",
+        "check or uncheck the selected checkbox",
+    ),
+    (Action::Comment, "comment", "add a comment to the task"),
+    (
+        Action::ToggleMine,
+        "toggle_mine",
+        "list all tasks, those assigned to me or those created by me",
+    ),
+    (
+        Action::SetMe,
+        "set_me",
+        "tell rask the selected contact is me",
+    ),
+    (
+        Action::MoveNext,
+        "move_next",
+        "move scrumboard item to next column",
+    ),
+    (
+        Action::MovePrev,
+        "move_prev",
+        "move scrumboard item to previous column",
+    ),
+    (
+        Action::MoveUp,
+        "move_up",
+        "move scrumboard item up in its column",
+    ),
+    (
+        Action::MoveDown,
+        "move_down",
+        "move scrumboard item down in its column",
+    ),
+    (
+        Action::CloseSprint,
+        "close_sprint",
+        "close the selected sprint, unfinished tasks move on",
+    ),
+    (
+        Action::ToggleSprint,
+        "toggle_sprint",
+        "show the active sprint or the whole project on the scrumboard",
+    ),
+    (
+        Action::Chart,
+        "chart",
+        "show the burndown chart of the selected project",
+    ),
+    (
+        Action::Export,
+        "export",
+        "save the chart as text and svg files",
+    ),
+    (
+        Action::Reschedule,
+        "reschedule",
+        "change the due date of the selected task",
+    ),
+    (
+        Action::Zoom,
+        "zoom",
+        "show days, weeks or months on the timeline",
+    ),
+    (
+        Action::Swimlanes,
+        "swimlanes",
+        "group the scrumboard in lanes by assignee, tag, parent task or priority",
+    ),
+    (Action::Help, "help", "show help for the current screen"),
+    (Action::Palette, "palette", "open the command palette"),
+];
+
+pub const CONTEXTS: [(KeyContext, &str); 6] = [
+    (KeyContext::Global, "global"),
+    (KeyContext::List, "list"),
+    (KeyContext::Form, "form"),
+    (KeyContext::Popup, "popup"),
+    (KeyContext::Board, "board"),
+    (KeyContext::Palette, "palette"),
+];
+
+impl Action {
+    pub fn from_name(name: &str) -> Option<Action> {
+        ACTIONS.iter().find(|a| a.1 == name).map(|a| a.0)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Insert(_) => "insert",
+            Action::JumpTo(_) => "jump_to",
+            Action::SetStatus(_) => "set_status",
+            action => ACTIONS
+                .iter()
+                .find(|a| a.0 == *action)
+                .map(|a| a.1)
+                .unwrap_or(""),
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Insert(_) => "type text",
+            Action::JumpTo(_) => "jump to project",
+            Action::SetStatus(_) => "change status of selected item",
+            action => ACTIONS
+                .iter()
+                .find(|a| a.0 == *action)
+                .map(|a| a.2)
+                .unwrap_or(""),
+        }
+    }
+}
+
+impl KeyContext {
+    pub fn from_name(name: &str) -> Option<KeyContext> {
+        CONTEXTS.iter().find(|c| c.1 == name).map(|c| c.0)
+    }
+
+    pub fn name(&self) -> &'static str {
+        CONTEXTS
+            .iter()
+            .find(|c| c.0 == *self)
+            .map(|c| c.1)
+            .unwrap_or("")
+    }
+
+    /// contexts where unbound printable keys are typed as text
+    pub fn takes_text(&self) -> bool {
+        matches!(self, KeyContext::Form | KeyContext::Palette)
+    }
+}
+
+/// a single key press, shift is folded into the character for printable keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyPress {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyPress {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> KeyPress {
+        match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => KeyPress {
+                code: KeyCode::Char(c.to_ascii_uppercase()),
+                modifiers: modifiers - KeyModifiers::SHIFT,
+            },
+            KeyCode::BackTab => KeyPress {
+                code,
+                modifiers: modifiers - KeyModifiers::SHIFT,
+            },
+            _ => KeyPress { code, modifiers },
+        }
+    }
+
+    /// parses keys like `q`, `Q`, `ctrl-p`, `alt-enter`, `shift-up` or `f12`
+    pub fn parse(spec: &str) -> Result<KeyPress, String> {
+        if spec.is_empty() {
+            return Err("empty key".to_string());
+        }
+        let mut modifiers = KeyModifiers::NONE;
+        let mut parts: Vec<&str> = spec.split('-').collect();
+        // a trailing '-' means the key itself is the minus sign
+        let key = if spec.ends_with('-') {
+            parts.truncate(parts.len().saturating_sub(2));
+            "-"
+        } else {
+            parts.pop().unwrap_or("")
+        };
+        for modifier in parts {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "c" => KeyModifiers::CONTROL,
+                "alt" | "a" | "m" => KeyModifiers::ALT,
+                "shift" | "s" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier '{}' in '{}'", modifier, spec)),
+            };
+        }
+
+        let code = match key.to_lowercase().as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            lower => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => match lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                        Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                        _ => return Err(format!("unknown key '{}'", spec)),
+                    },
+                }
+            }
+        };
+
+        Ok(KeyPress::new(code, modifiers))
+    }
+
+    /// true for keys that should be typed as text in a form
+    pub fn as_text(&self) -> Option<char> {
+        match self.code {
+            KeyCode::Char(c)
+                if !self
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                Some(c)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<&KeyEvent> for KeyPress {
+    fn from(key: &KeyEvent) -> KeyPress {
+        KeyPress::new(key.code, key.modifiers)
+    }
+}
+
+impl fmt::Display for KeyPress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::BackTab => write!(f, "Shift-Tab"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// a sequence of key presses bound to an action, ex: `g g`
+pub type Chord = Vec<KeyPress>;
+
+pub fn parse_chord(spec: &str) -> Result<Chord, String> {
+    let chord: Result<Chord, String> = spec.split_whitespace().map(KeyPress::parse).collect();
+    match chord {
+        Ok(keys) if keys.is_empty() => Err("empty key".to_string()),
+        other => other,
+    }
+}
+
+pub fn chord_to_string(chord: &Chord) -> String {
+    chord
+        .iter()
+        .map(|k| k.to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// outcome of feeding a key press to the keymap
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyResolution {
+    Action(Action),
+    /// the key started a chord, wait for the next one
+    Pending,
+    Unbound,
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    pub bindings: HashMap<KeyContext, Vec<(Chord, Action)>>,
+    pending: Chord,
+}
+
+impl Default for Keymap {
+    fn default() -> Keymap {
+        Keymap::from_preset("default")
+    }
+}
+
+impl Keymap {
+    pub fn from_preset(preset: &str) -> Keymap {
+        let mut keymap = Keymap {
+            bindings: HashMap::new(),
+            pending: vec![],
+        };
+        let defaults: Vec<(KeyContext, &str, Action)> = vec![
+            (KeyContext::Global, "q", Action::Quit),
+            (KeyContext::Global, "tab", Action::NextMenu),
+            (KeyContext::Global, "backtab", Action::PrevMenu),
+            (KeyContext::Global, "f12", Action::ToggleDebug),
+            (KeyContext::Global, "?", Action::Help),
+            (KeyContext::Global, "f1", Action::Help),
+            (KeyContext::Global, ":", Action::Palette),
+            (KeyContext::Global, "ctrl-p", Action::Palette),
+            (KeyContext::List, "up", Action::Up),
+            (KeyContext::List, "down", Action::Down),
+            (KeyContext::List, "home", Action::First),
+            (KeyContext::List, "end", Action::Last),
+            (KeyContext::List, "n", Action::New),
+            (KeyContext::List, "e", Action::Edit),
+            (KeyContext::List, "d", Action::Delete),
+            (KeyContext::List, "s", Action::Show),
+            (KeyContext::List, "m", Action::ToggleMine),
+            (KeyContext::List, "i", Action::SetMe),
+            (KeyContext::List, "c", Action::CloseSprint),
+            (KeyContext::Board, "up", Action::Up),
+            (KeyContext::Board, "down", Action::Down),
+            (KeyContext::Board, "left", Action::Left),
+            (KeyContext::Board, "right", Action::Right),
+            (KeyContext::Board, "home", Action::First),
+            (KeyContext::Board, "end", Action::Last),
+            (KeyContext::Board, "n", Action::New),
+            (KeyContext::Board, "e", Action::Edit),
+            (KeyContext::Board, "d", Action::Delete),
+            (KeyContext::Board, "shift-up", Action::MoveUp),
+            (KeyContext::Board, "shift-down", Action::MoveDown),
+            (KeyContext::Board, "N", Action::MoveNext),
+            (KeyContext::Board, "P", Action::MovePrev),
+            (KeyContext::Board, "s", Action::ToggleSprint),
+            (KeyContext::Board, "b", Action::Chart),
+            (KeyContext::Board, "r", Action::Reschedule),
+            (KeyContext::Board, "z", Action::Zoom),
+            (KeyContext::Board, "w", Action::Swimlanes),
+            (KeyContext::Board, "space", Action::ToggleCheck),
+            (KeyContext::Board, "enter", Action::Confirm),
+            (KeyContext::Board, "esc", Action::Cancel),
+            (KeyContext::Form, "tab", Action::NextField),
+            (KeyContext::Form, "backtab", Action::PrevField),
+            (KeyContext::Form, "enter", Action::Confirm),
+            (KeyContext::Form, "esc", Action::Cancel),
+            (KeyContext::Form, "backspace", Action::DeleteBack),
+            (KeyContext::Form, "delete", Action::DeleteForward),
+            (KeyContext::Form, "ctrl-w", Action::DeleteWord),
+            (KeyContext::Form, "ctrl-u", Action::DeleteToStart),
+            (KeyContext::Form, "left", Action::Left),
+            (KeyContext::Form, "right", Action::Right),
+            (KeyContext::Form, "home", Action::First),
+            (KeyContext::Form, "end", Action::Last),
+            (KeyContext::Form, "up", Action::Up),
+            (KeyContext::Form, "down", Action::Down),
+            (KeyContext::Form, "alt-enter", Action::NewLine),
+            (KeyContext::Form, "ctrl-j", Action::NewLine),
+            (KeyContext::Form, "ctrl-e", Action::OpenEditor),
+            (KeyContext::Popup, "up", Action::Up),
+            (KeyContext::Popup, "down", Action::Down),
+            (KeyContext::Popup, "enter", Action::Confirm),
+            (KeyContext::Popup, "esc", Action::Cancel),
+            (KeyContext::Popup, "space", Action::ToggleCheck),
+            (KeyContext::Popup, "x", Action::ToggleCheck),
+            (KeyContext::Popup, "a", Action::New),
+            (KeyContext::Popup, "d", Action::Delete),
+            (KeyContext::Popup, "c", Action::Comment),
+            (KeyContext::Popup, "e", Action::Edit),
+            (KeyContext::Popup, "s", Action::Export),
+            (KeyContext::Popup, "shift-up", Action::MovePrev),
+            (KeyContext::Popup, "shift-down", Action::MoveNext),
+            (KeyContext::Palette, "up", Action::Up),
+            (KeyContext::Palette, "down", Action::Down),
+            (KeyContext::Palette, "ctrl-k", Action::Up),
+            (KeyContext::Palette, "ctrl-j", Action::Down),
+            (KeyContext::Palette, "enter", Action::Confirm),
+            (KeyContext::Palette, "esc", Action::Cancel),
+            (KeyContext::Palette, "ctrl-p", Action::Cancel),
+            (KeyContext::Palette, "backspace", Action::DeleteBack),
+            (KeyContext::Palette, "delete", Action::DeleteForward),
+            (KeyContext::Palette, "ctrl-w", Action::DeleteWord),
+            (KeyContext::Palette, "ctrl-u", Action::DeleteToStart),
+            (KeyContext::Palette, "left", Action::Left),
+            (KeyContext::Palette, "right", Action::Right),
+            (KeyContext::Palette, "home", Action::First),
+            (KeyContext::Palette, "end", Action::Last),
+        ];
+        let vim: Vec<(KeyContext, &str, Action)> = vec![
+            (KeyContext::List, "k", Action::Up),
+            (KeyContext::List, "j", Action::Down),
+            (KeyContext::List, "g g", Action::First),
+            (KeyContext::List, "G", Action::Last),
+            (KeyContext::Board, "k", Action::Up),
+            (KeyContext::Board, "j", Action::Down),
+            (KeyContext::Board, "h", Action::Left),
+            (KeyContext::Board, "l", Action::Right),
+            (KeyContext::Board, "g g", Action::First),
+            (KeyContext::Board, "G", Action::Last),
+            (KeyContext::Board, "K", Action::MoveUp),
+            (KeyContext::Board, "J", Action::MoveDown),
+            (KeyContext::Popup, "k", Action::Up),
+            (KeyContext::Popup, "j", Action::Down),
+        ];
+
+        defaults
+            .iter()
+            .chain(vim.iter().filter(|_| preset == "vim"))
+            .for_each(|(ctx, key, action)| {
+                keymap.bind(*ctx, parse_chord(key).unwrap(), *action);
+            });
+        keymap
+    }
+
+    /// builds the keymap from the preset and applies the user bindings on top.
+    /// bindings are named `action` (rebinds the action everywhere it is bound) or
+    /// `context.action` (only in that context).
+    pub fn from_config(cfg: &KeymapConfig) -> Result<Keymap, String> {
+        let mut keymap = Keymap::from_preset(&cfg.preset);
+        let mut names: Vec<&String> = cfg.bindings.keys().collect();
+        // plain action names first so `context.action` entries win
+        names.sort_by_key(|name| name.contains('.'));
+        for name in names {
+            let spec = &cfg.bindings[name];
+            let chord = parse_chord(spec)
+                .map_err(|e| format!("config: keymap.bindings.{}: {}", name, e))?;
+            let (ctx, action_name) =
+                match name.split_once('.') {
+                    Some((ctx, action)) => match KeyContext::from_name(ctx) {
+                        Some(ctx) => (Some(ctx), action),
+                        None => {
+                            return Err(format!(
+                            "config: keymap.bindings.{}: unknown context '{}', expected one of: {}",
+                            name,
+                            ctx,
+                            CONTEXTS.iter().map(|c| c.1).collect::<Vec<&str>>().join(", ")
+                        ))
+                        }
+                    },
+                    None => (None, name.as_str()),
+                };
+            let action = Action::from_name(action_name).ok_or(format!(
+                "config: keymap.bindings.{}: unknown action '{}', expected one of: {}",
+                name,
+                action_name,
+                ACTIONS
+                    .iter()
+                    .map(|a| a.1)
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            ))?;
+
+            match ctx {
+                Some(ctx) => keymap.rebind(ctx, chord, action),
+                None => {
+                    let mut contexts: Vec<KeyContext> = keymap
+                        .bindings
+                        .iter()
+                        .filter(|(_, binds)| binds.iter().any(|(_, a)| *a == action))
+                        .map(|(ctx, _)| *ctx)
+                        .collect();
+                    if contexts.is_empty() {
+                        contexts.push(KeyContext::Global);
+                    }
+                    contexts
+                        .into_iter()
+                        .for_each(|ctx| keymap.rebind(ctx, chord.clone(), action));
+                }
+            }
+        }
+        Ok(keymap)
+    }
+
+    pub fn bind(&mut self, ctx: KeyContext, chord: Chord, action: Action) {
+        let binds = self.bindings.entry(ctx).or_default();
+        binds.retain(|(c, _)| *c != chord);
+        binds.push((chord, action));
+    }
+
+    /// replaces every binding of `action` in `ctx` with `chord`
+    pub fn rebind(&mut self, ctx: KeyContext, chord: Chord, action: Action) {
+        if let Some(binds) = self.bindings.get_mut(&ctx) {
+            binds.retain(|(_, a)| *a != action);
+        }
+        self.bind(ctx, chord, action);
+    }
+
+    /// bindings of a context followed by the global ones it doesn't shadow
+    pub fn context_bindings(&self, ctx: KeyContext) -> Vec<(Chord, Action)> {
+        let mut rv: Vec<(Chord, Action)> = self.bindings.get(&ctx).cloned().unwrap_or_default();
+        if ctx != KeyContext::Global {
+            if let Some(global) = self.bindings.get(&KeyContext::Global) {
+                let inherited: Vec<(Chord, Action)> = global
+                    .iter()
+                    .filter(|(chord, _)| !rv.iter().any(|(c, _)| c == chord))
+                    .filter(|(chord, _)| {
+                        !ctx.takes_text() || chord.iter().all(|k| k.as_text().is_none())
+                    })
+                    .cloned()
+                    .collect();
+                rv.extend(inherited);
+            }
+        }
+        rv
+    }
+
+    /// keys bound to `action` in `ctx`, used by footers and help screens
+    pub fn keys_for(&self, ctx: KeyContext, action: Action) -> Vec<String> {
+        self.context_bindings(ctx)
+            .iter()
+            .filter(|(_, a)| *a == action)
+            .map(|(chord, _)| chord_to_string(chord))
+            .collect()
+    }
+
+    pub fn reset_pending(&mut self) {
+        self.pending.clear();
+    }
+
+    pub fn resolve(&mut self, ctx: KeyContext, key: &KeyEvent) -> KeyResolution {
+        let press = KeyPress::from(key);
+        let binds = self.context_bindings(ctx);
+        self.pending.push(press);
+
+        if let Some((_, action)) = binds.iter().find(|(chord, _)| *chord == self.pending) {
+            self.pending.clear();
+            return KeyResolution::Action(*action);
+        }
+        if binds
+            .iter()
+            .any(|(chord, _)| chord.len() > self.pending.len() && chord.starts_with(&self.pending))
+        {
+            return KeyResolution::Pending;
+        }
+
+        let was_chord = self.pending.len() > 1;
+        self.pending.clear();
+        if was_chord {
+            // the chord didn't match, start over with the last key alone
+            return self.resolve(ctx, key);
+        }
+        match press.as_text() {
+            Some(c) if ctx.takes_text() => KeyResolution::Action(Action::Insert(c)),
+            _ => KeyResolution::Unbound,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(spec: &str) -> KeyEvent {
+        let press = KeyPress::parse(spec).unwrap();
+        KeyEvent::new(press.code, press.modifiers)
+    }
+
+    fn resolve_all(keymap: &mut Keymap, ctx: KeyContext, specs: &[&str]) -> Vec<KeyResolution> {
+        specs
+            .iter()
+            .map(|spec| keymap.resolve(ctx, &key(spec)))
+            .collect()
+    }
+
+    #[test]
+    fn parse_keys() {
+        assert_eq!(
+            KeyPress::parse("ctrl-p"),
+            Ok(KeyPress::new(KeyCode::Char('p'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            KeyPress::parse("alt-enter"),
+            Ok(KeyPress::new(KeyCode::Enter, KeyModifiers::ALT))
+        );
+        assert_eq!(
+            KeyPress::parse("ctrl--"),
+            Ok(KeyPress::new(KeyCode::Char('-'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(KeyPress::parse("f12").unwrap().code, KeyCode::F(12));
+        assert!(KeyPress::parse("f13").is_err());
+        assert!(KeyPress::parse("hyper-x").is_err());
+        assert!(KeyPress::parse("").is_err());
+        assert!(parse_chord("  ").is_err());
+        assert_eq!(parse_chord("g g").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn shift_is_folded_into_characters() {
+        assert_eq!(KeyPress::parse("shift-n"), KeyPress::parse("N"));
+        assert_eq!(
+            KeyPress::from(&KeyEvent::new(KeyCode::Char('n'), KeyModifiers::SHIFT)),
+            KeyPress::new(KeyCode::Char('N'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            KeyPress::parse("shift-up").unwrap().modifiers,
+            KeyModifiers::SHIFT
+        );
+        assert_eq!(KeyPress::parse("ctrl-p").unwrap().to_string(), "Ctrl-p");
+    }
+
+    #[test]
+    fn chords_wait_for_their_next_key() {
+        let mut keymap = Keymap::from_preset("vim");
+        assert_eq!(
+            resolve_all(&mut keymap, KeyContext::List, &["g", "g"]),
+            vec![KeyResolution::Pending, KeyResolution::Action(Action::First)]
+        );
+        // a key that doesn't continue the chord is resolved on its own
+        assert_eq!(
+            resolve_all(&mut keymap, KeyContext::List, &["g", "j"]),
+            vec![KeyResolution::Pending, KeyResolution::Action(Action::Down)]
+        );
+        assert_eq!(
+            resolve_all(&mut keymap, KeyContext::List, &["g", "x"]),
+            vec![KeyResolution::Pending, KeyResolution::Unbound]
+        );
+        keymap.resolve(KeyContext::List, &key("g"));
+        keymap.reset_pending();
+        assert_eq!(
+            keymap.resolve(KeyContext::List, &key("G")),
+            KeyResolution::Action(Action::Last)
+        );
+    }
+
+    #[test]
+    fn contexts_inherit_global_bindings() {
+        let mut keymap = Keymap::default();
+        assert_eq!(
+            keymap.resolve(KeyContext::List, &key("q")),
+            KeyResolution::Action(Action::Quit)
+        );
+        assert_eq!(
+            keymap.resolve(KeyContext::Board, &key("ctrl-p")),
+            KeyResolution::Action(Action::Palette)
+        );
+        // printable keys are text in forms, other global keys still work there
+        assert_eq!(
+            keymap.resolve(KeyContext::Form, &key("q")),
+            KeyResolution::Action(Action::Insert('q'))
+        );
+        assert_eq!(
+            keymap.resolve(KeyContext::Form, &key("f12")),
+            KeyResolution::Action(Action::ToggleDebug)
+        );
+        // the palette shadows the global ctrl-p
+        assert_eq!(
+            keymap.resolve(KeyContext::Palette, &key("ctrl-p")),
+            KeyResolution::Action(Action::Cancel)
+        );
+        assert_eq!(
+            keymap.resolve(KeyContext::List, &key("x")),
+            KeyResolution::Unbound
+        );
+    }
+
+    fn config(bindings: &[(&str, &str)]) -> KeymapConfig {
+        KeymapConfig {
+            bindings: bindings
+                .iter()
+                .map(|(name, keys)| (name.to_string(), keys.to_string()))
+                .collect(),
+            ..KeymapConfig::default()
+        }
+    }
+
+    #[test]
+    fn config_rebinds_actions() {
+        let keymap = Keymap::from_config(&config(&[("quit", "ctrl-q")])).unwrap();
+        assert_eq!(
+            keymap.keys_for(KeyContext::List, Action::Quit),
+            vec!["Ctrl-q"]
+        );
+
+        // an action rebinds everywhere it is bound, unless the context is given
+        let keymap =
+            Keymap::from_config(&config(&[("up", "ctrl-k"), ("board.up", "alt-k")])).unwrap();
+        assert_eq!(
+            keymap.keys_for(KeyContext::List, Action::Up),
+            vec!["Ctrl-k"]
+        );
+        assert_eq!(
+            keymap.keys_for(KeyContext::Popup, Action::Up),
+            vec!["Ctrl-k"]
+        );
+        assert_eq!(
+            keymap.keys_for(KeyContext::Board, Action::Up),
+            vec!["Alt-k"]
+        );
+
+        let keymap = Keymap::from_config(&config(&[("list.first", "g g")])).unwrap();
+        assert_eq!(
+            keymap.keys_for(KeyContext::List, Action::First),
+            vec!["g g"]
+        );
+    }
+
+    #[test]
+    fn config_errors_name_the_binding() {
+        let err = Keymap::from_config(&config(&[("fly", "f")])).unwrap_err();
+        assert!(err.starts_with("config: keymap.bindings.fly: unknown action 'fly'"));
+        let err = Keymap::from_config(&config(&[("menu.up", "k")])).unwrap_err();
+        assert!(err.starts_with("config: keymap.bindings.menu.up: unknown context 'menu'"));
+        let err = Keymap::from_config(&config(&[("quit", "hyper-q")])).unwrap_err();
+        assert!(err.starts_with("config: keymap.bindings.quit: unknown modifier"));
+    }
+}
@@ -1,9 +1,20 @@
 use rusqlite::{params, Connection, Result};
 use std::env;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 pub mod app;
+pub mod burndown;
+pub mod config;
 pub mod controllers;
+pub mod editor;
+pub mod estimate;
+pub mod identity;
+pub mod keymap;
+pub mod logger;
 pub mod models;
+pub mod mouse;
+pub mod stats;
+pub mod urgency;
 pub mod views;
 /*
 use crossterm::{
@@ -58,7 +69,10 @@ pub fn init_db(conn: &Connection) -> Result<usize> {
         */
         match rv {
             Ok(_n) => (),
-            Err(ref _e) => br = 1,
+            Err(ref e) => {
+                log::error!("failed to init db with '{}': {}", val, e);
+                br = 1
+            }
         }
 
         if br > 0 {
@@ -69,12 +83,57 @@ pub fn init_db(conn: &Connection) -> Result<usize> {
     rv
 }
 
+/// schema changes made after the tables of `init_db`, `PRAGMA user_version` holds how many of
+/// them were applied. only append to this list
+const MIGRATIONS: [&str; 12] = [
+    "CREATE TABLE IF NOT EXISTS task_checklist_item (id INTEGER PRIMARY KEY, task_id INTEGER NOT NULL, text TEXT NOT NULL, done INTEGER NOT NULL DEFAULT 0, position INTEGER NOT NULL DEFAULT 0)",
+    "CREATE TABLE IF NOT EXISTS task_comment (id INTEGER PRIMARY KEY, task_id INTEGER NOT NULL, author_id INTEGER NOT NULL DEFAULT 0, body TEXT NOT NULL, created_at INTEGER, updated_at INTEGER)",
+    "CREATE TABLE IF NOT EXISTS task_assignee (id INTEGER PRIMARY KEY, task_id INTEGER NOT NULL, person_id INTEGER NOT NULL, UNIQUE (task_id, person_id))",
+    "CREATE TABLE IF NOT EXISTS setting (name TEXT PRIMARY KEY, value TEXT NOT NULL)",
+    "CREATE TABLE IF NOT EXISTS sprint (id INTEGER PRIMARY KEY, project_id INTEGER NOT NULL, name TEXT NOT NULL, goal TEXT NOT NULL DEFAULT '', start_date TEXT NOT NULL DEFAULT '', end_date TEXT NOT NULL DEFAULT '', closed INTEGER NOT NULL DEFAULT 0, created_at INTEGER, updated_at INTEGER)",
+    "ALTER TABLE task ADD COLUMN sprint_id INTEGER NOT NULL DEFAULT 0",
+    "CREATE TABLE IF NOT EXISTS task_status_change (id INTEGER PRIMARY KEY, task_id INTEGER NOT NULL, from_status INTEGER NOT NULL, to_status INTEGER NOT NULL, changed_at INTEGER NOT NULL);
+    INSERT INTO task_status_change (task_id, from_status, to_status, changed_at) SELECT id, 0, status, coalesce(updated_at, created_at, 0) FROM task;
+    CREATE TRIGGER IF NOT EXISTS task_status_created AFTER INSERT ON task BEGIN INSERT INTO task_status_change (task_id, from_status, to_status, changed_at) VALUES (new.id, 0, new.status, CAST(strftime('%s', 'now') AS INTEGER)); END;
+    CREATE TRIGGER IF NOT EXISTS task_status_changed AFTER UPDATE OF status ON task WHEN old.status <> new.status BEGIN INSERT INTO task_status_change (task_id, from_status, to_status, changed_at) VALUES (new.id, old.status, new.status, CAST(strftime('%s', 'now') AS INTEGER)); END;
+    CREATE TRIGGER IF NOT EXISTS task_status_deleted AFTER DELETE ON task BEGIN DELETE FROM task_status_change WHERE task_id = old.id; END;",
+    "ALTER TABLE task ADD COLUMN due_date TEXT NOT NULL DEFAULT ''",
+    "ALTER TABLE task ADD COLUMN start_date TEXT NOT NULL DEFAULT '';
+    CREATE TABLE IF NOT EXISTS task_dependency (id INTEGER PRIMARY KEY, task_id INTEGER NOT NULL, depends_on_id INTEGER NOT NULL, UNIQUE (task_id, depends_on_id))",
+    "ALTER TABLE task ADD COLUMN position INTEGER NOT NULL DEFAULT 0",
+    "ALTER TABLE task ADD COLUMN priority INTEGER NOT NULL DEFAULT 2",
+    "CREATE TABLE IF NOT EXISTS task_tag (id INTEGER PRIMARY KEY, task_id INTEGER NOT NULL, name TEXT NOT NULL, UNIQUE (task_id, name))",
+];
+
+/// applies the migrations the database doesn't have yet, each one in a transaction with its
+/// `user_version` bump so a failed migration leaves the database as it was
+pub fn migrate_db(conn: &Connection) -> Result<()> {
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (idx, sql) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        log::info!("applying database migration {}", idx + 1);
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", idx as i64 + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
 pub fn db_open(db_path: String) -> Result<Connection> {
     Connection::open(db_path)
 }
 
 pub fn start_db(home_dir: String) -> Result<Connection> {
-    let db_path = Path::new(home_dir.as_str()).join("rask.db");
+    start_db_file(Path::new(home_dir.as_str()).join("rask.db"))
+}
+
+/// opens the sqlite file at `db_path`, creating and initializing it if needed
+pub fn start_db_file(db_path: PathBuf) -> Result<Connection> {
+    if let Some(parent) = db_path.parent() {
+        if !parent.as_os_str().is_empty() && !parent.exists() {
+            let _ = fs::create_dir_all(parent);
+        }
+    }
     let mut do_init_db = false;
     if !db_path.exists() {
         do_init_db = true;
@@ -84,22 +143,56 @@ pub fn start_db(home_dir: String) -> Result<Connection> {
         None => panic!("invalid db path"),
     };
 
-    let connection_rv = db_open(String::from(path_str));
+    let connection_rv = db_open(path_str.clone());
     match connection_rv {
         Ok(conn) => {
             if do_init_db {
+                log::info!("initializing new database at {}", path_str);
                 let _init_result = init_db(&conn);
             }
+            if let Err(e) = migrate_db(&conn) {
+                log::error!("failed to migrate db {}: {}", path_str, e);
+                return Err(e);
+            }
 
             Ok(conn)
         }
         Err(e) => {
-            println!("Failed to connect to db: {:?}", e);
+            log::error!("failed to connect to db {}: {}", path_str, e);
             Err(e)
         }
     }
 }
 
+/// sqlite file of the app, `db_path` of the config or rask.db in the working directory
+pub fn db_file_path() -> PathBuf {
+    match config::get().db_file() {
+        Some(db_path) => db_path,
+        None => env::current_dir()
+            .expect("No home dir defined in this system")
+            .join("rask.db"),
+    }
+}
+
+/// creates and migrates the database once at startup, connections opened later use it as it is
+pub fn prepare_db() -> Result<()> {
+    start_db_file(db_file_path()).map(|_| ())
+}
+
+pub use identity::current_user_id;
+
+/// directory where rask keeps its own files (logs, ...), created if missing
+pub fn get_datadir() -> PathBuf {
+    let data_dir = match dirs::data_dir() {
+        Some(dir) => dir.join("rask"),
+        None => env::current_dir().expect("No current dir defined in this system"),
+    };
+    if !data_dir.exists() {
+        let _ = fs::create_dir_all(&data_dir);
+    }
+    data_dir
+}
+
 pub trait UtilFns: Clone {
     fn get_homedir() -> String {
         format!(
@@ -109,7 +202,12 @@ pub trait UtilFns: Clone {
         .to_string()
     }
 
+    /// connection to the database `prepare_db` migrated at startup
     fn get_db_connection() -> Result<Connection, rusqlite::Error> {
-        start_db(Self::get_homedir())
+        let db_path = db_file_path();
+        Connection::open(&db_path).map_err(|e| {
+            log::error!("failed to connect to db {}: {}", db_path.display(), e);
+            e
+        })
     }
 }
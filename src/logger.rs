@@ -0,0 +1,232 @@
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::collections::VecDeque;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// environment variable used to set the log level when `--log-level` isn't given
+pub const LOG_ENV_VAR: &str = "RASK_LOG";
+pub const LOG_FILE_NAME: &str = "rask.log";
+/// size in bytes after which the log file is rotated
+const MAX_LOG_SIZE: u64 = 1024 * 1024;
+/// number of rotated files kept besides the active one (rask.log.1 .. rask.log.N)
+const MAX_LOG_FILES: usize = 3;
+/// number of lines kept in memory for the debug pane
+pub const RECENT_LINES: usize = 200;
+
+static LOGGER: OnceLock<RaskLogger> = OnceLock::new();
+
+pub struct RaskLogger {
+    level: LevelFilter,
+    path: PathBuf,
+    file: Mutex<Option<File>>,
+    /// last `RECENT_LINES` lines written, the debug pane reads them instead of the file
+    recent: Mutex<VecDeque<String>>,
+}
+
+impl RaskLogger {
+    pub fn new(level: LevelFilter, path: PathBuf) -> RaskLogger {
+        let recent = read_tail(&path, RECENT_LINES).into_iter().collect();
+        RaskLogger {
+            level,
+            path,
+            file: Mutex::new(None),
+            recent: Mutex::new(recent),
+        }
+    }
+
+    /// the last `count` lines logged, oldest first
+    pub fn recent(&self, count: usize) -> Vec<String> {
+        match self.recent.lock() {
+            Ok(recent) => recent
+                .iter()
+                .skip(recent.len().saturating_sub(count))
+                .cloned()
+                .collect(),
+            Err(_e) => vec![],
+        }
+    }
+
+    fn remember(&self, line: &str) {
+        if let Ok(mut recent) = self.recent.lock() {
+            if recent.len() == RECENT_LINES {
+                recent.pop_front();
+            }
+            recent.push_back(line.trim_end().to_string());
+        }
+    }
+
+    fn rotated_path(&self, idx: usize) -> PathBuf {
+        let mut rotated = self.path.clone().into_os_string();
+        rotated.push(format!(".{}", idx));
+        PathBuf::from(rotated)
+    }
+
+    /// shifts rask.log -> rask.log.1 -> rask.log.2 ... dropping the oldest one
+    fn rotate(&self) {
+        let _ = fs::remove_file(self.rotated_path(MAX_LOG_FILES));
+        for idx in (1..MAX_LOG_FILES).rev() {
+            let _ = fs::rename(self.rotated_path(idx), self.rotated_path(idx + 1));
+        }
+        let _ = fs::rename(&self.path, self.rotated_path(1));
+    }
+
+    fn open_file(&self) -> Option<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .ok()
+    }
+}
+
+impl Log for RaskLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format!(
+            "{} {:<5} [{}] {}\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        self.remember(&line);
+
+        if let Ok(mut guard) = self.file.lock() {
+            let needs_rotation = match fs::metadata(&self.path) {
+                Ok(meta) => meta.len() + line.len() as u64 > MAX_LOG_SIZE,
+                Err(_e) => false,
+            };
+            if needs_rotation {
+                *guard = None;
+                self.rotate();
+            }
+            if guard.is_none() {
+                *guard = self.open_file();
+            }
+            if let Some(file) = guard.as_mut() {
+                let _ = file.write_all(line.as_bytes());
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut guard) = self.file.lock() {
+            if let Some(file) = guard.as_mut() {
+                let _ = file.flush();
+            }
+        }
+    }
+}
+
+/// resolves the log level from the command line argument, falling back to `RASK_LOG`
+/// and finally to `info`
+pub fn resolve_level(cli_level: Option<String>) -> Result<LevelFilter, String> {
+    let raw = match cli_level {
+        Some(level) => level,
+        None => match env::var(LOG_ENV_VAR) {
+            Ok(level) => level,
+            Err(_e) => return Ok(LevelFilter::Info),
+        },
+    };
+
+    raw.trim().parse::<LevelFilter>().map_err(|_e| {
+        format!(
+            "invalid log level '{}', expected one of: off, error, warn, info, debug, trace",
+            raw
+        )
+    })
+}
+
+pub fn log_file_path() -> PathBuf {
+    crate::get_datadir().join(LOG_FILE_NAME)
+}
+
+/// installs the file logger as the global `log` backend
+pub fn init(level: LevelFilter) -> Result<(), String> {
+    let logger = LOGGER.get_or_init(|| RaskLogger::new(level, log_file_path()));
+    log::set_logger(logger).map_err(|e| e.to_string())?;
+    log::set_max_level(level);
+    log::info!(
+        "rask {} started, logging at {} level",
+        env!("CARGO_PKG_VERSION"),
+        level
+    );
+    Ok(())
+}
+
+/// returns the last `count` lines logged, including the ones of the previous runs still in
+/// the active log file
+pub fn tail(count: usize) -> Vec<String> {
+    match LOGGER.get() {
+        Some(logger) => logger.recent(count),
+        None => vec![],
+    }
+}
+
+/// returns the last `count` lines of a log file
+fn read_tail(path: &Path, count: usize) -> Vec<String> {
+    match File::open(path) {
+        Ok(file) => {
+            let lines: Vec<String> = BufReader::new(file).lines().map_while(Result::ok).collect();
+            let start = lines.len().saturating_sub(count);
+            lines[start..].to_vec()
+        }
+        Err(_e) => vec![],
+    }
+}
+
+/// helper used by the debug pane to color lines by level
+pub fn line_level(line: &str) -> Option<Level> {
+    line.split_whitespace()
+        .nth(2)
+        .and_then(|lvl| lvl.parse::<Level>().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log_line(logger: &RaskLogger, level: Level, message: &str) {
+        logger.log(
+            &Record::builder()
+                .args(format_args!("{}", message))
+                .level(level)
+                .target("rask::test")
+                .build(),
+        );
+    }
+
+    #[test]
+    fn keeps_the_last_lines_in_memory() {
+        let path = env::temp_dir().join(format!("rask-test-{}.log", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let logger = RaskLogger::new(LevelFilter::Info, path.clone());
+        for idx in 0..RECENT_LINES + 5 {
+            log_line(&logger, Level::Info, &format!("line {}", idx));
+        }
+        log_line(&logger, Level::Debug, "filtered out");
+
+        let recent = logger.recent(RECENT_LINES * 2);
+        assert_eq!(recent.len(), RECENT_LINES);
+        assert!(recent[0].ends_with("[rask::test] line 5"));
+        let last = logger.recent(2);
+        assert!(last[0].ends_with(&format!("line {}", RECENT_LINES + 3)));
+        assert!(last[1].ends_with(&format!("line {}", RECENT_LINES + 4)));
+        assert_eq!(line_level(&last[1]), Some(Level::Info));
+
+        // a new run starts from the lines of the file
+        logger.flush();
+        let next_run = RaskLogger::new(LevelFilter::Info, path.clone());
+        assert_eq!(next_run.recent(RECENT_LINES), recent);
+        let _ = fs::remove_file(&path);
+    }
+}
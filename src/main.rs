@@ -1,8 +1,10 @@
 //use std::env;
 
 //use std::result::Result;
+use argh::FromArgs;
 use rask::app::App;
-//use argh::FromArgs;
+use rask::burndown::{self, ChartKind};
+use rask::{config, logger, prepare_db, stats, urgency};
 use std::io;
 //use std::{ io, time::Duration};
 //use termion::{event::Key, input::MouseTerminal, raw::IntoRawMode, screen::AlternateScreen};
@@ -10,8 +12,126 @@ use std::io;
 //use notify_rust::Notification;
 
 
+#[derive(FromArgs)]
+/// A cli task manager for developers
+struct RaskArgs {
+    /// log level (off, error, warn, info, debug, trace), overrides RASK_LOG
+    #[argh(option)]
+    log_level: Option<String>,
+
+    #[argh(subcommand)]
+    command: Option<RaskCommand>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum RaskCommand {
+    Chart(ChartArgs),
+    Stats(StatsArgs),
+    Next(NextArgs),
+}
+
+#[derive(FromArgs)]
+/// print the burndown (or burnup) chart of a project or sprint
+#[argh(subcommand, name = "chart")]
+struct ChartArgs {
+    /// project id
+    #[argh(option)]
+    project: i32,
+    /// sprint id, the whole project when missing
+    #[argh(option)]
+    sprint: Option<i32>,
+    /// first day of the chart, YYYY-MM-DD
+    #[argh(option)]
+    from: Option<String>,
+    /// last day of the chart, YYYY-MM-DD
+    #[argh(option)]
+    to: Option<String>,
+    /// draw a burnup instead of a burndown
+    #[argh(switch)]
+    burnup: bool,
+    /// ascii (default) or svg
+    #[argh(option, default = "String::from(\"ascii\")")]
+    format: String,
+}
+
+#[derive(FromArgs)]
+/// print the flow metrics: cumulative flow, lead and cycle time, throughput and WIP age
+#[argh(subcommand, name = "stats")]
+struct StatsArgs {
+    /// project id, all projects when missing
+    #[argh(option)]
+    project: Option<i32>,
+    /// number of days to look back
+    #[argh(option, default = "30")]
+    days: i64,
+    /// text (default) or json
+    #[argh(option, default = "String::from(\"text\")")]
+    format: String,
+}
+
+#[derive(FromArgs)]
+/// print the most urgent tasks that can be worked on, across all projects
+#[argh(subcommand, name = "next")]
+struct NextArgs {
+    /// number of tasks, `urgency.next_count` of the config when missing
+    #[argh(option)]
+    count: Option<usize>,
+    /// text (default) or json
+    #[argh(option, default = "String::from(\"text\")")]
+    format: String,
+}
+
 #[allow(deprecated)]
 fn main() -> Result<(), io::Error> {
+    let args: RaskArgs = argh::from_env();
+    match logger::resolve_level(args.log_level) {
+        Ok(level) => {
+            if let Err(e) = logger::init(level) {
+                eprintln!("failed to start logger: {}", e);
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+    match config::Config::load() {
+        Ok(cfg) => config::init(cfg),
+        Err(e) => {
+            log::error!("{}", e);
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+
+    if let Err(e) = prepare_db() {
+        eprintln!("failed to open the database: {}", e);
+        std::process::exit(1);
+    }
+
+    if let Some(command) = args.command {
+        let output = match command {
+            RaskCommand::Chart(chart) => {
+                let kind = match chart.burnup {
+                    true => ChartKind::Burnup,
+                    false => ChartKind::Burndown,
+                };
+                burndown::export(chart.project, chart.sprint, chart.from, chart.to, kind, &chart.format)
+            }
+            RaskCommand::Stats(stats) => stats::export(stats.project, stats.days, &stats.format),
+            RaskCommand::Next(next) => urgency::export(next.count, &next.format),
+        };
+        match output {
+            Ok(output) => print!("{}", output),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
     /*
     println!("Rask your life!!!");
     let mut db_path = "".to_string();
@@ -42,8 +162,12 @@ fn main() -> Result<(), io::Error> {
     let mut app = App::default();
     app.init();
 
-    app.render()
-
+    let rv = app.render();
+    if let Err(ref e) = rv {
+        log::error!("rask stopped with error: {}", e);
+    }
+    log::info!("rask closed");
+    rv
 
 }
 
@@ -0,0 +1,114 @@
+use super::DbObj;
+use rusqlite::{params, Connection, Error, Row};
+use std::collections::HashMap;
+
+/// lightweight to-do entry of a task, ordered by `position`
+#[derive(Debug, Clone)]
+pub struct ChecklistItem {
+    pub id: i32,
+    pub task_id: i32,
+    pub text: String,
+    pub done: bool,
+    pub position: i32,
+}
+
+impl Default for ChecklistItem {
+    fn default() -> ChecklistItem {
+        ChecklistItem::new(0, 0, "".to_string(), false, 0)
+    }
+}
+
+impl DbObj for ChecklistItem {
+    fn fields() -> Vec<String> {
+        vec![
+            "id".to_string(),
+            "task_id".to_string(),
+            "text".to_string(),
+            "done".to_string(),
+            "position".to_string(),
+        ]
+    }
+
+    fn get_id(&mut self) -> i32 {
+        self.id
+    }
+
+    fn table_name() -> String {
+        "task_checklist_item".to_string()
+    }
+
+    fn from_row(row: &Row) -> ChecklistItem {
+        ChecklistItem::new(
+            row.get_unwrap(0),
+            row.get_unwrap(1),
+            row.get_unwrap(2),
+            row.get_unwrap(3),
+            row.get_unwrap(4),
+        )
+    }
+
+    fn to_hashmap(&self) -> HashMap<String, String> {
+        let mut rv: HashMap<String, String> = HashMap::new();
+        rv.insert("id".to_string(), self.id.to_string());
+        rv.insert("task_id".to_string(), self.task_id.to_string());
+        rv.insert("text".to_string(), self.text.to_string());
+        rv.insert("done".to_string(), (self.done as i32).to_string());
+        rv.insert("position".to_string(), self.position.to_string());
+
+        rv
+    }
+
+    fn from_hashmap(fieldmap: &mut HashMap<String, String>) -> Result<Self, Error> {
+        let expected_fields = Self::fields();
+        let existing_fields = expected_fields.iter().fold(0, |acc, item| -> usize {
+            if fieldmap.contains_key(item) {
+                return acc + 1;
+            }
+            acc
+        });
+        if expected_fields.len() == existing_fields {
+            Ok(Self::new(
+                fieldmap.get("id").unwrap().parse::<i32>().unwrap(),
+                fieldmap.get("task_id").unwrap().parse::<i32>().unwrap(),
+                fieldmap.get("text").unwrap().to_string(),
+                fieldmap.get("done").unwrap() == "1",
+                fieldmap.get("position").unwrap().parse::<i32>().unwrap(),
+            ))
+        } else {
+            Err(Error::InvalidQuery)
+        }
+    }
+}
+
+impl ChecklistItem {
+    pub fn new(id: i32, task_id: i32, text: String, done: bool, position: i32) -> ChecklistItem {
+        ChecklistItem {
+            id,
+            task_id,
+            text,
+            done,
+            position,
+        }
+    }
+
+    /// items of a task in display order
+    pub fn for_task(conn: &Connection, task_id: i32) -> Result<Vec<ChecklistItem>, Error> {
+        Self::list(conn, format!("task_id={} ORDER BY position, id", task_id))
+    }
+
+    pub fn delete_for_task(conn: &Connection, task_id: i32) -> Result<usize, Error> {
+        conn.execute(
+            "DELETE FROM task_checklist_item WHERE task_id=?",
+            params![task_id],
+        )
+    }
+
+    /// select expression giving the "done/total" progress of the task aliased `task_alias`, empty
+    /// when the task has no checklist
+    pub fn progress_column(task_alias: &str) -> String {
+        format!(
+            "(SELECT CASE WHEN COUNT(*) > 0 THEN SUM(c.done) || '/' || COUNT(*) ELSE '' END FROM task_checklist_item AS c WHERE c.task_id = {}.id) AS 'checklist'",
+            task_alias
+        )
+    }
+}
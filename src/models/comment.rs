@@ -0,0 +1,134 @@
+use super::person::Person;
+use super::DbObj;
+use rusqlite::{params, Connection, Error, Row};
+use std::collections::HashMap;
+
+/// message posted on a task by a `Person`
+#[derive(Debug, Clone)]
+pub struct Comment {
+    pub id: i32,
+    pub task_id: i32,
+    pub author_id: i32,
+    pub body: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Default for Comment {
+    fn default() -> Comment {
+        Comment::new(0, 0, 0, "".to_string(), 0, 0)
+    }
+}
+
+impl DbObj for Comment {
+    fn fields() -> Vec<String> {
+        vec![
+            "id".to_string(),
+            "task_id".to_string(),
+            "author_id".to_string(),
+            "body".to_string(),
+            "created_at".to_string(),
+            "updated_at".to_string(),
+        ]
+    }
+
+    fn get_id(&mut self) -> i32 {
+        self.id
+    }
+
+    fn table_name() -> String {
+        "task_comment".to_string()
+    }
+
+    fn from_row(row: &Row) -> Comment {
+        Comment::new(
+            row.get_unwrap(0),
+            row.get_unwrap(1),
+            row.get_unwrap(2),
+            row.get_unwrap(3),
+            row.get_unwrap(4),
+            row.get_unwrap(5),
+        )
+    }
+
+    fn to_hashmap(&self) -> HashMap<String, String> {
+        let mut rv: HashMap<String, String> = HashMap::new();
+        rv.insert("id".to_string(), self.id.to_string());
+        rv.insert("task_id".to_string(), self.task_id.to_string());
+        rv.insert("author_id".to_string(), self.author_id.to_string());
+        rv.insert("body".to_string(), self.body.to_string());
+        rv.insert("created_at".to_string(), self.created_at.to_string());
+        rv.insert("updated_at".to_string(), self.updated_at.to_string());
+
+        rv
+    }
+
+    fn from_hashmap(fieldmap: &mut HashMap<String, String>) -> Result<Self, Error> {
+        let expected_fields = Self::fields();
+        let existing_fields = expected_fields.iter().fold(0, |acc, item| -> usize {
+            if fieldmap.contains_key(item) {
+                return acc + 1;
+            }
+            acc
+        });
+        if expected_fields.len() == existing_fields {
+            Ok(Self::new(
+                fieldmap.get("id").unwrap().parse::<i32>().unwrap(),
+                fieldmap.get("task_id").unwrap().parse::<i32>().unwrap(),
+                fieldmap.get("author_id").unwrap().parse::<i32>().unwrap(),
+                fieldmap.get("body").unwrap().to_string(),
+                fieldmap.get("created_at").unwrap().parse::<i64>().unwrap(),
+                fieldmap.get("updated_at").unwrap().parse::<i64>().unwrap(),
+            ))
+        } else {
+            Err(Error::InvalidQuery)
+        }
+    }
+}
+
+impl Comment {
+    pub fn new(
+        id: i32,
+        task_id: i32,
+        author_id: i32,
+        body: String,
+        created_at: i64,
+        updated_at: i64,
+    ) -> Comment {
+        Comment {
+            id,
+            task_id,
+            author_id,
+            body,
+            created_at,
+            updated_at,
+        }
+    }
+
+    pub fn get_author(&self, conn: &Connection) -> Option<Person> {
+        Person::get_by_id(conn, self.author_id).ok()
+    }
+
+    /// comments of a task, oldest first, with the author's name as `author_name`
+    pub fn thread(conn: &Connection, task_id: i32) -> Result<Vec<HashMap<String, String>>, Error> {
+        Self::query(
+            conn,
+            format!(
+                "SELECT c.*, trim(coalesce(p.first_name, '') || ' ' || coalesce(p.last_name, '')) AS 'author_name' FROM task_comment AS c LEFT JOIN person AS p ON (c.author_id = p.id) WHERE c.task_id = {} ORDER BY c.created_at, c.id",
+                task_id
+            ),
+        )
+    }
+
+    pub fn delete_for_task(conn: &Connection, task_id: i32) -> Result<usize, Error> {
+        conn.execute("DELETE FROM task_comment WHERE task_id=?", params![task_id])
+    }
+
+    /// select expression giving the number of comments of the task aliased `task_alias`
+    pub fn count_column(task_alias: &str) -> String {
+        format!(
+            "(SELECT COUNT(*) FROM task_comment AS cm WHERE cm.task_id = {}.id) AS 'comments'",
+            task_alias
+        )
+    }
+}
@@ -1,7 +1,7 @@
 use rusqlite::{params, types::ValueRef, Connection, Error, Result, Row, ToSql};
 use std::collections::HashMap;
 use std::str;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 pub trait DbObj: Sized + Clone + std::fmt::Debug {
     fn fields() -> Vec<String>;
     fn table_name() -> String;
@@ -37,8 +37,28 @@ pub trait DbObj: Sized + Clone + std::fmt::Debug {
         rv
     }
 
+    /// logs an executed statement along with the time it took
+    fn log_query(sql: &str, started: Instant, result: &Result<usize, &Error>) {
+        match result {
+            Ok(rows) => log::debug!("{} rows in {:?}: {}", rows, started.elapsed(), sql),
+            Err(e) => log::error!(
+                "query failed after {:?}: {} ({})",
+                started.elapsed(),
+                sql,
+                e
+            ),
+        }
+    }
+
     fn query(conn: &Connection, sql: String) -> Result<Vec<HashMap<String, String>>, Error> {
-        match conn.prepare(&sql) {
+        let started = Instant::now();
+        let rv = Self::run_query(conn, &sql);
+        Self::log_query(&sql, started, &rv.as_ref().map(|rows| rows.len()));
+        rv
+    }
+
+    fn run_query(conn: &Connection, sql: &str) -> Result<Vec<HashMap<String, String>>, Error> {
+        match conn.prepare(sql) {
             Ok(mut stmt) => {
                 let fields: Vec<String> = stmt
                     .column_names()
@@ -82,6 +102,13 @@ pub trait DbObj: Sized + Clone + std::fmt::Debug {
                     format!("{}", current_timestamp).to_string(),
                 );
             }
+            // records nobody claimed are created by whoever runs rask
+            if self_map.get("created_by").map(|v| v.as_str()) == Some("0") {
+                self_map.insert(
+                    "created_by".to_string(),
+                    crate::current_user_id().to_string(),
+                );
+            }
 
             for v in &fields {
                 if pkey.trim() == v.trim() {
@@ -98,7 +125,7 @@ pub trait DbObj: Sized + Clone + std::fmt::Debug {
                 f_str.push_str(v);
                 //v_str.push_str(&format!("?{:}", cc));
                 v_str.push_str("'");
-                v_str.push_str(&self_map.get(v).unwrap().to_string());
+                v_str.push_str(&self_map.get(v).unwrap().replace('\'', "''"));
                 v_str.push_str("'");
             }
             let str_q = format!(
@@ -108,13 +135,12 @@ pub trait DbObj: Sized + Clone + std::fmt::Debug {
                 v_str
             );
 
-            match conn.prepare(&str_q) {
-                Ok(mut stmt) => match stmt.execute(params![]) {
-                    Ok(rec) => return Self::get_by_id(conn, rec as i32),
-                    Err(e) => return Err(e),
-                },
-                Err(e) => return Err(e),
-            }
+            let started = Instant::now();
+            let rv = conn
+                .prepare(&str_q)
+                .and_then(|mut stmt| stmt.execute(params![]));
+            Self::log_query(&str_q, started, &rv.as_ref().copied());
+            rv.and_then(|_rec| Self::get_by_id(conn, conn.last_insert_rowid() as i32))
             /*
             match conn.execute(&str_q, &pparams) {
                 Ok(rec) => return Self::get_by_id(conn, rec as i32),
@@ -140,7 +166,11 @@ pub trait DbObj: Sized + Clone + std::fmt::Debug {
                     f_str.push_str(",");
                 }
                 pparams.push(self_map.get(v).unwrap());
-                f_str.push_str(&format!("{}='{}'", v, self_map.get(v).unwrap().to_string()));
+                f_str.push_str(&format!(
+                    "{}='{}'",
+                    v,
+                    self_map.get(v).unwrap().replace('\'', "''")
+                ));
                 //f_str.push_str(&format!("{}=?{}", v, cc));
             }
             //pparams.push(&id);
@@ -152,24 +182,25 @@ pub trait DbObj: Sized + Clone + std::fmt::Debug {
                 id.to_string()
             );
 
-            match conn.execute(&str_q, params![]) {
-                Ok(_rec) => {
-                    return Self::get_by_id(conn, id.parse::<i32>().unwrap());
-                }
-                Err(e) => return Err(e),
-            }
+            let started = Instant::now();
+            let rv = conn.execute(&str_q, params![]);
+            Self::log_query(&str_q, started, &rv.as_ref().copied());
+            rv.and_then(|_rec| Self::get_by_id(conn, id.parse::<i32>().unwrap()))
         }
     }
 
     fn del(&mut self, conn: &Connection) -> Result<(), Error> {
         let q = format!("DELETE FROM {} WHERE id=?", Self::table_name());
-        match conn.prepare(q.as_str()) {
-            Ok(mut stmt) => match stmt.execute(&[&self.get_id()]) {
-                Ok(_i) => return Ok(()),
-                Err(e) => return Err(e),
-            },
-            Err(e) => return Err(e),
-        }
+        let started = Instant::now();
+        let rv = conn
+            .prepare(q.as_str())
+            .and_then(|mut stmt| stmt.execute([&self.get_id()]));
+        Self::log_query(
+            &format!("{} [{}]", q, self.get_id()),
+            started,
+            &rv.as_ref().copied(),
+        );
+        rv.map(|_i| ())
     }
     fn list(conn: &Connection, sql_filters: String) -> Result<Vec<Self>, Error> {
         let bq = format!("SELECT * FROM {}", Self::table_name());
@@ -178,7 +209,14 @@ pub trait DbObj: Sized + Clone + std::fmt::Debug {
         } else {
             format!("{}", bq)
         };
-        let mut stmt = conn.prepare(&qr)?;
+        let started = Instant::now();
+        let rv = Self::run_list(conn, &qr);
+        Self::log_query(&qr, started, &rv.as_ref().map(|rows| rows.len()));
+        rv
+    }
+
+    fn run_list(conn: &Connection, sql: &str) -> Result<Vec<Self>, Error> {
+        let mut stmt = conn.prepare(sql)?;
         let rs_iter = stmt.query_map(params![], |row: &Row| -> Result<Self> {
             Ok(Self::from_row(row))
         })?;
@@ -202,7 +240,14 @@ pub trait DbObj: Sized + Clone + std::fmt::Debug {
     }
 }
 
+pub mod checklist_item;
+pub mod comment;
 pub mod person;
 pub mod project;
+pub mod sprint;
+pub mod status_change;
 pub mod task;
+pub mod task_assignee;
+pub mod task_dependency;
 pub mod task_status;
+pub mod task_tag;
@@ -62,8 +62,8 @@ impl DbObj for Person {
         if expected_fields.len() == existing_fields {
             Ok(Self::new(
                 fieldmap.get("id").unwrap().parse::<i32>().unwrap(),
-                fieldmap.get("fname").unwrap().to_string(),
-                fieldmap.get("lname").unwrap().to_string(),
+                fieldmap.get("first_name").unwrap().to_string(),
+                fieldmap.get("last_name").unwrap().to_string(),
                 fieldmap.get("email").unwrap().to_string(),
             ))
         } else {
@@ -81,4 +81,20 @@ impl Person {
             email: semail,
         }
     }
+
+    pub fn full_name(&self) -> String {
+        format!("{} {}", self.first_name, self.last_name)
+            .trim()
+            .to_string()
+    }
+
+    /// first letters of the first and family names, "JD" for John Doe
+    pub fn initials(&self) -> String {
+        self.first_name
+            .chars()
+            .take(1)
+            .chain(self.last_name.chars().take(1))
+            .collect::<String>()
+            .to_uppercase()
+    }
 }
@@ -1,5 +1,6 @@
+use super::person::Person;
 use super::DbObj;
-use rusqlite::{Error, Row};
+use rusqlite::{Connection, Error, Row};
 use std::collections::HashMap;
 #[derive(Debug, Clone)]
 pub struct Project {
@@ -55,6 +56,10 @@ impl Project {
     }
 
     pub fn tasks(&mut self) {}
+
+    pub fn get_creator(&self, conn: &Connection) -> Option<Person> {
+        Person::get_by_id(conn, self.created_by).ok()
+    }
 }
 
 impl DbObj for Project {
@@ -0,0 +1,199 @@
+use super::task_status::TaskStatus;
+use super::DbObj;
+use chrono::{Local, NaiveDate};
+use rusqlite::{params, Connection, Error, Row};
+use std::collections::HashMap;
+
+/// dates of sprints, as typed in the sprint form
+pub const SPRINT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// iteration of a project, tasks join it through `Task.sprint_id`
+#[derive(Debug, Clone)]
+pub struct Sprint {
+    pub id: i32,
+    pub project_id: i32,
+    pub name: String,
+    pub goal: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub closed: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Default for Sprint {
+    fn default() -> Sprint {
+        Sprint {
+            id: 0,
+            project_id: 0,
+            name: "".to_string(),
+            goal: "".to_string(),
+            start_date: "".to_string(),
+            end_date: "".to_string(),
+            closed: false,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+}
+
+impl DbObj for Sprint {
+    fn fields() -> Vec<String> {
+        vec![
+            "id".to_string(),
+            "project_id".to_string(),
+            "name".to_string(),
+            "goal".to_string(),
+            "start_date".to_string(),
+            "end_date".to_string(),
+            "closed".to_string(),
+            "created_at".to_string(),
+            "updated_at".to_string(),
+        ]
+    }
+
+    fn get_id(&mut self) -> i32 {
+        self.id
+    }
+
+    fn table_name() -> String {
+        "sprint".to_string()
+    }
+
+    fn from_row(row: &Row) -> Sprint {
+        Sprint {
+            id: row.get_unwrap(0),
+            project_id: row.get_unwrap(1),
+            name: row.get_unwrap(2),
+            goal: row.get_unwrap(3),
+            start_date: row.get_unwrap(4),
+            end_date: row.get_unwrap(5),
+            closed: row.get_unwrap::<usize, i32>(6) != 0,
+            created_at: row.get_unwrap(7),
+            updated_at: row.get_unwrap(8),
+        }
+    }
+
+    fn to_hashmap(&self) -> HashMap<String, String> {
+        let mut rv: HashMap<String, String> = HashMap::new();
+        rv.insert("id".to_string(), self.id.to_string());
+        rv.insert("project_id".to_string(), self.project_id.to_string());
+        rv.insert("name".to_string(), self.name.to_string());
+        rv.insert("goal".to_string(), self.goal.to_string());
+        rv.insert("start_date".to_string(), self.start_date.to_string());
+        rv.insert("end_date".to_string(), self.end_date.to_string());
+        rv.insert("closed".to_string(), (self.closed as i32).to_string());
+        rv.insert("created_at".to_string(), self.created_at.to_string());
+        rv.insert("updated_at".to_string(), self.updated_at.to_string());
+
+        rv
+    }
+
+    fn from_hashmap(fieldmap: &mut HashMap<String, String>) -> Result<Self, Error> {
+        let expected_fields = Self::fields();
+        let existing_fields = expected_fields.iter().fold(0, |acc, item| -> usize {
+            if fieldmap.contains_key(item) {
+                return acc + 1;
+            }
+            acc
+        });
+        if expected_fields.len() == existing_fields {
+            Ok(Sprint {
+                id: fieldmap.get("id").unwrap().parse::<i32>().unwrap(),
+                project_id: fieldmap.get("project_id").unwrap().parse::<i32>().unwrap(),
+                name: fieldmap.get("name").unwrap().to_string(),
+                goal: fieldmap.get("goal").unwrap().to_string(),
+                start_date: fieldmap.get("start_date").unwrap().to_string(),
+                end_date: fieldmap.get("end_date").unwrap().to_string(),
+                closed: fieldmap.get("closed").unwrap() != "0",
+                created_at: fieldmap.get("created_at").unwrap().parse::<i64>().unwrap(),
+                updated_at: fieldmap.get("updated_at").unwrap().parse::<i64>().unwrap(),
+            })
+        } else {
+            Err(Error::InvalidQuery)
+        }
+    }
+}
+
+impl Sprint {
+    pub fn start(&self) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(&self.start_date, SPRINT_DATE_FORMAT).ok()
+    }
+
+    pub fn end(&self) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(&self.end_date, SPRINT_DATE_FORMAT).ok()
+    }
+
+    /// "closed", "active" when today is between its dates, "planned" before and "overdue" after
+    pub fn state(&self) -> &'static str {
+        let today = Local::now().date_naive();
+        match (self.closed, self.start(), self.end()) {
+            (true, _, _) => "closed",
+            (_, Some(start), _) if today < start => "planned",
+            (_, _, Some(end)) if today > end => "overdue",
+            (_, Some(_), _) => "active",
+            _ => "planned",
+        }
+    }
+
+    /// sprints of a project, the open ones first and then by start date
+    pub fn for_project(conn: &Connection, project_id: i32) -> Result<Vec<Sprint>, Error> {
+        Self::list(
+            conn,
+            format!("project_id={} ORDER BY closed, start_date, id", project_id),
+        )
+    }
+
+    /// open sprint of a project that has already started, the most recent one if several did
+    pub fn active(conn: &Connection, project_id: i32) -> Option<Sprint> {
+        let today = Local::now().date_naive();
+        Self::for_project(conn, project_id)
+            .ok()?
+            .into_iter()
+            .filter(|sprint| !sprint.closed && sprint.start().is_some_and(|start| start <= today))
+            .max_by_key(|sprint| sprint.start())
+    }
+
+    /// open sprint of the same project that starts after this one
+    pub fn next(&self, conn: &Connection) -> Option<Sprint> {
+        Self::for_project(conn, self.project_id)
+            .ok()?
+            .into_iter()
+            .filter(|sprint| sprint.id != self.id && !sprint.closed)
+            .find(|sprint| sprint.start_date >= self.start_date)
+    }
+
+    /// closes the sprint, its unfinished tasks go to the next sprint or back to the backlog.
+    /// returns the sprint they went to, None for the backlog
+    pub fn close(&mut self, conn: &Connection) -> Result<Option<Sprint>, Error> {
+        let next = self.next(conn);
+        conn.execute(
+            "UPDATE task SET sprint_id = ? WHERE sprint_id = ? AND status <> ?",
+            params![
+                next.as_ref().map(|s| s.id).unwrap_or(0),
+                self.id,
+                TaskStatus::done_id(conn)
+            ],
+        )?;
+        self.closed = true;
+        self.save(conn)?;
+        Ok(next)
+    }
+
+    /// sends the tasks of a sprint back to the backlog
+    pub fn release_tasks(conn: &Connection, sprint_id: i32) -> Result<usize, Error> {
+        conn.execute(
+            "UPDATE task SET sprint_id = 0 WHERE sprint_id = ?",
+            params![sprint_id],
+        )
+    }
+
+    /// select expression giving the sum of the weights of the tasks in the sprint aliased
+    /// `sprint_alias`
+    pub fn points_column(sprint_alias: &str) -> String {
+        format!(
+            "(SELECT coalesce(sum(st.weight), 0) FROM task AS st WHERE st.sprint_id = {}.id) AS 'points'",
+            sprint_alias
+        )
+    }
+}
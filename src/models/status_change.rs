@@ -0,0 +1,126 @@
+use super::DbObj;
+use rusqlite::{Connection, Error, Row};
+use std::collections::HashMap;
+
+/// a task moving from one status to another, written by the triggers on the task table.
+/// `from_status` is 0 when the task was created
+#[derive(Debug, Clone)]
+pub struct StatusChange {
+    pub id: i32,
+    pub task_id: i32,
+    pub from_status: i32,
+    pub to_status: i32,
+    pub changed_at: i64,
+}
+
+impl Default for StatusChange {
+    fn default() -> StatusChange {
+        StatusChange::new(0, 0, 0, 0, 0)
+    }
+}
+
+impl DbObj for StatusChange {
+    fn fields() -> Vec<String> {
+        vec![
+            "id".to_string(),
+            "task_id".to_string(),
+            "from_status".to_string(),
+            "to_status".to_string(),
+            "changed_at".to_string(),
+        ]
+    }
+
+    fn get_id(&mut self) -> i32 {
+        self.id
+    }
+
+    fn table_name() -> String {
+        "task_status_change".to_string()
+    }
+
+    fn from_row(row: &Row) -> StatusChange {
+        StatusChange::new(
+            row.get_unwrap(0),
+            row.get_unwrap(1),
+            row.get_unwrap(2),
+            row.get_unwrap(3),
+            row.get_unwrap(4),
+        )
+    }
+
+    fn to_hashmap(&self) -> HashMap<String, String> {
+        let mut rv: HashMap<String, String> = HashMap::new();
+        rv.insert("id".to_string(), self.id.to_string());
+        rv.insert("task_id".to_string(), self.task_id.to_string());
+        rv.insert("from_status".to_string(), self.from_status.to_string());
+        rv.insert("to_status".to_string(), self.to_status.to_string());
+        rv.insert("changed_at".to_string(), self.changed_at.to_string());
+
+        rv
+    }
+
+    fn from_hashmap(fieldmap: &mut HashMap<String, String>) -> Result<Self, Error> {
+        let expected_fields = Self::fields();
+        let existing_fields = expected_fields.iter().fold(0, |acc, item| -> usize {
+            if fieldmap.contains_key(item) {
+                return acc + 1;
+            }
+            acc
+        });
+        if expected_fields.len() == existing_fields {
+            Ok(Self::new(
+                fieldmap.get("id").unwrap().parse::<i32>().unwrap(),
+                fieldmap.get("task_id").unwrap().parse::<i32>().unwrap(),
+                fieldmap.get("from_status").unwrap().parse::<i32>().unwrap(),
+                fieldmap.get("to_status").unwrap().parse::<i32>().unwrap(),
+                fieldmap.get("changed_at").unwrap().parse::<i64>().unwrap(),
+            ))
+        } else {
+            Err(Error::InvalidQuery)
+        }
+    }
+}
+
+impl StatusChange {
+    pub fn new(
+        id: i32,
+        task_id: i32,
+        from_status: i32,
+        to_status: i32,
+        changed_at: i64,
+    ) -> StatusChange {
+        StatusChange {
+            id,
+            task_id,
+            from_status,
+            to_status,
+            changed_at,
+        }
+    }
+
+    /// status changes of the tasks of a project, oldest first
+    pub fn for_project(conn: &Connection, project_id: i32) -> Result<Vec<StatusChange>, Error> {
+        Self::list(
+            conn,
+            format!(
+                "task_id IN (SELECT id FROM task WHERE project_id={}) ORDER BY changed_at, id",
+                project_id
+            ),
+        )
+    }
+
+    /// every status change, oldest first
+    pub fn all(conn: &Connection) -> Result<Vec<StatusChange>, Error> {
+        Self::list(conn, "id > 0 ORDER BY changed_at, id".to_string())
+    }
+
+    /// status of a task at `timestamp` given its changes sorted by time, None before the first
+    /// one
+    pub fn status_at(changes: &[&StatusChange], timestamp: i64) -> Option<i32> {
+        changes
+            .iter()
+            .rev()
+            .find(|c| c.changed_at <= timestamp)
+            .map(|c| c.to_status)
+    }
+}
@@ -1,7 +1,15 @@
 use super::DbObj;
-use super::{project::Project, task_status::TaskStatus};
-use rusqlite::{Connection, Error, Row};
+use super::{person::Person, project::Project, task_status::TaskStatus};
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, Error, Row};
 use std::collections::HashMap;
+
+/// start and due dates are typed as YYYY-MM-DD, empty when the task has none
+pub const DUE_DATE_FORMAT: &str = "%Y-%m-%d";
+/// P0 is the most important, tasks made before priorities existed are P2
+pub const PRIORITIES: [&str; 4] = ["P0", "P1", "P2", "P3"];
+pub const NORMAL_PRIORITY: i32 = 2;
+
 #[derive(Debug, Clone)]
 pub struct Task {
     pub id: i32,
@@ -14,33 +22,30 @@ pub struct Task {
     pub created_by: i32,
     pub created_at: i64,
     pub updated_at: i64,
+    /// 0 while the task is in the backlog
+    pub sprint_id: i32,
+    pub due_date: String,
+    pub start_date: String,
+    /// order of the card in its scrumboard column
+    pub position: i32,
+    /// index in `PRIORITIES`
+    pub priority: i32,
 }
 
 impl Task {
-    pub fn new(
-        id: i32,
-        project_id: i32,
-        parent_id: i32,
-        name: String,
-        description: String,
-        weight: i32,
-        status: i32,
-        created_by: i32,
-        created_at: i64,
-        updated_at: i64,
-    ) -> Task {
-        Task {
-            id,
-            project_id,
-            parent_id,
-            name,
-            description,
-            weight,
-            status,
-            created_by,
-            created_at,
-            updated_at,
-        }
+    pub fn due(&self) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(&self.due_date, DUE_DATE_FORMAT).ok()
+    }
+
+    pub fn start(&self) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(&self.start_date, DUE_DATE_FORMAT).ok()
+    }
+
+    pub fn priority_label(&self) -> &'static str {
+        PRIORITIES
+            .get(self.priority as usize)
+            .copied()
+            .unwrap_or("--")
     }
 
     pub fn get_status(&self, conn: &Connection) -> Option<TaskStatus> {
@@ -55,11 +60,80 @@ impl Task {
             Err(_e) => return None,
         }
     }
+    pub fn get_creator(&self, conn: &Connection) -> Option<Person> {
+        Person::get_by_id(conn, self.created_by).ok()
+    }
+
+    /// position after the last card of a status column of a project
+    pub fn next_position(conn: &Connection, project_id: i32, status: i32) -> i32 {
+        conn.query_row(
+            "SELECT coalesce(max(position), 0) + 1 FROM task WHERE project_id=? AND status=?",
+            params![project_id, status],
+            |row| row.get(0),
+        )
+        .unwrap_or(0)
+    }
+
+    /// number of tasks of a project in each status, whatever their sprint, 0 for the tasks
+    /// without a project
+    pub fn status_counts(conn: &Connection, project_id: i32) -> Result<HashMap<i32, usize>, Error> {
+        let mut stmt = conn.prepare(
+            "SELECT status, count(*) FROM task WHERE coalesce(project_id, 0)=? GROUP BY status",
+        )?;
+        let rows = stmt.query_map(params![project_id], |row| {
+            Ok((row.get::<_, i32>(0)?, row.get::<_, i64>(1)? as usize))
+        })?;
+        rows.collect()
+    }
+
+    /// whether `parent_id` is the task itself or one of its subtasks, at any depth
+    pub fn parent_creates_cycle(
+        conn: &Connection,
+        task_id: i32,
+        parent_id: i32,
+    ) -> Result<bool, Error> {
+        if parent_id == 0 {
+            return Ok(false);
+        }
+        conn.query_row(
+            "WITH RECURSIVE up(id) AS (SELECT ?1 UNION SELECT t.parent_id FROM task AS t JOIN up ON (t.id = up.id) WHERE coalesce(t.parent_id, 0) <> 0) SELECT count(*) FROM up WHERE id = ?2",
+            params![parent_id, task_id],
+            |row| row.get::<_, i64>(0),
+        )
+        .map(|count| count > 0)
+    }
+
+    /// numbers the tasks `ids` in this order
+    pub fn set_positions(conn: &Connection, ids: &[i32]) -> Result<(), Error> {
+        for (idx, id) in ids.iter().enumerate() {
+            conn.execute(
+                "UPDATE task SET position=? WHERE id=?",
+                params![idx as i32 + 1, id],
+            )?;
+        }
+        Ok(())
+    }
 }
 
 impl Default for Task {
     fn default() -> Task {
-        Task::new(0, 0, 0, "".to_string(), "".to_string(), 0, 0, 0, 0, 0)
+        Task {
+            id: 0,
+            project_id: 0,
+            parent_id: 0,
+            name: "".to_string(),
+            description: "".to_string(),
+            weight: 0,
+            status: 0,
+            created_by: 0,
+            created_at: 0,
+            updated_at: 0,
+            sprint_id: 0,
+            due_date: "".to_string(),
+            start_date: "".to_string(),
+            position: 0,
+            priority: NORMAL_PRIORITY,
+        }
     }
 }
 
@@ -83,6 +157,11 @@ impl DbObj for Task {
             "created_by".to_string(),
             "created_at".to_string(),
             "updated_at".to_string(),
+            "sprint_id".to_string(),
+            "due_date".to_string(),
+            "start_date".to_string(),
+            "position".to_string(),
+            "priority".to_string(),
         ]
     }
 
@@ -98,6 +177,11 @@ impl DbObj for Task {
             created_by: row.get_unwrap(7),
             created_at: row.get_unwrap(8),
             updated_at: row.get_unwrap(9),
+            sprint_id: row.get_unwrap(10),
+            due_date: row.get_unwrap(11),
+            start_date: row.get_unwrap(12),
+            position: row.get_unwrap(13),
+            priority: row.get_unwrap(14),
         }
     }
 
@@ -113,6 +197,11 @@ impl DbObj for Task {
         rv.insert("created_by".to_string(), self.created_by.to_string());
         rv.insert("created_at".to_string(), self.created_at.to_string());
         rv.insert("updated_at".to_string(), self.updated_at.to_string());
+        rv.insert("sprint_id".to_string(), self.sprint_id.to_string());
+        rv.insert("due_date".to_string(), self.due_date.to_string());
+        rv.insert("start_date".to_string(), self.start_date.to_string());
+        rv.insert("position".to_string(), self.position.to_string());
+        rv.insert("priority".to_string(), self.priority.to_string());
 
         rv
     }
@@ -126,20 +215,50 @@ impl DbObj for Task {
             acc
         });
         if expected_fields.len() == existing_fields {
-            Ok(Self::new(
-                fieldmap.get("id").unwrap().parse::<i32>().unwrap(),
-                fieldmap.get("project_id").unwrap().parse::<i32>().unwrap(),
-                fieldmap.get("parent_id").unwrap().parse::<i32>().unwrap(),
-                fieldmap.get("name").unwrap().to_string(),
-                fieldmap.get("description").unwrap().to_string(),
-                fieldmap.get("weight").unwrap().parse::<i32>().unwrap(),
-                fieldmap.get("status").unwrap().parse::<i32>().unwrap(),
-                fieldmap.get("created_by").unwrap().parse::<i32>().unwrap(),
-                fieldmap.get("created_at").unwrap().parse::<i64>().unwrap(),
-                fieldmap.get("updated_at").unwrap().parse::<i64>().unwrap(),
-            ))
+            Ok(Task {
+                id: fieldmap.get("id").unwrap().parse::<i32>().unwrap(),
+                project_id: fieldmap.get("project_id").unwrap().parse::<i32>().unwrap(),
+                parent_id: fieldmap.get("parent_id").unwrap().parse::<i32>().unwrap(),
+                name: fieldmap.get("name").unwrap().to_string(),
+                description: fieldmap.get("description").unwrap().to_string(),
+                weight: fieldmap.get("weight").unwrap().parse::<i32>().unwrap(),
+                status: fieldmap.get("status").unwrap().parse::<i32>().unwrap(),
+                created_by: fieldmap.get("created_by").unwrap().parse::<i32>().unwrap(),
+                created_at: fieldmap.get("created_at").unwrap().parse::<i64>().unwrap(),
+                updated_at: fieldmap.get("updated_at").unwrap().parse::<i64>().unwrap(),
+                sprint_id: fieldmap.get("sprint_id").unwrap().parse::<i32>().unwrap(),
+                due_date: fieldmap.get("due_date").unwrap().to_string(),
+                start_date: fieldmap.get("start_date").unwrap().to_string(),
+                position: fieldmap.get("position").unwrap().parse::<i32>().unwrap(),
+                priority: fieldmap.get("priority").unwrap().parse::<i32>().unwrap(),
+            })
         } else {
             Err(Error::InvalidQuery)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_db, migrate_db};
+
+    #[test]
+    fn parents_cannot_be_the_task_or_its_subtasks() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        migrate_db(&conn).unwrap();
+        // 3 is a subtask of 2, itself a subtask of 1
+        conn.execute_batch(
+            "INSERT INTO task (id, parent_id, name, description, status) VALUES (1, 0, 'a', '', 1), (2, 1, 'b', '', 1), (3, 2, 'c', '', 1), (4, 0, 'd', '', 1)",
+        )
+        .unwrap();
+
+        assert!(Task::parent_creates_cycle(&conn, 1, 1).unwrap());
+        assert!(Task::parent_creates_cycle(&conn, 1, 3).unwrap());
+        assert!(Task::parent_creates_cycle(&conn, 2, 3).unwrap());
+        assert!(!Task::parent_creates_cycle(&conn, 3, 1).unwrap());
+        assert!(!Task::parent_creates_cycle(&conn, 1, 4).unwrap());
+        assert!(!Task::parent_creates_cycle(&conn, 1, 0).unwrap());
+    }
+}
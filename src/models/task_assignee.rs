@@ -0,0 +1,144 @@
+use super::DbObj;
+use rusqlite::{params, Connection, Error, Row};
+use std::collections::HashMap;
+
+/// a `Person` working on a task, a task can have several
+#[derive(Debug, Clone)]
+pub struct TaskAssignee {
+    pub id: i32,
+    pub task_id: i32,
+    pub person_id: i32,
+}
+
+impl Default for TaskAssignee {
+    fn default() -> TaskAssignee {
+        TaskAssignee::new(0, 0, 0)
+    }
+}
+
+impl DbObj for TaskAssignee {
+    fn fields() -> Vec<String> {
+        vec![
+            "id".to_string(),
+            "task_id".to_string(),
+            "person_id".to_string(),
+        ]
+    }
+
+    fn get_id(&mut self) -> i32 {
+        self.id
+    }
+
+    fn table_name() -> String {
+        "task_assignee".to_string()
+    }
+
+    fn from_row(row: &Row) -> TaskAssignee {
+        TaskAssignee::new(row.get_unwrap(0), row.get_unwrap(1), row.get_unwrap(2))
+    }
+
+    fn to_hashmap(&self) -> HashMap<String, String> {
+        let mut rv: HashMap<String, String> = HashMap::new();
+        rv.insert("id".to_string(), self.id.to_string());
+        rv.insert("task_id".to_string(), self.task_id.to_string());
+        rv.insert("person_id".to_string(), self.person_id.to_string());
+
+        rv
+    }
+
+    fn from_hashmap(fieldmap: &mut HashMap<String, String>) -> Result<Self, Error> {
+        let expected_fields = Self::fields();
+        let existing_fields = expected_fields.iter().fold(0, |acc, item| -> usize {
+            if fieldmap.contains_key(item) {
+                return acc + 1;
+            }
+            acc
+        });
+        if expected_fields.len() == existing_fields {
+            Ok(Self::new(
+                fieldmap.get("id").unwrap().parse::<i32>().unwrap(),
+                fieldmap.get("task_id").unwrap().parse::<i32>().unwrap(),
+                fieldmap.get("person_id").unwrap().parse::<i32>().unwrap(),
+            ))
+        } else {
+            Err(Error::InvalidQuery)
+        }
+    }
+}
+
+impl TaskAssignee {
+    pub fn new(id: i32, task_id: i32, person_id: i32) -> TaskAssignee {
+        TaskAssignee {
+            id,
+            task_id,
+            person_id,
+        }
+    }
+
+    /// ids of the people assigned to a task
+    pub fn person_ids(conn: &Connection, task_id: i32) -> Result<Vec<i32>, Error> {
+        Self::list(conn, format!("task_id={} ORDER BY id", task_id))
+            .map(|rows| rows.iter().map(|row| row.person_id).collect())
+    }
+
+    /// replaces the assignees of a task with `person_ids`
+    pub fn set_for_task(conn: &Connection, task_id: i32, person_ids: &[i32]) -> Result<(), Error> {
+        Self::delete_for_task(conn, task_id)?;
+        for person_id in person_ids {
+            conn.execute(
+                "INSERT INTO task_assignee (task_id, person_id) VALUES (?, ?)",
+                params![task_id, person_id],
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn delete_for_task(conn: &Connection, task_id: i32) -> Result<usize, Error> {
+        conn.execute(
+            "DELETE FROM task_assignee WHERE task_id=?",
+            params![task_id],
+        )
+    }
+
+    pub fn delete_for_person(conn: &Connection, person_id: i32) -> Result<usize, Error> {
+        conn.execute(
+            "DELETE FROM task_assignee WHERE person_id=?",
+            params![person_id],
+        )
+    }
+
+    /// select expression giving the names of the people assigned to the task aliased
+    /// `task_alias`, comma separated
+    pub fn names_column(task_alias: &str) -> String {
+        format!(
+            "(SELECT coalesce(group_concat(trim(p.first_name || ' ' || p.last_name), ', '), '') FROM task_assignee AS ta JOIN person AS p ON (ta.person_id = p.id) WHERE ta.task_id = {}.id) AS 'assignees'",
+            task_alias
+        )
+    }
+
+    /// select expression giving the initials of the people assigned to the task aliased
+    /// `task_alias`, space separated
+    pub fn initials_column(task_alias: &str) -> String {
+        format!(
+            "(SELECT coalesce(group_concat(upper(substr(p.first_name, 1, 1) || substr(p.last_name, 1, 1)), ' '), '') FROM task_assignee AS ta JOIN person AS p ON (ta.person_id = p.id) WHERE ta.task_id = {}.id) AS 'initials'",
+            task_alias
+        )
+    }
+
+    /// select expressions giving the id and name of the first person assigned to the task
+    /// aliased `task_alias`, as `assignee_id` and `assignee_name`
+    pub fn first_assignee_columns(task_alias: &str) -> String {
+        format!(
+            "(SELECT coalesce(min(ta.person_id), 0) FROM task_assignee AS ta WHERE ta.task_id = {0}.id) AS 'assignee_id', coalesce((SELECT trim(p.first_name || ' ' || p.last_name) FROM task_assignee AS ta JOIN person AS p ON (ta.person_id = p.id) WHERE ta.task_id = {0}.id ORDER BY ta.person_id LIMIT 1), '') AS 'assignee_name'",
+            task_alias
+        )
+    }
+
+    /// sql condition keeping the tasks aliased `task_alias` assigned to `person_id`
+    pub fn assigned_filter(task_alias: &str, person_id: i32) -> String {
+        format!(
+            "EXISTS (SELECT 1 FROM task_assignee AS ta WHERE ta.task_id = {}.id AND ta.person_id = {})",
+            task_alias, person_id
+        )
+    }
+}
@@ -0,0 +1,180 @@
+use super::DbObj;
+use rusqlite::{params, Connection, Error, Row};
+use std::collections::HashMap;
+
+/// a task that can only start once another one, `depends_on_id`, is finished
+#[derive(Debug, Clone)]
+pub struct TaskDependency {
+    pub id: i32,
+    pub task_id: i32,
+    pub depends_on_id: i32,
+}
+
+impl Default for TaskDependency {
+    fn default() -> TaskDependency {
+        TaskDependency::new(0, 0, 0)
+    }
+}
+
+impl DbObj for TaskDependency {
+    fn fields() -> Vec<String> {
+        vec![
+            "id".to_string(),
+            "task_id".to_string(),
+            "depends_on_id".to_string(),
+        ]
+    }
+
+    fn get_id(&mut self) -> i32 {
+        self.id
+    }
+
+    fn table_name() -> String {
+        "task_dependency".to_string()
+    }
+
+    fn from_row(row: &Row) -> TaskDependency {
+        TaskDependency::new(row.get_unwrap(0), row.get_unwrap(1), row.get_unwrap(2))
+    }
+
+    fn to_hashmap(&self) -> HashMap<String, String> {
+        let mut rv: HashMap<String, String> = HashMap::new();
+        rv.insert("id".to_string(), self.id.to_string());
+        rv.insert("task_id".to_string(), self.task_id.to_string());
+        rv.insert("depends_on_id".to_string(), self.depends_on_id.to_string());
+
+        rv
+    }
+
+    fn from_hashmap(fieldmap: &mut HashMap<String, String>) -> Result<Self, Error> {
+        let expected_fields = Self::fields();
+        let existing_fields = expected_fields.iter().fold(0, |acc, item| -> usize {
+            if fieldmap.contains_key(item) {
+                return acc + 1;
+            }
+            acc
+        });
+        if expected_fields.len() == existing_fields {
+            Ok(Self::new(
+                fieldmap.get("id").unwrap().parse::<i32>().unwrap(),
+                fieldmap.get("task_id").unwrap().parse::<i32>().unwrap(),
+                fieldmap
+                    .get("depends_on_id")
+                    .unwrap()
+                    .parse::<i32>()
+                    .unwrap(),
+            ))
+        } else {
+            Err(Error::InvalidQuery)
+        }
+    }
+}
+
+impl TaskDependency {
+    pub fn new(id: i32, task_id: i32, depends_on_id: i32) -> TaskDependency {
+        TaskDependency {
+            id,
+            task_id,
+            depends_on_id,
+        }
+    }
+
+    /// ids of the tasks a task depends on
+    pub fn depends_on_ids(conn: &Connection, task_id: i32) -> Result<Vec<i32>, Error> {
+        Self::list(conn, format!("task_id={} ORDER BY id", task_id))
+            .map(|rows| rows.iter().map(|row| row.depends_on_id).collect())
+    }
+
+    /// whether `task_id` depending on `depends_on_id` closes a loop: `depends_on_id` is the task
+    /// itself or already waits for it, directly or through other dependencies
+    pub fn creates_cycle(
+        conn: &Connection,
+        task_id: i32,
+        depends_on_id: i32,
+    ) -> Result<bool, Error> {
+        conn.query_row(
+            "WITH RECURSIVE reach(id) AS (SELECT ?1 UNION SELECT td.depends_on_id FROM task_dependency AS td JOIN reach ON (td.task_id = reach.id)) SELECT count(*) FROM reach WHERE id = ?2",
+            params![depends_on_id, task_id],
+            |row| row.get::<_, i64>(0),
+        )
+        .map(|count| count > 0)
+    }
+
+    /// the dependencies between the tasks of a project
+    pub fn for_project(conn: &Connection, project_id: i32) -> Result<Vec<TaskDependency>, Error> {
+        Self::list(
+            conn,
+            format!(
+                "task_id IN (SELECT id FROM task WHERE project_id={}) ORDER BY id",
+                project_id
+            ),
+        )
+    }
+
+    /// replaces the dependencies of a task with `depends_on_ids`
+    pub fn set_for_task(
+        conn: &Connection,
+        task_id: i32,
+        depends_on_ids: &[i32],
+    ) -> Result<(), Error> {
+        conn.execute(
+            "DELETE FROM task_dependency WHERE task_id=?",
+            params![task_id],
+        )?;
+        for depends_on_id in depends_on_ids {
+            conn.execute(
+                "INSERT INTO task_dependency (task_id, depends_on_id) VALUES (?, ?)",
+                params![task_id, depends_on_id],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// removes the links from and to a task
+    pub fn delete_for_task(conn: &Connection, task_id: i32) -> Result<usize, Error> {
+        conn.execute(
+            "DELETE FROM task_dependency WHERE task_id=?1 OR depends_on_id=?1",
+            params![task_id],
+        )
+    }
+
+    /// select expression counting the unfinished tasks the task aliased `task_alias` waits for,
+    /// as `blocked`
+    pub fn blocked_column(task_alias: &str, done_id: i32) -> String {
+        format!(
+            "(SELECT count(*) FROM task_dependency AS td JOIN task AS dt ON (td.depends_on_id = dt.id) WHERE td.task_id = {}.id AND dt.status != {}) AS 'blocked'",
+            task_alias, done_id
+        )
+    }
+
+    /// select expression giving the ids of the tasks the task aliased `task_alias` depends on,
+    /// as `#1, #2`
+    pub fn ids_column(task_alias: &str) -> String {
+        format!(
+            "(SELECT coalesce(group_concat('#' || td.depends_on_id, ', '), '') FROM task_dependency AS td WHERE td.task_id = {}.id) AS 'depends_on'",
+            task_alias
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_db, migrate_db};
+
+    #[test]
+    fn cycles_are_found_through_other_tasks() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        migrate_db(&conn).unwrap();
+        // 1 waits for 2, which waits for 3
+        TaskDependency::set_for_task(&conn, 1, &[2]).unwrap();
+        TaskDependency::set_for_task(&conn, 2, &[3]).unwrap();
+
+        assert!(TaskDependency::creates_cycle(&conn, 4, 4).unwrap());
+        assert!(TaskDependency::creates_cycle(&conn, 2, 1).unwrap());
+        assert!(TaskDependency::creates_cycle(&conn, 3, 1).unwrap());
+        assert!(!TaskDependency::creates_cycle(&conn, 1, 3).unwrap());
+        assert!(!TaskDependency::creates_cycle(&conn, 4, 1).unwrap());
+    }
+}
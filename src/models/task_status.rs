@@ -1,5 +1,5 @@
 use super::DbObj;
-use rusqlite::{Error, Row};
+use rusqlite::{Connection, Error, Row};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
@@ -60,4 +60,12 @@ impl TaskStatus {
     pub fn new(iid: i32, name: String) -> TaskStatus {
         TaskStatus { id: iid, name }
     }
+
+    /// status of finished tasks, the last column of the scrumboard
+    pub fn done_id(conn: &Connection) -> i32 {
+        conn.query_row("SELECT coalesce(max(id), 0) FROM task_status", [], |row| {
+            row.get(0)
+        })
+        .unwrap_or(0)
+    }
 }
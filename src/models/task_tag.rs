@@ -0,0 +1,166 @@
+use super::DbObj;
+use rusqlite::{params, Connection, Error, Row};
+use std::collections::HashMap;
+
+/// a free text label put on a task, a task can have several
+#[derive(Debug, Clone)]
+pub struct TaskTag {
+    pub id: i32,
+    pub task_id: i32,
+    pub name: String,
+}
+
+impl Default for TaskTag {
+    fn default() -> TaskTag {
+        TaskTag {
+            id: 0,
+            task_id: 0,
+            name: "".to_string(),
+        }
+    }
+}
+
+impl DbObj for TaskTag {
+    fn fields() -> Vec<String> {
+        vec!["id".to_string(), "task_id".to_string(), "name".to_string()]
+    }
+
+    fn get_id(&mut self) -> i32 {
+        self.id
+    }
+
+    fn table_name() -> String {
+        "task_tag".to_string()
+    }
+
+    fn from_row(row: &Row) -> TaskTag {
+        TaskTag {
+            id: row.get_unwrap(0),
+            task_id: row.get_unwrap(1),
+            name: row.get_unwrap(2),
+        }
+    }
+
+    fn to_hashmap(&self) -> HashMap<String, String> {
+        let mut rv: HashMap<String, String> = HashMap::new();
+        rv.insert("id".to_string(), self.id.to_string());
+        rv.insert("task_id".to_string(), self.task_id.to_string());
+        rv.insert("name".to_string(), self.name.to_string());
+
+        rv
+    }
+
+    fn from_hashmap(fieldmap: &mut HashMap<String, String>) -> Result<Self, Error> {
+        let expected_fields = Self::fields();
+        let existing_fields = expected_fields.iter().fold(0, |acc, item| -> usize {
+            if fieldmap.contains_key(item) {
+                return acc + 1;
+            }
+            acc
+        });
+        if expected_fields.len() == existing_fields {
+            Ok(TaskTag {
+                id: fieldmap.get("id").unwrap().parse::<i32>().unwrap(),
+                task_id: fieldmap.get("task_id").unwrap().parse::<i32>().unwrap(),
+                name: fieldmap.get("name").unwrap().to_string(),
+            })
+        } else {
+            Err(Error::InvalidQuery)
+        }
+    }
+}
+
+impl TaskTag {
+    /// tags typed comma separated, trimmed, without empty or repeated ones
+    pub fn parse(text: &str) -> Vec<String> {
+        let mut rv: Vec<String> = vec![];
+        for tag in text.split(',').map(|tag| tag.trim()) {
+            if !tag.is_empty() && !rv.iter().any(|t| t == tag) {
+                rv.push(tag.to_string());
+            }
+        }
+        rv
+    }
+
+    /// tags of a task sorted by name
+    pub fn names(conn: &Connection, task_id: i32) -> Result<Vec<String>, Error> {
+        Self::list(conn, format!("task_id={} ORDER BY name", task_id))
+            .map(|rows| rows.into_iter().map(|row| row.name).collect())
+    }
+
+    /// replaces the tags of a task with `names`
+    pub fn set_for_task(conn: &Connection, task_id: i32, names: &[String]) -> Result<(), Error> {
+        Self::delete_for_task(conn, task_id)?;
+        for name in names {
+            conn.execute(
+                "INSERT OR IGNORE INTO task_tag (task_id, name) VALUES (?, ?)",
+                params![task_id, name],
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn delete_for_task(conn: &Connection, task_id: i32) -> Result<usize, Error> {
+        conn.execute("DELETE FROM task_tag WHERE task_id=?", params![task_id])
+    }
+
+    /// select expression giving the tags of the task aliased `task_alias` sorted by name, comma
+    /// separated
+    pub fn names_column(task_alias: &str) -> String {
+        format!(
+            "(SELECT coalesce(group_concat(name, ', '), '') FROM (SELECT tt.name FROM task_tag AS tt WHERE tt.task_id = {}.id ORDER BY tt.name)) AS 'tags'",
+            task_alias
+        )
+    }
+
+    /// select expression giving the first tag by name of the task aliased `task_alias`, as
+    /// `tag_name`
+    pub fn first_tag_column(task_alias: &str) -> String {
+        format!(
+            "(SELECT coalesce(min(tt.name), '') FROM task_tag AS tt WHERE tt.task_id = {}.id) AS 'tag_name'",
+            task_alias
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_db, migrate_db};
+
+    #[test]
+    fn parse_trims_and_drops_repeats() {
+        assert_eq!(
+            TaskTag::parse(" ui, backend ,,ui, été "),
+            vec!["ui", "backend", "été"]
+        );
+        assert!(TaskTag::parse(" , ").is_empty());
+    }
+
+    #[test]
+    fn tags_are_replaced_and_listed_by_name() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        migrate_db(&conn).unwrap();
+        TaskTag::set_for_task(&conn, 1, &TaskTag::parse("ui, api")).unwrap();
+        TaskTag::set_for_task(&conn, 2, &TaskTag::parse("ops")).unwrap();
+        assert_eq!(TaskTag::names(&conn, 1).unwrap(), vec!["api", "ui"]);
+
+        TaskTag::set_for_task(&conn, 1, &TaskTag::parse("web, api")).unwrap();
+        assert_eq!(TaskTag::names(&conn, 1).unwrap(), vec!["api", "web"]);
+        assert_eq!(TaskTag::names(&conn, 2).unwrap(), vec!["ops"]);
+
+        let row: (String, String) = conn
+            .query_row(
+                &format!(
+                    "SELECT {}, {} FROM (SELECT 1 AS id) AS t",
+                    TaskTag::names_column("t"),
+                    TaskTag::first_tag_column("t")
+                ),
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(row, ("api, web".to_string(), "api".to_string()));
+    }
+}
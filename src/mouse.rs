@@ -0,0 +1,96 @@
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
+use std::time::{Duration, Instant};
+
+/// two clicks on the same cell within this delay make a double click
+const DOUBLE_CLICK_DELAY: Duration = Duration::from_millis(400);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MouseKind {
+    Click,
+    DoubleClick,
+    /// left button moved while pressed
+    Drag,
+    /// left button released, ends a drag
+    Release,
+    ScrollUp,
+    ScrollDown,
+}
+
+/// mouse event reduced to what the controllers handle, `column`/`row` are screen coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MouseInput {
+    pub kind: MouseKind,
+    pub column: u16,
+    pub row: u16,
+}
+
+impl MouseInput {
+    pub fn is_in(&self, area: Rect) -> bool {
+        self.column >= area.x
+            && self.column < area.right()
+            && self.row >= area.y
+            && self.row < area.bottom()
+    }
+
+    /// index of the first area under the mouse, areas come from the last rendered frame
+    pub fn hit(&self, areas: &[(usize, Rect)]) -> Option<usize> {
+        areas
+            .iter()
+            .find(|(_, area)| self.is_in(*area))
+            .map(|(idx, _)| *idx)
+    }
+}
+
+/// turns crossterm mouse events into `MouseInput`, detecting double clicks
+#[derive(Debug, Default)]
+pub struct MouseTracker {
+    last_click: Option<(Instant, u16, u16)>,
+}
+
+impl MouseTracker {
+    pub fn translate(&mut self, event: &MouseEvent) -> Option<MouseInput> {
+        let kind = match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let now = Instant::now();
+                let double = matches!(self.last_click, Some((at, col, row))
+                    if col == event.column && row == event.row && now - at <= DOUBLE_CLICK_DELAY);
+                if double {
+                    self.last_click = None;
+                    MouseKind::DoubleClick
+                } else {
+                    self.last_click = Some((now, event.column, event.row));
+                    MouseKind::Click
+                }
+            }
+            MouseEventKind::Drag(MouseButton::Left) => MouseKind::Drag,
+            MouseEventKind::Up(MouseButton::Left) => MouseKind::Release,
+            MouseEventKind::ScrollUp => MouseKind::ScrollUp,
+            MouseEventKind::ScrollDown => MouseKind::ScrollDown,
+            _ => return None,
+        };
+
+        Some(MouseInput {
+            kind,
+            column: event.column,
+            row: event.row,
+        })
+    }
+}
+
+/// areas of the visible items of a list or table drawn in `area` (without borders or header),
+/// `heights` are the item heights, `offset` the first visible item and `spacing` the blank lines
+/// after each item
+pub fn item_areas(area: Rect, heights: &[u16], offset: usize, spacing: u16) -> Vec<(usize, Rect)> {
+    let mut areas = vec![];
+    let mut y = area.y;
+    for (idx, height) in heights.iter().enumerate().skip(offset) {
+        if y >= area.bottom() {
+            break;
+        }
+        let height = (*height).min(area.bottom() - y);
+        areas.push((idx, Rect::new(area.x, y, area.width, height)));
+        y = y.saturating_add(height + spacing);
+    }
+    areas
+}
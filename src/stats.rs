@@ -0,0 +1,399 @@
+use crate::burndown::end_of_day;
+use crate::models::{
+    project::Project, status_change::StatusChange, task::Task, task_status::TaskStatus, DbObj,
+};
+use crate::UtilFns;
+use chrono::{Datelike, Duration, Local, TimeZone};
+use rusqlite::{Connection, Error};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// periods offered by the metrics screen, in days
+pub const STATS_PERIODS: [i64; 4] = [14, 30, 90, 180];
+/// upper bounds in days of the lead and cycle time histogram buckets, the last one is open
+pub const TIME_BUCKETS: [(f64, &str); 6] = [
+    (1.0, "<1d"),
+    (3.0, "1-3d"),
+    (7.0, "3-7d"),
+    (14.0, "1-2w"),
+    (30.0, "2-4w"),
+    (f64::MAX, ">4w"),
+];
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// number of tasks in each status at the end of a day, in the order of `FlowStats.statuses`
+#[derive(Debug, Clone, Serialize)]
+pub struct CfdDay {
+    pub date: String,
+    pub counts: Vec<i32>,
+}
+
+/// durations in days with their average and percentiles
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Durations {
+    pub days: Vec<f64>,
+    pub average: f64,
+    pub p50: f64,
+    pub p85: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WeekThroughput {
+    /// monday of the week
+    pub week: String,
+    pub done: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WipItem {
+    pub task_id: i32,
+    pub name: String,
+    pub status: String,
+    /// days since the task was first moved to a work in progress status
+    pub age_days: f64,
+}
+
+/// flow metrics of a project, or of all of them, computed from the status changes of the tasks.
+/// the first status is the backlog, the last one done and the ones in between are in progress
+#[derive(Debug, Clone, Serialize)]
+pub struct FlowStats {
+    pub project: String,
+    pub from: String,
+    pub to: String,
+    pub statuses: Vec<String>,
+    /// cumulative flow diagram, one entry per day
+    pub cfd: Vec<CfdDay>,
+    /// created -> done, for the tasks finished during the period
+    pub lead_time: Durations,
+    /// first work in progress status -> done, for the tasks finished during the period
+    pub cycle_time: Durations,
+    pub throughput: Vec<WeekThroughput>,
+    /// tasks in progress, the oldest first
+    pub wip_age: Vec<WipItem>,
+}
+
+impl Durations {
+    fn from_days(mut days: Vec<f64>) -> Durations {
+        if days.is_empty() {
+            return Durations::default();
+        }
+        days.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        // nearest rank percentile
+        let percentile = |p: f64| days[((p * days.len() as f64).ceil() as usize).max(1) - 1];
+        Durations {
+            average: round(days.iter().sum::<f64>() / days.len() as f64),
+            p50: percentile(0.5),
+            p85: percentile(0.85),
+            days,
+        }
+    }
+
+    /// how many durations fall in each of `TIME_BUCKETS`
+    pub fn histogram(&self) -> Vec<u64> {
+        let mut rv = vec![0; TIME_BUCKETS.len()];
+        for day in &self.days {
+            if let Some(idx) = TIME_BUCKETS.iter().position(|(max, _)| day < max) {
+                rv[idx] += 1;
+            }
+        }
+        rv
+    }
+}
+
+impl FlowStats {
+    /// metrics of the last `period` days for a project, or for all projects when None
+    pub fn compute(
+        conn: &Connection,
+        project: Option<&Project>,
+        period: i64,
+    ) -> Result<FlowStats, Error> {
+        let (tasks, changes) = match project {
+            Some(project) => (
+                Task::list(conn, format!("project_id={}", project.id))?,
+                StatusChange::for_project(conn, project.id)?,
+            ),
+            None => (Task::list(conn, "".to_string())?, StatusChange::all(conn)?),
+        };
+        let statuses = TaskStatus::list(conn, "id > 0 ORDER BY id".to_string())?;
+        let backlog_id = statuses.first().map(|s| s.id).unwrap_or(0);
+        let done_id = TaskStatus::done_id(conn);
+        let is_wip = |status: i32| status != backlog_id && status != done_id;
+        let status_name = |id: i32| {
+            statuses
+                .iter()
+                .find(|s| s.id == id)
+                .map(|s| s.name.clone())
+                .unwrap_or_default()
+        };
+
+        let to = Local::now().date_naive();
+        let from = to - Duration::days(period - 1);
+        let period_start = end_of_day(from - Duration::days(1)).unwrap_or(0);
+        let now = Local::now().timestamp();
+
+        // changes are sorted by time, so are the histories
+        let mut histories: HashMap<i32, Vec<&StatusChange>> = HashMap::new();
+        for change in &changes {
+            histories.entry(change.task_id).or_default().push(change);
+        }
+        let history = |task: &Task| -> &[&StatusChange] {
+            histories
+                .get(&task.id)
+                .map_or(&[], |changes| changes.as_slice())
+        };
+
+        let mut cfd = vec![];
+        let mut day = from;
+        while day <= to {
+            let timestamp = end_of_day(day).unwrap_or(now);
+            let mut counts = vec![0; statuses.len()];
+            for task in tasks.iter().filter(|t| t.created_at <= timestamp) {
+                let status =
+                    StatusChange::status_at(history(task), timestamp).unwrap_or(backlog_id);
+                if let Some(idx) = statuses.iter().position(|s| s.id == status) {
+                    counts[idx] += 1;
+                }
+            }
+            cfd.push(CfdDay {
+                date: day.to_string(),
+                counts,
+            });
+            day += Duration::days(1);
+        }
+
+        let mut lead = vec![];
+        let mut cycle = vec![];
+        let mut done_dates = vec![];
+        let mut wip_age = vec![];
+        for task in &tasks {
+            let task_changes = history(task);
+            let started = task_changes
+                .iter()
+                .find(|c| is_wip(c.to_status))
+                .map(|c| c.changed_at);
+            if task.status == done_id {
+                let finished = match task_changes.iter().rev().find(|c| c.to_status == done_id) {
+                    Some(change) if change.changed_at > period_start => change.changed_at,
+                    _ => continue,
+                };
+                if task.created_at > 0 {
+                    lead.push(days_between(task.created_at, finished));
+                }
+                if let Some(started) = started {
+                    cycle.push(days_between(started, finished));
+                }
+                if let Some(date) = Local.timestamp_opt(finished, 0).single() {
+                    done_dates.push(date.date_naive());
+                }
+            } else if is_wip(task.status) {
+                wip_age.push(WipItem {
+                    task_id: task.id,
+                    name: task.name.clone(),
+                    status: status_name(task.status),
+                    age_days: days_between(started.unwrap_or(task.updated_at), now),
+                });
+            }
+        }
+        wip_age.sort_by(|a, b| b.age_days.partial_cmp(&a.age_days).unwrap());
+
+        let mut throughput = vec![];
+        let mut monday = from - Duration::days(from.weekday().num_days_from_monday() as i64);
+        while monday <= to {
+            let sunday = monday + Duration::days(6);
+            throughput.push(WeekThroughput {
+                week: monday.to_string(),
+                done: done_dates
+                    .iter()
+                    .filter(|d| **d >= monday && **d <= sunday)
+                    .count() as i32,
+            });
+            monday += Duration::days(7);
+        }
+
+        Ok(FlowStats {
+            project: match project {
+                Some(project) => project.name.clone(),
+                None => "All projects".to_string(),
+            },
+            from: from.to_string(),
+            to: to.to_string(),
+            statuses: statuses.iter().map(|s| s.name.clone()).collect(),
+            cfd,
+            lead_time: Durations::from_days(lead),
+            cycle_time: Durations::from_days(cycle),
+            throughput,
+            wip_age,
+        })
+    }
+
+    /// a short report of the metrics, for `rask stats`
+    pub fn to_text(&self) -> String {
+        let mut rv = format!("{} from {} to {}\n\n", self.project, self.from, self.to);
+        if let Some(today) = self.cfd.last() {
+            rv.push_str("Tasks per status:\n");
+            for (name, count) in self.statuses.iter().zip(today.counts.iter()) {
+                rv.push_str(&format!("  {:<12} {}\n", name, count));
+            }
+        }
+        for (name, times) in [
+            ("Lead time", &self.lead_time),
+            ("Cycle time", &self.cycle_time),
+        ] {
+            rv.push_str(&format!(
+                "{}: {} tasks, average {}d, p50 {}d, p85 {}d\n",
+                name,
+                times.days.len(),
+                times.average,
+                times.p50,
+                times.p85
+            ));
+        }
+        rv.push_str("Throughput per week:\n");
+        for week in &self.throughput {
+            rv.push_str(&format!("  {}  {}\n", week.week, week.done));
+        }
+        rv.push_str("Work in progress:\n");
+        for item in &self.wip_age {
+            rv.push_str(&format!(
+                "  #{} {} ({}, {}d)\n",
+                item.task_id, item.name, item.status, item.age_days
+            ));
+        }
+        rv
+    }
+}
+
+fn round(days: f64) -> f64 {
+    (days * 10.0).round() / 10.0
+}
+
+fn days_between(from: i64, to: i64) -> f64 {
+    round(((to - from).max(0) as f64) / SECONDS_PER_DAY)
+}
+
+#[derive(Clone)]
+struct StatsExport;
+
+impl UtilFns for StatsExport {}
+
+/// the metrics of a project, or of all projects, as text or json, for `rask stats`
+pub fn export(project_id: Option<i32>, period: i64, format: &str) -> Result<String, String> {
+    if period < 1 {
+        return Err("the period must be at least one day".to_string());
+    }
+    let conn = StatsExport::get_db_connection().map_err(|e| e.to_string())?;
+    let project = match project_id {
+        Some(id) => {
+            Some(Project::get_by_id(&conn, id).map_err(|_| format!("no project with id {}", id))?)
+        }
+        None => None,
+    };
+    let stats = FlowStats::compute(&conn, project.as_ref(), period).map_err(|e| e.to_string())?;
+    match format {
+        "text" => Ok(stats.to_text()),
+        "json" => serde_json::to_string_pretty(&stats)
+            .map(|json| format!("{}\n", json))
+            .map_err(|e| e.to_string()),
+        _ => Err(format!("unknown format '{}', use text or json", format)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_db, migrate_db};
+
+    #[test]
+    fn durations_use_nearest_rank_percentiles() {
+        let times = Durations::from_days(vec![5.0, 1.0, 3.0, 2.0, 4.0]);
+        assert_eq!(times.days, vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(times.average, 3.0);
+        assert_eq!(times.p50, 3.0);
+        assert_eq!(times.p85, 5.0);
+
+        let times = Durations::from_days((1..=10).map(f64::from).collect());
+        assert_eq!(times.average, 5.5);
+        assert_eq!(times.p50, 5.0);
+        assert_eq!(times.p85, 9.0);
+
+        let times = Durations::from_days(vec![7.0]);
+        assert_eq!((times.p50, times.p85), (7.0, 7.0));
+        assert!(Durations::from_days(vec![]).days.is_empty());
+    }
+
+    #[test]
+    fn histogram_buckets_exclude_their_upper_bound() {
+        let times = Durations::from_days(vec![
+            0.5, 1.0, 2.9, 3.0, 6.0, 7.0, 13.9, 14.0, 29.0, 30.0, 100.0,
+        ]);
+        assert_eq!(times.histogram(), vec![1, 2, 2, 2, 2, 2]);
+        assert_eq!(
+            Durations::default().histogram(),
+            vec![0; TIME_BUCKETS.len()]
+        );
+    }
+
+    fn days_ago(days: i64) -> i64 {
+        Local::now().timestamp() - days * 86400
+    }
+
+    /// a task created `created` days ago that went through `history`, (status, days ago) pairs
+    fn add_task(conn: &Connection, name: &str, created: i64, history: &[(i32, i64)]) -> i32 {
+        let task = Task {
+            name: name.to_string(),
+            status: history.last().map_or(1, |(status, _)| *status),
+            created_by: 1,
+            ..Task::default()
+        };
+        let id = task.save(conn).unwrap().id;
+        conn.execute(
+            "UPDATE task SET created_at = ?1, updated_at = ?1 WHERE id = ?2",
+            (days_ago(created), id),
+        )
+        .unwrap();
+        conn.execute("DELETE FROM task_status_change WHERE task_id = ?1", [id])
+            .unwrap();
+        let mut from_status = 0;
+        for (status, days) in history {
+            conn.execute(
+                "INSERT INTO task_status_change (task_id, from_status, to_status, changed_at) VALUES (?1, ?2, ?3, ?4)",
+                (id, from_status, status, days_ago(*days)),
+            )
+            .unwrap();
+            from_status = *status;
+        }
+        id
+    }
+
+    #[test]
+    fn flow_metrics_of_the_period() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        migrate_db(&conn).unwrap();
+        add_task(&conn, "started then done", 10, &[(1, 10), (2, 8), (3, 2)]);
+        add_task(&conn, "done directly", 5, &[(1, 5), (3, 1)]);
+        add_task(&conn, "done long ago", 40, &[(1, 40), (3, 30)]);
+        let wip = add_task(&conn, "in progress", 6, &[(1, 6), (2, 3)]);
+        add_task(&conn, "waiting", 20, &[(1, 20)]);
+
+        let stats = FlowStats::compute(&conn, None, 14).unwrap();
+        assert_eq!(stats.statuses, vec!["BACKLOG", "WIP", "DONE"]);
+
+        // only the tasks finished during the period count
+        assert_eq!(stats.lead_time.days, vec![4.0, 8.0]);
+        assert_eq!(stats.cycle_time.days, vec![6.0]);
+        assert_eq!(
+            stats.throughput.iter().map(|week| week.done).sum::<i32>(),
+            2
+        );
+
+        assert_eq!(stats.wip_age.len(), 1);
+        assert_eq!(stats.wip_age[0].task_id, wip);
+        assert_eq!(stats.wip_age[0].status, "WIP");
+        assert_eq!(stats.wip_age[0].age_days, 3.0);
+
+        assert_eq!(stats.cfd.len(), 14);
+        assert_eq!(stats.cfd[0].counts, vec![1, 0, 1]);
+        assert_eq!(stats.cfd[13].counts, vec![1, 1, 3]);
+    }
+}
@@ -0,0 +1,390 @@
+use crate::config::{self, UrgencyConfig};
+use crate::models::task::{Task, DUE_DATE_FORMAT};
+use crate::models::{
+    task_dependency::TaskDependency, task_status::TaskStatus, task_tag::TaskTag, DbObj,
+};
+use crate::UtilFns;
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+use rusqlite::{Connection, Error};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// share of the priority coefficient each priority gets, P0 first
+const PRIORITY_FACTORS: [f64; 4] = [1.0, 0.65, 0.3, 0.0];
+/// estimate getting the whole weight coefficient
+const MAX_WEIGHT: f64 = 13.0;
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// a task of the Next tab and of `rask next`
+#[derive(Debug, Clone, Serialize)]
+pub struct NextTask {
+    pub id: i32,
+    pub name: String,
+    pub project: String,
+    pub priority: String,
+    pub due_date: String,
+    pub urgency: f64,
+}
+
+/// urgency of a task with `tags` tags, in the style of taskwarrior: the sum of the `urgency`
+/// coefficients of the config, each one multiplied by how much it applies to the task (0 to 1)
+pub fn score(
+    cfg: &UrgencyConfig,
+    task: &Task,
+    blocked: bool,
+    tags: usize,
+    now: &DateTime<Local>,
+) -> f64 {
+    let priority = PRIORITY_FACTORS
+        .get(task.priority as usize)
+        .copied()
+        .unwrap_or(0.0);
+    let due = task
+        .due()
+        .map_or(0.0, |due| due_factor(due, now.date_naive()));
+    let age = match Local.timestamp_opt(task.created_at, 0).single() {
+        Some(created) if task.created_at > 0 => {
+            let days = (now.timestamp() - created.timestamp()).max(0) as f64 / SECONDS_PER_DAY;
+            (days / cfg.age_max).min(1.0)
+        }
+        _ => 0.0,
+    };
+    let weight = (task.weight.max(0) as f64 / MAX_WEIGHT).min(1.0);
+    let blocked = if blocked { 1.0 } else { 0.0 };
+    let tags = match tags {
+        0 => 0.0,
+        1 => 0.8,
+        2 => 0.9,
+        _ => 1.0,
+    };
+
+    cfg.priority * priority
+        + cfg.due * due
+        + cfg.age * age
+        + cfg.blocked * blocked
+        + cfg.weight * weight
+        + cfg.tags * tags
+}
+
+/// 0.2 up to two weeks before the due date, then growing to 1 a week after it
+fn due_factor(due: NaiveDate, today: NaiveDate) -> f64 {
+    let overdue = (today - due).num_days() as f64;
+    if overdue >= 7.0 {
+        1.0
+    } else if overdue >= -14.0 {
+        (overdue + 14.0) * 0.8 / 21.0 + 0.2
+    } else {
+        0.2
+    }
+}
+
+/// urgency of a task row read with `TaskDependency::blocked_column` and
+/// `TaskTag::names_column`, None when it isn't a task
+pub fn row_score(row: &HashMap<String, String>, now: &DateTime<Local>) -> Option<f64> {
+    let blocked = row.get("blocked").is_some_and(|count| count != "0");
+    let task = Task::from_hashmap(&mut row.clone()).ok()?;
+    Some(round(score(
+        &config::get().urgency,
+        &task,
+        blocked,
+        tag_count(row),
+        now,
+    )))
+}
+
+/// number of tags of a row read with `TaskTag::names_column`
+fn tag_count(row: &HashMap<String, String>) -> usize {
+    row.get("tags").map_or(0, |tags| {
+        tags.split(", ").filter(|tag| !tag.is_empty()).count()
+    })
+}
+
+/// the `count` most urgent tasks of every project that can be worked on: not done, not waiting
+/// for unfinished tasks and not starting later
+pub fn next_tasks(conn: &Connection, count: usize) -> Result<Vec<NextTask>, Error> {
+    let done_id = TaskStatus::done_id(conn);
+    let now = Local::now();
+    let query = format!(
+        "SELECT t.*, coalesce(p.name, '') AS 'project_name', {}, {} FROM task AS t LEFT JOIN project AS p ON (t.project_id = p.id) WHERE t.status != {} AND (t.start_date = '' OR t.start_date <= '{}')",
+        TaskDependency::blocked_column("t", done_id),
+        TaskTag::names_column("t"),
+        done_id,
+        now.date_naive().format(DUE_DATE_FORMAT)
+    );
+    let mut tasks = vec![];
+    for row in Task::query(conn, query)? {
+        if row.get("blocked").is_some_and(|count| count != "0") {
+            continue;
+        }
+        let task = Task::from_hashmap(&mut row.clone())?;
+        tasks.push(NextTask {
+            id: task.id,
+            project: row.get("project_name").cloned().unwrap_or_default(),
+            priority: task.priority_label().to_string(),
+            urgency: round(score(
+                &config::get().urgency,
+                &task,
+                false,
+                tag_count(&row),
+                &now,
+            )),
+            due_date: task.due_date,
+            name: task.name,
+        });
+    }
+    tasks.sort_by(|a, b| {
+        b.urgency
+            .partial_cmp(&a.urgency)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.id.cmp(&b.id))
+    });
+    tasks.truncate(count);
+    Ok(tasks)
+}
+
+fn to_text(tasks: &[NextTask]) -> String {
+    if tasks.is_empty() {
+        return "no task to work on\n".to_string();
+    }
+    let mut rv = format!(
+        "{:>7}  {:<8}  {:>6}  {:<20}  {}\n",
+        "Urgency", "Priority", "Id", "Project", "Task"
+    );
+    for task in tasks {
+        let project: String = match task.project.is_empty() {
+            true => "Inbox".to_string(),
+            false => task.project.chars().take(20).collect(),
+        };
+        let due = match task.due_date.is_empty() {
+            true => "".to_string(),
+            false => format!(" (due {})", task.due_date),
+        };
+        rv.push_str(&format!(
+            "{:>7.1}  {:<8}  {:>6}  {:<20}  {}{}\n",
+            task.urgency,
+            task.priority,
+            format!("#{}", task.id),
+            project,
+            task.name,
+            due
+        ));
+    }
+    rv
+}
+
+fn round(urgency: f64) -> f64 {
+    (urgency * 10.0).round() / 10.0
+}
+
+#[derive(Clone)]
+struct NextExport;
+
+impl UtilFns for NextExport {}
+
+/// the most urgent tasks as text or json, for `rask next`
+pub fn export(count: Option<usize>, format: &str) -> Result<String, String> {
+    let count = count.unwrap_or(config::get().urgency.next_count);
+    if count < 1 {
+        return Err("the count must be at least 1".to_string());
+    }
+    let conn = NextExport::get_db_connection().map_err(|e| e.to_string())?;
+    let tasks = next_tasks(&conn, count).map_err(|e| e.to_string())?;
+    match format {
+        "text" => Ok(to_text(&tasks)),
+        "json" => serde_json::to_string_pretty(&tasks)
+            .map(|json| format!("{}\n", json))
+            .map_err(|e| e.to_string()),
+        _ => Err(format!("unknown format '{}', use text or json", format)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_db, migrate_db};
+    use chrono::Duration;
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap()
+    }
+
+    /// a P3 task without due date, age or estimate, it scores 0
+    fn plain_task() -> Task {
+        Task {
+            priority: 3,
+            ..Task::default()
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn priority_factors() {
+        let cfg = UrgencyConfig::default();
+        let scores: Vec<f64> = (0..4)
+            .map(|priority| {
+                let task = Task {
+                    priority,
+                    ..plain_task()
+                };
+                score(&cfg, &task, false, 0, &now())
+            })
+            .collect();
+        for (actual, expected) in scores.iter().zip([6.0, 3.9, 1.8, 0.0]) {
+            assert_close(*actual, expected);
+        }
+    }
+
+    #[test]
+    fn due_ramp() {
+        let today = now().date_naive();
+        let factor = |overdue: i64| due_factor(today - Duration::days(overdue), today);
+        assert_close(factor(-30), 0.2);
+        assert_close(factor(-14), 0.2);
+        assert_close(factor(0), 14.0 * 0.8 / 21.0 + 0.2);
+        assert_close(factor(7), 1.0);
+        assert_close(factor(30), 1.0);
+        assert!(factor(-7) < factor(0) && factor(0) < factor(6));
+    }
+
+    #[test]
+    fn due_date_counts_with_its_coefficient() {
+        let cfg = UrgencyConfig::default();
+        let task = Task {
+            due_date: (now().date_naive() - Duration::days(7))
+                .format(DUE_DATE_FORMAT)
+                .to_string(),
+            ..plain_task()
+        };
+        assert_close(score(&cfg, &task, false, 0, &now()), cfg.due);
+    }
+
+    #[test]
+    fn age_is_capped_at_age_max() {
+        let cfg = UrgencyConfig {
+            age_max: 10.0,
+            ..UrgencyConfig::default()
+        };
+        let created = |days: i64| Task {
+            created_at: (now() - Duration::days(days)).timestamp(),
+            ..plain_task()
+        };
+        assert_close(score(&cfg, &created(5), false, 0, &now()), cfg.age * 0.5);
+        assert_close(score(&cfg, &created(10), false, 0, &now()), cfg.age);
+        assert_close(score(&cfg, &created(100), false, 0, &now()), cfg.age);
+        // tasks without a creation time have no age
+        assert_close(score(&cfg, &created(0), false, 0, &now()), 0.0);
+        assert_close(score(&cfg, &plain_task(), false, 0, &now()), 0.0);
+    }
+
+    #[test]
+    fn weight_is_capped_at_13() {
+        let cfg = UrgencyConfig::default();
+        let weighted = |weight: i32| Task {
+            weight,
+            ..plain_task()
+        };
+        assert_close(score(&cfg, &weighted(0), false, 0, &now()), 0.0);
+        assert_close(
+            score(&cfg, &weighted(5), false, 0, &now()),
+            cfg.weight * 5.0 / 13.0,
+        );
+        assert_close(score(&cfg, &weighted(13), false, 0, &now()), cfg.weight);
+        assert_close(score(&cfg, &weighted(40), false, 0, &now()), cfg.weight);
+    }
+
+    #[test]
+    fn blocked_tasks_get_the_blocked_coefficient() {
+        let cfg = UrgencyConfig::default();
+        assert_close(score(&cfg, &plain_task(), true, 0, &now()), cfg.blocked);
+    }
+
+    #[test]
+    fn tags_count_up_to_three() {
+        let cfg = UrgencyConfig::default();
+        let factors: Vec<f64> = (0..5)
+            .map(|tags| score(&cfg, &plain_task(), false, tags, &now()) / cfg.tags)
+            .collect();
+        for (actual, expected) in factors.iter().zip([0.0, 0.8, 0.9, 1.0, 1.0]) {
+            assert_close(*actual, expected);
+        }
+    }
+
+    fn test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        migrate_db(&conn).unwrap();
+        conn
+    }
+
+    fn add_task(conn: &Connection, name: &str, priority: i32, status: i32, start: &str) -> i32 {
+        let task = Task {
+            name: name.to_string(),
+            priority,
+            status,
+            start_date: start.to_string(),
+            created_by: 1,
+            ..Task::default()
+        };
+        task.save(conn).unwrap().id
+    }
+
+    #[test]
+    fn next_tasks_leaves_out_blocked_done_and_later_tasks() {
+        let conn = test_db();
+        let done_id = TaskStatus::done_id(&conn);
+        let urgent = add_task(&conn, "urgent", 0, 1, "");
+        let low = add_task(&conn, "low", 3, 1, "");
+        let blocked = add_task(&conn, "blocked", 1, 1, "");
+        let blocker = add_task(&conn, "blocker", 2, 2, "");
+        add_task(&conn, "later", 0, 1, "2999-01-01");
+        add_task(&conn, "done", 0, done_id, "");
+        let low_too = add_task(&conn, "low too", 3, 1, "2000-01-01");
+        TaskDependency::set_for_task(&conn, blocked, &[blocker]).unwrap();
+
+        let ids: Vec<i32> = next_tasks(&conn, 10)
+            .unwrap()
+            .iter()
+            .map(|task| task.id)
+            .collect();
+        // most urgent first, ties by id
+        assert_eq!(ids, vec![urgent, blocker, low, low_too]);
+    }
+
+    #[test]
+    fn next_tasks_keeps_the_count_most_urgent() {
+        let conn = test_db();
+        let low = add_task(&conn, "low", 3, 1, "");
+        let urgent = add_task(&conn, "urgent", 0, 1, "");
+        let normal = add_task(&conn, "normal", 2, 1, "");
+        let tasks = next_tasks(&conn, 2).unwrap();
+        assert_eq!(
+            tasks.iter().map(|task| task.id).collect::<Vec<i32>>(),
+            vec![urgent, normal]
+        );
+        assert_eq!(tasks[0].priority, "P0");
+        assert!(tasks[0].urgency > tasks[1].urgency);
+        assert_eq!(next_tasks(&conn, 3).unwrap().last().unwrap().id, low);
+    }
+
+    #[test]
+    fn next_tasks_count_the_tags() {
+        let conn = test_db();
+        let plain = add_task(&conn, "plain", 3, 1, "");
+        let tagged = add_task(&conn, "tagged", 3, 1, "");
+        TaskTag::set_for_task(&conn, tagged, &TaskTag::parse("ui, api")).unwrap();
+        let tasks = next_tasks(&conn, 10).unwrap();
+        assert_eq!(
+            tasks.iter().map(|task| task.id).collect::<Vec<i32>>(),
+            vec![tagged, plain]
+        );
+        assert_close(tasks[0].urgency - tasks[1].urgency, 0.9);
+    }
+}
@@ -0,0 +1,99 @@
+use ratatui::{layout::*, symbols, text::*, widgets::*, Frame};
+
+use crate::burndown::{BurnChart, ChartKind};
+use crate::views::theme::Theme;
+use crate::views::titled_box;
+
+/// the chart with its ideal line, a sparkline of the work completed each day and the keys
+pub fn chart_panel(chart: &BurnChart, message: &str, f: &mut Frame, area: Rect, theme: &Theme) {
+    let panel = titled_box(format!(
+        "{} {} ({}, {} to {})",
+        chart.title,
+        chart.kind.name(),
+        chart.unit,
+        chart.start,
+        chart.end
+    ));
+    let inner = panel.inner(area);
+    f.render_widget(panel, area);
+    let zones = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Min(6),
+                Constraint::Length(4),
+                Constraint::Length(2),
+            ]
+            .as_ref(),
+        )
+        .split(inner);
+
+    let actual = chart.actual();
+    let ideal = chart.ideal();
+    let scope = chart.scope();
+    let (actual_name, actual_style) = match chart.kind {
+        ChartKind::Burndown => ("remaining", theme.warning_style()),
+        ChartKind::Burnup => ("completed", theme.success_style()),
+    };
+    let mut datasets = vec![
+        Dataset::default()
+            .name("ideal")
+            .marker(symbols::Marker::Dot)
+            .graph_type(GraphType::Line)
+            .style(theme.muted_style())
+            .data(&ideal),
+        Dataset::default()
+            .name(actual_name)
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(actual_style)
+            .data(&actual),
+    ];
+    if !scope.is_empty() {
+        datasets.push(
+            Dataset::default()
+                .name("scope")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(theme.text_style())
+                .data(&scope),
+        );
+    }
+    let last = (chart.day_count() - 1).max(1) as f64;
+    let max = chart.max_value() as f64;
+    let content = Chart::new(datasets)
+        .x_axis(
+            Axis::default()
+                .style(theme.muted_style())
+                .bounds([0.0, last])
+                .labels(vec![
+                    Span::raw(chart.start.to_string()),
+                    Span::raw(chart.end.to_string()),
+                ]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(theme.muted_style())
+                .bounds([0.0, max])
+                .labels(vec![
+                    Span::raw("0"),
+                    Span::raw(chart.max_value().to_string()),
+                ]),
+        );
+    f.render_widget(content, zones[0]);
+
+    let done = chart.daily_done();
+    let sparkline = Sparkline::default()
+        .block(Block::default().title(format!("{} completed per day", chart.unit)))
+        .style(theme.success_style())
+        .data(&done);
+    f.render_widget(sparkline, zones[1]);
+
+    let hints = Paragraph::new(vec![
+        Line::from(vec![Span::raw(
+            "'Space' burndown/burnup, 'Up'/'Down' project or sprint, 's' saves as text and svg, 'Esc' to close.",
+        )]),
+        Line::from(vec![Span::styled(message.to_string(), theme.muted_style())]),
+    ]);
+    f.render_widget(hints, zones[2]);
+}
@@ -0,0 +1,182 @@
+use chrono::{Datelike, Local, NaiveDate};
+use ratatui::{
+    layout::*,
+    style::Modifier,
+    text::*,
+    widgets::{calendar::*, *},
+    Frame,
+};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use time::{Date, Month};
+
+use crate::controllers::calendar_controller::CalendarCtrl;
+use crate::models::task::DUE_DATE_FORMAT;
+use crate::views::theme::Theme;
+use crate::views::{generic_popup, list_ui, titled_box, FORM_FIELD_WIDTH};
+
+/// the month grid on the left, the tasks due on the selected day on the right
+pub fn calendar_view(ctrl: &mut CalendarCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(30), Constraint::Min(20)].as_ref())
+        .split(area);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(12), Constraint::Min(1)].as_ref())
+        .split(cols[0]);
+
+    month_grid(ctrl, f, rows[0], theme);
+    let hints = Paragraph::new(vec![
+        Line::from("Arrows move the day,"),
+        Line::from("'P'/'N' the month,"),
+        Line::from("'Home' goes to today,"),
+        Line::from("'Enter' lists the tasks."),
+        Line::from(""),
+        Line::from(Span::styled("overdue", theme.error_style())),
+        Line::from(Span::styled("tasks due", theme.warning_style())),
+    ])
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(hints, rows[1]);
+
+    day_list(ctrl, f, cols[1], theme);
+    if ctrl.show_task {
+        task_popup(ctrl, f);
+    }
+    if ctrl.reschedule.is_some() {
+        reschedule_popup(ctrl, f, theme);
+    }
+}
+
+fn to_date(day: NaiveDate) -> Option<Date> {
+    let month = Month::try_from(day.month() as u8).ok()?;
+    Date::from_calendar_date(day.year(), month, day.day() as u8).ok()
+}
+
+fn month_grid(ctrl: &CalendarCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let mut events = CalendarEventStore::default();
+    if let Some(today) = to_date(Local::now().date_naive()) {
+        events.add(today, theme.text_style().add_modifier(Modifier::UNDERLINED));
+    }
+    // overdue tasks go last so they win over the others due the same day
+    let mut tasks: Vec<&HashMap<String, String>> = ctrl.tasks.iter().collect();
+    tasks.sort_by_key(|task| ctrl.is_overdue(task));
+    for task in tasks {
+        let day = task
+            .get("due_date")
+            .and_then(|d| NaiveDate::parse_from_str(d, DUE_DATE_FORMAT).ok())
+            .and_then(to_date);
+        if let Some(day) = day {
+            events.add(
+                day,
+                match ctrl.is_overdue(task) {
+                    true => theme.error_style(),
+                    false => theme.warning_style().add_modifier(Modifier::BOLD),
+                },
+            );
+        }
+    }
+    let selected = match to_date(ctrl.selected) {
+        Some(selected) => selected,
+        None => return,
+    };
+    let selected_style = match ctrl.day_focus {
+        true => theme.row_selected_style(),
+        false => theme.selection_style(),
+    };
+    events.add(selected, selected_style);
+
+    let calendar = Monthly::new(selected, events)
+        .block(titled_box("Calendar".to_string()))
+        .show_month_header(theme.heading_style(2))
+        .show_weekdays_header(theme.muted_style())
+        .default_style(theme.text_style());
+    f.render_widget(calendar, area);
+}
+
+fn task_label(task: &HashMap<String, String>) -> String {
+    format!(
+        "#{} {} [{}] {}",
+        task.get("id").cloned().unwrap_or_default(),
+        task.get("name").cloned().unwrap_or_default(),
+        task.get("status_name").cloned().unwrap_or_default(),
+        task.get("project_name").cloned().unwrap_or_default()
+    )
+}
+
+fn day_list(ctrl: &mut CalendarCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let tasks = ctrl.day_tasks();
+    let items: Vec<ListItem> = tasks
+        .iter()
+        .map(|task| {
+            let style = match ctrl.is_overdue(task) {
+                true => theme.error_style(),
+                false => theme.text_style(),
+            };
+            ListItem::new(task_label(task)).style(style)
+        })
+        .collect();
+    let title = match ctrl.day_focus {
+        true => format!(
+            "<{}> ('Enter' open, 'P'/'N' a day earlier/later, 'r' reschedule, 'Esc' back)",
+            ctrl.selected.format("%A %d %B %Y")
+        ),
+        false => format!(
+            "{} - {} task(s)",
+            ctrl.selected.format("%A %d %B %Y"),
+            items.len()
+        ),
+    };
+    let mut state = ctrl.l_state.clone();
+    if !ctrl.day_focus {
+        state.select(None);
+    }
+    f.render_stateful_widget(list_ui(items, title, theme), area, &mut state);
+}
+
+fn task_popup(ctrl: &CalendarCtrl, f: &mut Frame) {
+    let task = match ctrl.selected_task() {
+        Some(task) => task,
+        None => return,
+    };
+    let field = |key: &str| task.get(key).cloned().unwrap_or_default();
+    let mut lines = vec![
+        Line::from(format!("Project : {}", field("project_name"))),
+        Line::from(format!("Status : {}", field("status_name"))),
+        Line::from(format!("Assigned to : {}", field("assignees"))),
+        Line::from(format!("Due date : {}", field("due_date"))),
+        Line::from(""),
+    ];
+    lines.extend(
+        field("description")
+            .lines()
+            .map(|l| Line::from(l.to_string())),
+    );
+    let content = Paragraph::new(lines).wrap(Wrap { trim: false });
+    generic_popup(
+        format!("#{} {}", field("id"), field("name")),
+        content,
+        "Press 'Esc' to close.".to_string(),
+        f,
+    );
+}
+
+fn reschedule_popup(ctrl: &CalendarCtrl, f: &mut Frame, theme: &Theme) {
+    let input = match &ctrl.reschedule {
+        Some(input) => input,
+        None => return,
+    };
+    let mut line = vec![Span::raw("Due date : ")];
+    line.extend(input.spans(
+        FORM_FIELD_WIDTH / 2,
+        theme.field_style(true),
+        theme.cursor_style(),
+    ));
+    let content = Paragraph::new(vec![Line::from(""), Line::from(line)]);
+    generic_popup(
+        "Reschedule".to_string(),
+        content,
+        "Type the new date as YYYY-MM-DD, 'Enter' to save, 'Esc' to cancel.".to_string(),
+        f,
+    );
+}
@@ -1,51 +1,70 @@
-use ratatui::{layout::*, style::*, text::*, widgets::*, Frame};
+use ratatui::{layout::*, text::*, widgets::*, Frame};
 
 use crate::controllers::contact_controller::ContactCtrl;
-use crate::views::{string_min_size, titled_box};
-pub fn list_view(parent_controller: &mut ContactCtrl, f: &mut Frame, area: Rect) {
+use crate::current_user_id;
+use crate::mouse::item_areas;
+use crate::views::input::truncate_to_width;
+use crate::views::theme::Theme;
+use crate::views::{string_min_size, table_body, titled_box};
+pub fn list_view(parent_controller: &mut ContactCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
     //@TODO: add a filter area with an input to search the list above the Table object
     let mut tablestate = parent_controller.t_state.clone();
-    let content = list_project_records(parent_controller);
+    let (content, heights) = list_project_records(parent_controller, theme);
 
     f.render_stateful_widget(content, area, &mut tablestate);
+    parent_controller.row_areas = item_areas(table_body(area, 2), &heights, tablestate.offset(), 1);
 }
 
-pub fn edit_view(parent_controller: &mut ContactCtrl, f: &mut Frame, area: Rect) {
-    let content = edit_project_form(parent_controller);
+pub fn edit_view(parent_controller: &mut ContactCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let content = edit_project_form(parent_controller, theme);
 
     f.render_widget(content, area);
 }
 
-pub fn del_view(parent_controller: &mut ContactCtrl, f: &mut Frame, area: Rect) {
-    let content = show_delete_confirm(parent_controller);
+pub fn del_view(parent_controller: &mut ContactCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let content = show_delete_confirm(parent_controller, theme);
     f.render_widget(content, area);
 }
 
-pub fn detail_view(parent_controller: &mut ContactCtrl, f: &mut Frame, area: Rect) {
-    let content = show_detail_project(parent_controller);
+pub fn detail_view(parent_controller: &mut ContactCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let content = show_detail_project(parent_controller, theme);
     f.render_widget(content, area);
 }
 
-fn list_project_records(controller: &mut ContactCtrl) -> Table<'_> {
-    let selected_style = Style::default().add_modifier(Modifier::REVERSED);
-    let normal_style = Style::default().bg(Color::LightYellow);
+/// the table and the height of each row
+fn list_project_records<'a>(
+    controller: &'a mut ContactCtrl,
+    theme: &Theme,
+) -> (Table<'a>, Vec<u16>) {
+    let selected_style = theme.row_selected_style();
+    let normal_style = theme.table_header_style();
     let header_cells = ["Id", "First name", "Family name", "Email"]
         .iter()
-        .map(|h| Cell::from(*h).style(Style::default().fg(Color::Red)));
+        .map(|h| Cell::from(*h));
     let header = Row::new(header_cells)
         .style(normal_style)
         .height(1)
         .bottom_margin(1);
 
     let mut rows: Vec<Row> = vec![];
+    let mut heights: Vec<u16> = vec![];
+    let me = current_user_id();
+    let title = match me {
+        0 => "Contacts (press 'i' on yourself)",
+        _ => "Contacts (* is you)",
+    };
 
     match controller.list() {
         Ok(list) => {
             rows = list
                 .iter()
                 .map(|item| -> Row<'_> {
+                    let mut id = format!("{}", item.id);
+                    if item.id == me {
+                        id.push_str(" *");
+                    }
                     let row_values: Vec<String> = vec![
-                        format!("{}", item.id),
+                        id,
                         item.first_name.clone(),
                         item.last_name.clone(),
                         item.email.clone(),
@@ -56,6 +75,7 @@ fn list_project_records(controller: &mut ContactCtrl) -> Table<'_> {
                         .max()
                         .unwrap_or(0)
                         + 1;
+                    heights.push(height as u16);
                     let cells = row_values.iter().map(|c| Cell::from(c.clone()));
                     Row::new(cells).height(height as u16).bottom_margin(1)
                 })
@@ -65,7 +85,7 @@ fn list_project_records(controller: &mut ContactCtrl) -> Table<'_> {
             //@TODO display error
         }
     }
-    Table::new(
+    let table = Table::new(
         rows,
         [
             Constraint::Max(6),
@@ -75,14 +95,17 @@ fn list_project_records(controller: &mut ContactCtrl) -> Table<'_> {
         ],
     )
     .header(header)
-    .block(Block::default().borders(Borders::ALL).title("Contacts"))
+    .block(Block::default().borders(Borders::ALL).title(title))
     .highlight_style(selected_style)
-    .highlight_symbol(">> ")
+    .highlight_symbol(">> ");
+    (table, heights)
 }
 
-fn get_project_form_fields(data: &ContactCtrl, show_selected: bool) -> Vec<Line<'_>> {
-    let normal_bg = Color::Black;
-    let higlight_bg = Color::Yellow;
+fn get_project_form_fields<'a>(
+    data: &'a ContactCtrl,
+    show_selected: bool,
+    theme: &Theme,
+) -> Vec<Line<'a>> {
     let str_min_size = 60;
     let lbl_min_size = 16;
     let record = &data.table;
@@ -100,28 +123,29 @@ fn get_project_form_fields(data: &ContactCtrl, show_selected: bool) -> Vec<Line<
     let mut form_parts = vec![Line::from(vec![Span::raw("")])];
 
     fields.iter().enumerate().for_each(|(i, x)| {
-        let mut field = String::from(x);
+        let focused = data.field_idx == i as u32 && show_selected;
         let mut label = String::from(&labels[i]);
-        string_min_size(&mut field, str_min_size);
         string_min_size(&mut label, lbl_min_size);
-        form_parts.push(Line::from(vec![
-            Span::raw(label),
-            Span::styled(
-                field,
-                Style::default().bg(if data.field_idx == i as u32 && show_selected {
-                    higlight_bg
-                } else {
-                    normal_bg
-                }),
-            ),
-        ]));
+        let mut line = vec![Span::raw(label)];
+        if focused {
+            line.extend(data.input.spans(
+                str_min_size,
+                theme.field_style(true),
+                theme.cursor_style(),
+            ));
+        } else {
+            let mut field = truncate_to_width(x, str_min_size);
+            string_min_size(&mut field, str_min_size);
+            line.push(Span::styled(field, theme.field_style(focused)));
+        }
+        form_parts.push(Line::from(line));
         form_parts.push(Line::from(vec![Span::raw("")]));
     });
 
     form_parts
 }
-fn edit_project_form(data: &ContactCtrl) -> Paragraph {
-    let mut form_parts = get_project_form_fields(data, true);
+fn edit_project_form<'a>(data: &'a ContactCtrl, theme: &Theme) -> Paragraph<'a> {
+    let mut form_parts = get_project_form_fields(data, true, theme);
 
     form_parts.push(Line::from(vec![Span::raw("")]));
     form_parts.push(Line::from(vec![Span::raw(
@@ -133,8 +157,8 @@ fn edit_project_form(data: &ContactCtrl) -> Paragraph {
         .block(titled_box("Edit Contact".to_string()))
 }
 
-fn show_delete_confirm(data: &ContactCtrl) -> Paragraph {
-    let mut form_parts = get_project_form_fields(data, false);
+fn show_delete_confirm<'a>(data: &'a ContactCtrl, theme: &Theme) -> Paragraph<'a> {
+    let mut form_parts = get_project_form_fields(data, false, theme);
 
     form_parts.push(Line::from(vec![Span::raw("")]));
     form_parts.push(Line::from(vec![Span::raw(
@@ -147,8 +171,8 @@ fn show_delete_confirm(data: &ContactCtrl) -> Paragraph {
         .block(titled_box("Confirm Deletion".to_string()))
 }
 
-fn show_detail_project(data: &ContactCtrl) -> Paragraph {
-    let mut form_parts = get_project_form_fields(data, false);
+fn show_detail_project<'a>(data: &'a ContactCtrl, theme: &Theme) -> Paragraph<'a> {
+    let mut form_parts = get_project_form_fields(data, false, theme);
 
     form_parts.push(Line::from(vec![Span::raw("")]));
     form_parts.push(Line::from(vec![Span::raw(
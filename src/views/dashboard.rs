@@ -5,41 +5,312 @@ use ratatui::{
     widgets::{calendar::*, *},
     Frame,
 };
+use chrono::{Local, NaiveDate};
 use std::collections::HashMap;
 
-use crate::controllers::dashboard_controller::DashboardCtrl;
-use crate::views::task::project_list_ui;
-use crate::views::{list_ui, listitems_from_id_name, string_min_size, titled_box};
-pub fn list_view(parent_controller: &mut DashboardCtrl, f: &mut Frame, area: Rect) {
+use crate::controllers::dashboard_controller::{DashboardCtrl, PopupDashboardType};
+use crate::controllers::{CtrObj, CtrlActions};
+use crate::estimate::EstimateScale;
+use crate::models::task::{DUE_DATE_FORMAT, NORMAL_PRIORITY, PRIORITIES};
+use crate::mouse::item_areas;
+use crate::views::burndown::chart_panel;
+use crate::views::input::{display_width, truncate_to_width, wrap_text};
+use crate::views::theme::Theme;
+use crate::views::{list_ui, popup_confirm, titled_box};
+pub fn list_view(parent_controller: &mut DashboardCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
     let subareas = Layout::default()
         .direction(Direction::Horizontal)
         .margin(0)
         .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
         .split(area);
 
-    project_list_col(parent_controller, f, subareas[0]);
-    project_detail_col(parent_controller, f, subareas[1]);
+    project_list_col(parent_controller, f, subareas[0], theme);
+    project_detail_col(parent_controller, f, subareas[1], theme);
+    if let (true, PopupDashboardType::WipLimit) =
+        (parent_controller.show_popup, &parent_controller.popup_type)
+    {
+        wip_limit_popup(parent_controller, f);
+    }
+    match parent_controller.action {
+        CtrlActions::Edit => quick_add_box(parent_controller, f, theme),
+        CtrlActions::Del => delete_card_popup(parent_controller, f),
+        _ => {}
+    }
 
     //f.render_stateful_widget(content, area, &mut tablestate);
 }
 
-fn scrum_col_ui(list: Vec<HashMap<String, String>>, title: String) -> List<'static> {
-    list_ui(listitems_from_id_name(list), title)
+/// name of the task being added, at the bottom of the focused column
+fn quick_add_box(ctrl: &DashboardCtrl, f: &mut Frame, theme: &Theme) {
+    let col = (ctrl.scrum_col_focus - 1).max(0) as usize;
+    let col_area = match ctrl.column_areas.iter().find(|(i, _)| *i == col) {
+        Some((_, area)) if area.height >= 3 => *area,
+        _ => return,
+    };
+    let area = Rect::new(col_area.x, col_area.bottom() - 3, col_area.width, 3);
+    let input = ctrl.input.spans(
+        area.width.saturating_sub(2) as usize,
+        theme.field_style(true),
+        theme.cursor_style(),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(Line::from(input))
+            .block(titled_box("New task".to_string())),
+        area,
+    );
+}
+
+fn delete_card_popup(ctrl: &DashboardCtrl, f: &mut Frame) {
+    if let Some(task) = ctrl
+        .l_scrum_state
+        .selected()
+        .and_then(|idx| ctrl.scrum_col_list.get(idx))
+    {
+        popup_confirm(
+            "Delete task".to_string(),
+            format!(
+                "Delete task #{} {} with its checklist and comments?",
+                task.get("id").cloned().unwrap_or_default(),
+                task.get("name").cloned().unwrap_or_default()
+            ),
+            f,
+        );
+    }
+}
+
+/// asks before moving a card into a column that reached its WIP limit
+fn wip_limit_popup(ctrl: &DashboardCtrl, f: &mut Frame) {
+    if let Some((task_id, status_id)) = ctrl.pending_move {
+        let status_name = ctrl
+            .task_status_vec
+            .iter()
+            .find(|s| s.get("id") == Some(&status_id.to_string()))
+            .and_then(|s| s.get("name").cloned())
+            .unwrap_or_default();
+        popup_confirm(
+            "WIP limit reached".to_string(),
+            format!(
+                "{} already holds {} of {} tasks, move task {} there anyway?",
+                status_name,
+                ctrl.status_count(status_id),
+                ctrl.wip_limit(status_id).unwrap_or(0),
+                task_id
+            ),
+            f,
+        );
+    }
+}
+
+/// the right side of the dashboard shows the opened card instead of the board
+pub fn card_view(ctrl: &mut DashboardCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let subareas = Layout::default()
+        .direction(Direction::Horizontal)
+        .margin(0)
+        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
+        .split(area);
+
+    project_list_col(ctrl, f, subareas[0], theme);
+    if let Some(task) = ctrl.task_detail.as_mut() {
+        if let Err(e) = task.display(f, subareas[1], theme) {
+            log::error!("failed to show task: {}", e);
+        }
+    }
 }
 
-fn project_detail_col(ctrl: &mut DashboardCtrl, f: &mut Frame, area: Rect) {
+/// lines of a board card: its name wrapped to `width`, in the color of its priority for P0 and
+/// P1, its project when the board shows several of them, then its priority unless it is the
+/// usual P2, its points, tags, checklist progress, whether it waits for unfinished tasks, its
+/// due date, red when overdue, and who works on it
+fn board_card(
+    task: &HashMap<String, String>,
+    width: usize,
+    done_id: &str,
+    show_project: bool,
+    scale: &EstimateScale,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let field = |key: &str| task.get(key).cloned().unwrap_or_default();
+    let priority = field("priority").parse::<i32>().unwrap_or(NORMAL_PRIORITY);
+    let title_style = match priority < NORMAL_PRIORITY {
+        true => theme.priority_style(priority),
+        false => theme.text_style(),
+    };
+    let mut lines: Vec<Line<'static>> =
+        wrap_text(&format!("#{} {}", field("id"), field("name")), width)
+            .into_iter()
+            .map(|row| {
+                Line::from(Span::styled(
+                    row.trim_end().to_string(),
+                    title_style.add_modifier(Modifier::BOLD),
+                ))
+            })
+            .collect();
+    if show_project {
+        let project = match field("project_name") {
+            name if name.is_empty() => "Inbox".to_string(),
+            name => name,
+        };
+        lines.push(Line::from(Span::styled(
+            truncate_to_width(&project, width),
+            theme.muted_style(),
+        )));
+    }
+
+    let mut details = vec![];
+    if priority != NORMAL_PRIORITY {
+        if let Some(label) = PRIORITIES.get(priority as usize) {
+            details.push(Span::styled(
+                label.to_string(),
+                theme.priority_style(priority),
+            ));
+        }
+    }
+    let weight = field("weight").parse::<i32>().unwrap_or(0);
+    if weight > 0 {
+        details.push(Span::styled(
+            format!("[{}]", scale.label(weight)),
+            theme.tab_style(),
+        ));
+    }
+    for tag in field("tags").split(", ").filter(|tag| !tag.is_empty()) {
+        details.push(Span::styled(format!("+{}", tag), theme.muted_style()));
+    }
+    if !field("checklist").is_empty() {
+        details.push(Span::styled(
+            format!("☑ {}", field("checklist")),
+            theme.muted_style(),
+        ));
+    }
+    if field("blocked").parse::<i32>().unwrap_or(0) > 0 && field("status") != done_id {
+        details.push(Span::styled("blocked".to_string(), theme.error_style()));
+    }
+    if let Ok(due) = NaiveDate::parse_from_str(&field("due_date"), DUE_DATE_FORMAT) {
+        let style = match due < Local::now().date_naive() && field("status") != done_id {
+            true => theme.error_style(),
+            false => theme.warning_style(),
+        };
+        details.push(Span::styled(format!("due {}", field("due_date")), style));
+    }
+    if !field("initials").is_empty() {
+        details.push(Span::styled(field("initials"), theme.tab_style()));
+    }
+
+    // as many details on a line as fit
+    let mut line: Vec<Span<'static>> = vec![];
+    let mut used = 0;
+    for span in details {
+        let span_width = display_width(&span.content);
+        if !line.is_empty() && used + 1 + span_width > width {
+            lines.push(Line::from(std::mem::take(&mut line)));
+            used = 0;
+        }
+        if !line.is_empty() {
+            line.push(Span::raw(" "));
+            used += 1;
+        }
+        used += span_width;
+        line.push(span);
+    }
+    if !line.is_empty() {
+        lines.push(Line::from(line));
+    }
+    lines
+}
+
+/// board cards of a column `width` wide with the height of each card, the title red when the
+/// column is over its WIP limit
+fn scrum_col_ui(
+    list: &[HashMap<String, String>],
+    title: String,
+    over_limit: bool,
+    width: u16,
+    done_id: &str,
+    show_project: bool,
+    theme: &Theme,
+) -> (List<'static>, Vec<u16>) {
+    let scale = EstimateScale::configured();
+    // borders and the highlight symbol
+    let card_width = (width as usize).saturating_sub(4).max(1);
+    let cards: Vec<Vec<Line<'static>>> = list
+        .iter()
+        .map(|task| board_card(task, card_width, done_id, show_project, &scale, theme))
+        .collect();
+    let heights = cards.iter().map(|card| card.len() as u16).collect();
+    let items = cards.into_iter().map(ListItem::new).collect();
+    let list = match over_limit {
+        true => list_ui(items, "".to_string(), theme).block(
+            Block::default()
+                .title(Span::styled(title, theme.error_style()))
+                .borders(Borders::ALL),
+        ),
+        false => list_ui(items, title, theme),
+    };
+    (list, heights)
+}
+
+/// sum of the weights of `tasks`
+fn total_points(tasks: &[HashMap<String, String>]) -> i32 {
+    tasks
+        .iter()
+        .filter_map(|task| task.get("weight").and_then(|w| w.parse::<i32>().ok()))
+        .sum()
+}
+
+fn project_detail_col(ctrl: &mut DashboardCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
     let mut project_name = "Project Details (Scrumboard)".to_string();
+    let scale = EstimateScale::configured();
 
-    if ctrl.projects.id > 0 {
-        project_name = ctrl.projects.name.clone();
+    if !ctrl.projects_vec.is_empty() {
+        // the last column of the board holds the completed tasks
+        let done_id = ctrl.task_status_vec.last().and_then(|s| s.get("id")).cloned();
+        let all = total_points(&ctrl.tasks_vec);
+        let done = ctrl
+            .tasks_vec
+            .iter()
+            .filter(|task| task.get("status") == done_id.as_ref())
+            .filter_map(|task| task.get("weight").and_then(|w| w.parse::<i32>().ok()))
+            .sum();
+        let sprint_name = match (&ctrl.sprint, ctrl.whole_project) {
+            (Some(sprint), false) => format!(" [{}]", sprint.name),
+            (Some(_), true) => " [all tasks]".to_string(),
+            _ => "".to_string(),
+        };
+        let board_name = match ctrl.picked_projects.len() {
+            0 => ctrl.projects.name.clone(),
+            _ => ctrl
+                .projects_vec
+                .iter()
+                .filter(|p| {
+                    p.get("id")
+                        .and_then(|id| id.parse::<i32>().ok())
+                        .is_some_and(|id| ctrl.picked_projects.contains(&id))
+                })
+                .filter_map(|p| p.get("name").cloned())
+                .collect::<Vec<String>>()
+                .join(", "),
+        };
+        project_name = format!(
+            "{}{} - {} remaining, {} completed",
+            board_name,
+            sprint_name,
+            scale.total_label(all - done),
+            scale.total_label(done)
+        );
+        if ctrl.lanes_on() {
+            project_name.push_str(&format!(
+                " - lanes by {} ('w' change, 'space' fold)",
+                ctrl.lanes_by.name()
+            ));
+        }
     }
 
     let mut scrum_cols_constraint = vec![];
     let mut scrum_cols_content_count = vec![];
     let mut scrum_cols_content = vec![];
-    let mut scrum_list_views = vec![];
+    let mut col_titles = vec![];
     let constraint_percent = 100 / ctrl.task_status_vec.len();
-    ctrl.task_status_vec.iter().for_each(|tstatus| {
+    ctrl.task_status_vec.iter().enumerate().for_each(|(i, tstatus)| {
         scrum_cols_constraint.push(Constraint::Percentage(constraint_percent as u16));
         let status_id = tstatus.get("id").unwrap().parse::<i32>().unwrap();
         let status_content: Vec<HashMap<String, String>> = ctrl
@@ -50,17 +321,33 @@ fn project_detail_col(ctrl: &mut DashboardCtrl, f: &mut Frame, area: Rect) {
             .collect();
         let col_focus = ctrl.scrum_col_focus;
         let col_id = tstatus.get("id").unwrap().parse::<i32>().unwrap();
-        let col_name: String;
-        if col_focus == col_id {
+        let mut col_name: String;
+        if ctrl.drop_col == Some(i) {
+            // column under a dragged card
+            col_name = format!(">> {} <<", tstatus.get("name").unwrap().clone());
+        } else if col_focus == col_id {
             col_name = format!("<{}>", tstatus.get("name").unwrap().clone());
         } else {
             col_name = tstatus.get("name").unwrap().clone();
         }
+        col_name.push_str(&format!(" ({})", scale.total_label(total_points(&status_content))));
+        let limit = ctrl.wip_limit(status_id);
+        if let Some(limit) = limit {
+            col_name.push_str(&format!(" WIP {}/{}", ctrl.status_count(status_id), limit));
+        }
+        let over_limit = limit.is_some_and(|limit| ctrl.status_count(status_id) > limit);
+        col_titles.push((col_name.clone(), over_limit));
         scrum_cols_content_count.push(status_content.len());
         scrum_cols_content.push(status_content.clone());
-        scrum_list_views.push(scrum_col_ui(status_content.clone(), col_name.clone()));
     });
     let project_details = titled_box(project_name);
+    ctrl.lane_areas = vec![];
+    if ctrl.lanes_on() {
+        let inner = project_details.inner(area);
+        swimlanes_ui(ctrl, f, inner, scrum_cols_constraint, col_titles, theme);
+        f.render_widget(project_details, area);
+        return;
+    }
 
     let scrumcols = Layout::default()
         .direction(Direction::Horizontal)
@@ -68,32 +355,299 @@ fn project_detail_col(ctrl: &mut DashboardCtrl, f: &mut Frame, area: Rect) {
         .constraints(scrum_cols_constraint)
         .split(project_details.inner(area));
 
-    scrum_list_views.reverse();
+    ctrl.column_areas = vec![];
+    ctrl.card_areas = vec![];
+    let done_id =
+        ctrl.task_status_vec.last().and_then(|s| s.get("id")).cloned().unwrap_or_default();
+    let show_project = ctrl.single_project().is_none();
 
     scrumcols.iter().enumerate().for_each(|(i, col_area)| {
         //f.render_widget(col_box, *col_area);
+        let (title, over_limit) = col_titles[i].clone();
+        let (list, heights) = scrum_col_ui(
+            &scrum_cols_content[i],
+            title,
+            over_limit,
+            col_area.width,
+            &done_id,
+            show_project,
+            theme,
+        );
+        let mut offset = 0;
         if i as i32 == (ctrl.scrum_col_focus - 1) {
             ctrl.scrum_col_count = scrum_cols_content_count[i];
             ctrl.scrum_col_list = scrum_cols_content[i].clone();
-            f.render_stateful_widget(
-                scrum_list_views.pop().unwrap(),
-                *col_area,
-                &mut ctrl.l_scrum_state,
-            );
+            f.render_stateful_widget(list, *col_area, &mut ctrl.l_scrum_state);
+            offset = ctrl.l_scrum_state.offset();
         } else {
-            f.render_widget(scrum_list_views.pop().unwrap(), *col_area);
+            f.render_widget(list, *col_area);
         }
+        ctrl.column_areas.push((i, *col_area));
+        ctrl.card_areas.push(item_areas(
+            Block::default().borders(Borders::ALL).inner(*col_area),
+            &heights,
+            offset,
+            0,
+        ));
     });
     f.render_widget(project_details, area);
 }
 
-fn project_list_col(ctrl: &mut DashboardCtrl, f: &mut Frame, area: Rect) {
+/// the column titles on top, then one row of cells per swimlane under a header with the number of
+/// tasks and points of the lane, folded lanes only show their header
+fn swimlanes_ui(
+    ctrl: &mut DashboardCtrl,
+    f: &mut Frame,
+    area: Rect,
+    constraints: Vec<Constraint>,
+    col_titles: Vec<(String, bool)>,
+    theme: &Theme,
+) {
+    let scale = EstimateScale::configured();
+    let lanes = ctrl.lanes();
+    ctrl.lane_focus = ctrl.lane_focus.min(lanes.len().saturating_sub(1));
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)].as_ref())
+        .split(area);
+    let header_cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(constraints.clone())
+        .split(rows[0]);
+    ctrl.column_areas = vec![];
+    ctrl.card_areas = vec![];
+    for (i, (title, over_limit)) in col_titles.iter().enumerate() {
+        let style = match over_limit {
+            true => theme.error_style(),
+            false => theme.heading_style(2),
+        };
+        f.render_widget(
+            Paragraph::new(Span::styled(format!(" {}", title), style)),
+            header_cols[i],
+        );
+        ctrl.column_areas.push((i, header_cols[i]));
+        ctrl.card_areas.push(vec![]);
+    }
+    if lanes.is_empty() {
+        f.render_widget(
+            Paragraph::new(Span::styled(" No tasks", theme.muted_style())),
+            rows[1],
+        );
+        return;
+    }
+
+    // cards of each lane by column, and the height of the lane
+    let cells: Vec<Vec<Vec<HashMap<String, String>>>> = lanes
+        .iter()
+        .map(|(key, _)| {
+            ctrl.task_status_vec
+                .iter()
+                .map(|status| {
+                    ctrl.tasks_vec
+                        .iter()
+                        .filter(|task| {
+                            task.get("status") == status.get("id") && ctrl.lane_key(task) == *key
+                        })
+                        .cloned()
+                        .collect()
+                })
+                .collect()
+        })
+        .collect();
+    let done_id = ctrl
+        .task_status_vec
+        .last()
+        .and_then(|s| s.get("id"))
+        .cloned()
+        .unwrap_or_default();
+    let show_project = ctrl.single_project().is_none();
+    // the cells of a lane are as wide as the column titles
+    let card_heights: Vec<Vec<Vec<u16>>> = cells
+        .iter()
+        .map(|lane| {
+            lane.iter()
+                .zip(header_cols.iter())
+                .map(|(cell, col)| {
+                    let width = col.width;
+                    scrum_col_ui(
+                        cell,
+                        "".to_string(),
+                        false,
+                        width,
+                        &done_id,
+                        show_project,
+                        theme,
+                    )
+                    .1
+                })
+                .collect()
+        })
+        .collect();
+    let heights: Vec<u16> = lanes
+        .iter()
+        .zip(card_heights.iter())
+        .map(|((key, _), lane)| match ctrl.is_collapsed(key) {
+            true => 1,
+            false => {
+                3 + lane
+                    .iter()
+                    .map(|cell| cell.iter().sum::<u16>())
+                    .max()
+                    .unwrap_or(0)
+                    .max(1)
+            }
+        })
+        .collect();
+    // scrolls down until the focused lane fits
+    let mut first = 0;
+    while first < ctrl.lane_focus
+        && heights[first..=ctrl.lane_focus].iter().sum::<u16>() > rows[1].height
+    {
+        first += 1;
+    }
+
+    let mut y = rows[1].y;
+    for idx in first..lanes.len() {
+        if y >= rows[1].bottom() {
+            break;
+        }
+        let (key, name) = &lanes[idx];
+        let lane_area = Rect::new(
+            rows[1].x,
+            y,
+            rows[1].width,
+            heights[idx].min(rows[1].bottom() - y),
+        );
+        y += lane_area.height;
+        ctrl.lane_areas.push((idx, lane_area));
+
+        let collapsed = ctrl.is_collapsed(key);
+        let count: usize = cells[idx].iter().map(|cell| cell.len()).sum();
+        let points: i32 = cells[idx].iter().map(|cell| total_points(cell)).sum();
+        let header = format!(
+            "{} {} ({} task(s), {})",
+            if collapsed { "▶" } else { "▼" },
+            name,
+            count,
+            scale.total_label(points)
+        );
+        let header_style = match idx == ctrl.lane_focus {
+            true => theme.selection_style(),
+            false => theme.tab_style(),
+        };
+        f.render_widget(
+            Paragraph::new(Span::styled(header, header_style)),
+            Rect::new(lane_area.x, lane_area.y, lane_area.width, 1),
+        );
+        if collapsed || lane_area.height < 2 {
+            continue;
+        }
+
+        let cell_area = Rect::new(
+            lane_area.x,
+            lane_area.y + 1,
+            lane_area.width,
+            lane_area.height - 1,
+        );
+        let lane_cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(constraints.clone())
+            .split(cell_area);
+        let focused_lane = idx == ctrl.lane_focus;
+        if focused_lane {
+            ctrl.column_areas = vec![];
+            ctrl.card_areas = vec![];
+        }
+        for (i, col_area) in lane_cols.iter().enumerate() {
+            let focused = focused_lane && i as i32 == ctrl.scrum_col_focus - 1;
+            let title = match focused {
+                true => format!(
+                    "<{}>",
+                    ctrl.task_status_vec[i]
+                        .get("name")
+                        .cloned()
+                        .unwrap_or_default()
+                ),
+                false => "".to_string(),
+            };
+            let (list, _) = scrum_col_ui(
+                &cells[idx][i],
+                title,
+                false,
+                col_area.width,
+                &done_id,
+                show_project,
+                theme,
+            );
+            let mut offset = 0;
+            if focused {
+                ctrl.scrum_col_count = cells[idx][i].len();
+                ctrl.scrum_col_list = cells[idx][i].clone();
+                f.render_stateful_widget(list, *col_area, &mut ctrl.l_scrum_state);
+                offset = ctrl.l_scrum_state.offset();
+            } else {
+                f.render_widget(list, *col_area);
+            }
+            if focused_lane {
+                ctrl.column_areas.push((i, *col_area));
+                ctrl.card_areas.push(item_areas(
+                    Block::default().borders(Borders::ALL).inner(*col_area),
+                    &card_heights[idx][i],
+                    offset,
+                    0,
+                ));
+            }
+        }
+    }
+}
+
+/// "All projects" and "Inbox" on top of the projects, the ticked ones marked
+fn project_list_col(ctrl: &mut DashboardCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
     ctrl.record_count = ctrl.projects_vec.len();
-    let project_col = project_list_ui(ctrl.projects_vec.clone());
-    f.render_stateful_widget(project_col, area, &mut ctrl.l_state);
+    let items: Vec<ListItem> = ctrl
+        .projects_vec
+        .iter()
+        .map(|p| {
+            let id = p.get("id").and_then(|id| id.parse::<i32>().ok()).unwrap_or(0);
+            let name = p.get("name").cloned().unwrap_or_default();
+            match (id > 0, ctrl.picked_projects.contains(&id)) {
+                (false, _) => ListItem::new(Line::from(Span::styled(name, theme.heading_style(2)))),
+                (true, true) => ListItem::new(Line::from(vec![
+                    Span::styled("[x] ", theme.success_style()),
+                    Span::raw(format!("{} - {}", id, name)),
+                ])),
+                (true, false) => ListItem::new(Line::from(format!("[ ] {} - {}", id, name))),
+            }
+        })
+        .collect();
+    let title = match ctrl.scrum_col_focus {
+        0 => "Projects ('space' ticks several)".to_string(),
+        _ => "Projects".to_string(),
+    };
+    f.render_stateful_widget(list_ui(items, title, theme), area, &mut ctrl.l_state);
+    ctrl.project_areas = item_areas(
+        Block::default().borders(Borders::ALL).inner(area),
+        &vec![1; ctrl.record_count],
+        ctrl.l_state.offset(),
+        0,
+    );
 }
 
-pub fn detail_view(parent_controller: &mut DashboardCtrl, f: &mut Frame, area: Rect) {
-    //let content = show_detail_project(parent_controller);
-    //f.render_widget(content, area);
+/// burndown/burnup chart of the selected project next to the project list
+pub fn detail_view(
+    parent_controller: &mut DashboardCtrl,
+    f: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+) {
+    let subareas = Layout::default()
+        .direction(Direction::Horizontal)
+        .margin(0)
+        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
+        .split(area);
+
+    project_list_col(parent_controller, f, subareas[0], theme);
+    if let Some(chart) = &parent_controller.chart {
+        chart_panel(chart, &parent_controller.chart_msg, f, subareas[1], theme);
+    }
 }
@@ -0,0 +1,30 @@
+use ratatui::{layout::*, text::*, widgets::*, Frame};
+
+use crate::logger::{line_level, log_file_path};
+use crate::views::theme::Theme;
+use crate::views::titled_box;
+use log::Level;
+
+/// hidden pane showing the tail of the log file, toggled with F12
+pub fn debug_pane_ui(lines: Vec<String>, f: &mut Frame, area: Rect, theme: &Theme) {
+    let visible = area.height.saturating_sub(2) as usize;
+    let start = lines.len().saturating_sub(visible);
+    let content: Vec<Line> = lines[start..]
+        .iter()
+        .map(|l| {
+            let style = match line_level(l) {
+                Some(Level::Error) => theme.error_style(),
+                Some(Level::Warn) => theme.warning_style(),
+                Some(Level::Debug) | Some(Level::Trace) => theme.muted_style(),
+                _ => theme.text_style(),
+            };
+            Line::from(Span::styled(l.clone(), style))
+        })
+        .collect();
+
+    let title = format!("Debug log - {} (F12 to close)", log_file_path().display());
+    let pane = Paragraph::new(content).block(titled_box(title));
+
+    f.render_widget(Clear, area);
+    f.render_widget(pane, area);
+}
@@ -1,16 +1,12 @@
-use ratatui::{layout::*, style::*, text::*, widgets::*, Frame};
+use ratatui::{layout::*, text::*, widgets::*, Frame};
 
 use crate::controllers::header_controller::HeaderCtrl;
+use crate::views::theme::Theme;
 
-pub fn ui_menu<'a>(menu: &Vec<String>, app_title: &str) -> Tabs<'a> {
+pub fn ui_menu<'a>(menu: &[String], app_title: &str, theme: &Theme) -> Tabs<'a> {
     let tab_titles = menu
         .iter()
-        .map(|t| Line::from(
-                Span::styled(t.clone(), 
-                             Style::default().fg(Color::Green)
-                             )
-                )
-            )
+        .map(|t| Line::from(Span::styled(t.clone(), theme.tab_style())))
         .collect();
     //let tabs =
     Tabs::new(tab_titles)
@@ -19,19 +15,35 @@ pub fn ui_menu<'a>(menu: &Vec<String>, app_title: &str) -> Tabs<'a> {
                 .borders(Borders::ALL)
                 .title(format!("{}", app_title)),
         )
-        .highlight_style(Style::default().fg(Color::Yellow))
+        .highlight_style(theme.tab_active_style())
 }
 
-pub fn header_ui(parent_controller: &mut HeaderCtrl, f: &mut Frame, area: Rect) {
-    let header = ui_menu(
-        &parent_controller
-            .menu
-            .iter()
-            .map(|m| -> String { m.name.clone() })
-            .collect(),
-        parent_controller.title.as_str(),
-    )
-    .select(parent_controller.active_item);
+pub fn header_ui(parent_controller: &mut HeaderCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let names: Vec<String> = parent_controller
+        .menu
+        .iter()
+        .map(|m| -> String { m.name.clone() })
+        .collect();
+    let header = ui_menu(&names, parent_controller.title.as_str(), theme)
+        .select(parent_controller.active_item);
 
+    parent_controller.tab_areas = tab_areas(&names, area);
     f.render_widget(header, area);
 }
+
+/// mirrors the Tabs layout: a space on each side of the title and a one column divider
+fn tab_areas(names: &[String], area: Rect) -> Vec<(usize, Rect)> {
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    let mut x = inner.x;
+    let mut areas = vec![];
+    for (idx, name) in names.iter().enumerate() {
+        let width =
+            (Line::from(name.as_str()).width() as u16 + 2).min(inner.right().saturating_sub(x));
+        if width == 0 {
+            break;
+        }
+        areas.push((idx, Rect::new(x, inner.y, width, 1)));
+        x += width + 1;
+    }
+    areas
+}
@@ -0,0 +1,47 @@
+use ratatui::{layout::*, style::*, widgets::*, Frame};
+
+use crate::keymap::{chord_to_string, KeyContext, Keymap};
+use crate::views::theme::Theme;
+use crate::views::{centered_rect, titled_box};
+
+/// lists every binding available in `ctx`, generated from the active keymap
+pub fn help_ui(keymap: &Keymap, ctx: KeyContext, f: &mut Frame, area: Rect, theme: &Theme) {
+    let popup_rect = centered_rect(70, 80, area);
+    let mut bindings = keymap.context_bindings(ctx);
+    bindings.sort_by_key(|(_, action)| action.name());
+
+    let mut rows: Vec<Row> = vec![];
+    let mut idx = 0;
+    while idx < bindings.len() {
+        let action = bindings[idx].1;
+        let mut keys = vec![];
+        while idx < bindings.len() && bindings[idx].1 == action {
+            keys.push(chord_to_string(&bindings[idx].0));
+            idx += 1;
+        }
+        rows.push(Row::new(vec![
+            Cell::from(keys.join(", ")).style(Style::default().add_modifier(Modifier::BOLD)),
+            Cell::from(action.description()),
+        ]));
+    }
+    if ctx.takes_text() {
+        rows.push(Row::new(vec![
+            Cell::from("other keys").style(Style::default().add_modifier(Modifier::BOLD)),
+            Cell::from("type text"),
+        ]));
+    }
+
+    let table = Table::new(rows, [Constraint::Length(24), Constraint::Min(10)])
+        .header(
+            Row::new(vec!["Key", "Action"])
+                .style(theme.table_header_style())
+                .bottom_margin(1),
+        )
+        .block(titled_box(format!(
+            "Help - {} keys (Esc to close)",
+            ctx.name()
+        )));
+
+    f.render_widget(Clear, popup_rect);
+    f.render_widget(table, popup_rect);
+}
@@ -0,0 +1,490 @@
+use crate::keymap::Action;
+use ratatui::{style::Style, text::Span};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// text field used by every form, the cursor is counted in graphemes so accented letters,
+/// emojis and wide characters move and delete as one
+#[derive(Debug, Clone, Default)]
+pub struct TextInput {
+    value: String,
+    cursor: usize,
+    /// columns of a multi-line field, 0 for a single line field
+    wrap_width: usize,
+}
+
+impl TextInput {
+    /// input holding `value` with the cursor at the end
+    pub fn new(value: &str) -> TextInput {
+        TextInput {
+            value: value.to_string(),
+            cursor: value.graphemes(true).count(),
+            wrap_width: 0,
+        }
+    }
+
+    /// multi-line input `width` columns wide, lines are word wrapped
+    pub fn multiline(value: &str, width: usize) -> TextInput {
+        TextInput {
+            wrap_width: width.max(2),
+            ..TextInput::new(value)
+        }
+    }
+
+    pub fn is_multiline(&self) -> bool {
+        self.wrap_width > 0
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn clear(&mut self) {
+        self.value.clear();
+        self.cursor = 0;
+    }
+
+    fn len(&self) -> usize {
+        self.value.graphemes(true).count()
+    }
+
+    /// byte index of the grapheme at `position`
+    fn byte_index(&self, position: usize) -> usize {
+        self.value
+            .grapheme_indices(true)
+            .nth(position)
+            .map(|(idx, _)| idx)
+            .unwrap_or(self.value.len())
+    }
+
+    /// inserts `c` at the cursor, a line break becomes a space in a single line field
+    pub fn insert(&mut self, c: char) {
+        let c = match c {
+            '\n' if !self.is_multiline() => ' ',
+            '\n' => c,
+            _ if c.is_control() => return,
+            _ => c,
+        };
+        let before = self.len();
+        self.value.insert(self.byte_index(self.cursor), c);
+        // a combining mark merges with the previous grapheme instead of adding one
+        self.cursor += self.len() - before;
+    }
+
+    pub fn delete_back(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.delete_forward();
+        }
+    }
+
+    pub fn delete_forward(&mut self) {
+        if self.cursor < self.len() {
+            let start = self.byte_index(self.cursor);
+            let end = self.byte_index(self.cursor + 1);
+            self.value.replace_range(start..end, "");
+        }
+    }
+
+    /// deletes the word before the cursor and the spaces following it, like Ctrl-W in a shell
+    pub fn delete_word(&mut self) {
+        let graphemes: Vec<&str> = self.value.graphemes(true).collect();
+        let is_space = |g: &str| g.chars().all(char::is_whitespace);
+        let mut start = self.cursor;
+        while start > 0 && is_space(graphemes[start - 1]) {
+            start -= 1;
+        }
+        while start > 0 && !is_space(graphemes[start - 1]) {
+            start -= 1;
+        }
+        let (from, to) = (self.byte_index(start), self.byte_index(self.cursor));
+        self.value.replace_range(from..to, "");
+        self.cursor = start;
+    }
+
+    pub fn delete_to_start(&mut self) {
+        let end = self.byte_index(self.cursor);
+        self.value.replace_range(..end, "");
+        self.cursor = 0;
+    }
+
+    pub fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn move_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.len());
+    }
+
+    /// start of the field, or of the current row in a multi-line field
+    pub fn move_home(&mut self) {
+        self.cursor = match self.is_multiline() {
+            true => {
+                let rows = self.rows();
+                rows[cursor_row(&rows, self.cursor)].0
+            }
+            false => 0,
+        };
+    }
+
+    /// end of the field, or of the current row in a multi-line field
+    pub fn move_end(&mut self) {
+        self.cursor = match self.is_multiline() {
+            true => {
+                let rows = self.rows();
+                rows[cursor_row(&rows, self.cursor)].1
+            }
+            false => self.len(),
+        };
+    }
+
+    /// moves the cursor `step` rows up or down keeping its column
+    fn move_rows(&mut self, step: isize) {
+        let graphemes: Vec<&str> = self.value.graphemes(true).collect();
+        let rows = wrap_rows(&graphemes, self.wrap_width - 1);
+        let current = cursor_row(&rows, self.cursor);
+        let target = current as isize + step;
+        if target < 0 || target >= rows.len() as isize {
+            return;
+        }
+        let column: usize = graphemes[rows[current].0..self.cursor]
+            .iter()
+            .map(|g| g.width())
+            .sum();
+        let (start, end) = rows[target as usize];
+        let mut used = 0;
+        self.cursor = start;
+        while self.cursor < end && used + graphemes[self.cursor].width() <= column {
+            used += graphemes[self.cursor].width();
+            self.cursor += 1;
+        }
+        // the end of a wrapped row is the start of the next one
+        if self.cursor == end && end < graphemes.len() && graphemes[end] != "\n" && end > start {
+            self.cursor -= 1;
+        }
+    }
+
+    fn rows(&self) -> Vec<(usize, usize)> {
+        let graphemes: Vec<&str> = self.value.graphemes(true).collect();
+        wrap_rows(&graphemes, self.wrap_width - 1)
+    }
+
+    /// applies an editing action, false when `action` doesn't edit text
+    pub fn handle_action(&mut self, action: &Action) -> bool {
+        match *action {
+            Action::Insert(c) => self.insert(c),
+            Action::DeleteBack => self.delete_back(),
+            Action::DeleteForward => self.delete_forward(),
+            Action::DeleteWord => self.delete_word(),
+            Action::DeleteToStart => self.delete_to_start(),
+            Action::Left => self.move_left(),
+            Action::Right => self.move_right(),
+            Action::First => self.move_home(),
+            Action::Last => self.move_end(),
+            Action::Up if self.is_multiline() => self.move_rows(-1),
+            Action::Down if self.is_multiline() => self.move_rows(1),
+            Action::NewLine if self.is_multiline() => self.insert('\n'),
+            _ => return false,
+        }
+        true
+    }
+
+    /// the part of the value visible in a field `width` columns wide, scrolled horizontally so
+    /// the cursor stays in view, padded to `width` and with the cursor drawn in `cursor_style`
+    pub fn spans(&self, width: usize, style: Style, cursor_style: Style) -> Vec<Span<'static>> {
+        let graphemes: Vec<&str> = self.value.graphemes(true).collect();
+        let cursor_width = graphemes.get(self.cursor).map(|g| g.width()).unwrap_or(1);
+
+        let mut start = self.cursor;
+        let mut used = cursor_width;
+        while start > 0 && used + graphemes[start - 1].width() <= width {
+            start -= 1;
+            used += graphemes[start].width();
+        }
+        let mut end = self.cursor.min(graphemes.len());
+        if end < graphemes.len() {
+            end += 1;
+        }
+        while end < graphemes.len() && used + graphemes[end].width() <= width {
+            used += graphemes[end].width();
+            end += 1;
+        }
+
+        let before: String = graphemes[start..self.cursor.min(graphemes.len())].concat();
+        let under = graphemes
+            .get(self.cursor)
+            .copied()
+            .unwrap_or(" ")
+            .to_string();
+        let after: String = if self.cursor < graphemes.len() {
+            graphemes[self.cursor + 1..end].concat()
+        } else {
+            String::new()
+        };
+        let padding = " ".repeat(width.saturating_sub(used));
+
+        vec![
+            Span::styled(before, style),
+            Span::styled(under, cursor_style),
+            Span::styled(format!("{}{}", after, padding), style),
+        ]
+    }
+
+    /// `height` rows of a multi-line field, scrolled so the cursor row stays in view, each row
+    /// padded to the field width
+    pub fn area_spans(
+        &self,
+        height: usize,
+        style: Style,
+        cursor_style: Style,
+    ) -> Vec<Vec<Span<'static>>> {
+        let graphemes: Vec<&str> = self.value.graphemes(true).collect();
+        let rows = wrap_rows(&graphemes, self.wrap_width - 1);
+        let current = cursor_row(&rows, self.cursor);
+        let scroll = current.saturating_sub(height.saturating_sub(1));
+
+        (scroll..scroll + height)
+            .map(|row| match rows.get(row) {
+                Some((start, end)) if row == current => {
+                    let before = graphemes[*start..self.cursor].concat();
+                    let (under, after) = if self.cursor < *end {
+                        (
+                            graphemes[self.cursor],
+                            graphemes[self.cursor + 1..*end].concat(),
+                        )
+                    } else {
+                        (" ", String::new())
+                    };
+                    let used = display_width(&before) + under.width() + display_width(&after);
+                    vec![
+                        Span::styled(before, style),
+                        Span::styled(under.to_string(), cursor_style),
+                        Span::styled(
+                            format!(
+                                "{}{}",
+                                after,
+                                " ".repeat(self.wrap_width.saturating_sub(used))
+                            ),
+                            style,
+                        ),
+                    ]
+                }
+                Some((start, end)) => {
+                    let text = graphemes[*start..*end].concat();
+                    let padding = " ".repeat(self.wrap_width.saturating_sub(display_width(&text)));
+                    vec![Span::styled(format!("{}{}", text, padding), style)]
+                }
+                None => vec![Span::styled(" ".repeat(self.wrap_width), style)],
+            })
+            .collect()
+    }
+}
+
+/// splits `graphemes` in rows of at most `width` columns, breaking lines after spaces when
+/// possible. rows are (start, end) grapheme indexes, line breaks are not part of any row
+pub(crate) fn wrap_rows(graphemes: &[&str], width: usize) -> Vec<(usize, usize)> {
+    let is_space = |g: &str| g == " " || g == "\t";
+    let mut rows = vec![];
+    let mut line_start = 0;
+    loop {
+        let line_end = graphemes[line_start..]
+            .iter()
+            .position(|g| *g == "\n")
+            .map(|idx| line_start + idx)
+            .unwrap_or(graphemes.len());
+        let mut start = line_start;
+        loop {
+            let mut used = 0;
+            let mut idx = start;
+            let mut last_space = None;
+            while idx < line_end && used + graphemes[idx].width() <= width {
+                if is_space(graphemes[idx]) {
+                    last_space = Some(idx);
+                }
+                used += graphemes[idx].width();
+                idx += 1;
+            }
+            if idx >= line_end {
+                rows.push((start, line_end));
+                break;
+            }
+            let brk = match last_space {
+                Some(space) => space + 1,
+                None => idx.max(start + 1),
+            };
+            rows.push((start, brk));
+            start = brk;
+        }
+        if line_end >= graphemes.len() {
+            return rows;
+        }
+        line_start = line_end + 1;
+    }
+}
+
+/// row holding the cursor, the end of a wrapped row belongs to the next one
+fn cursor_row(rows: &[(usize, usize)], cursor: usize) -> usize {
+    rows.iter()
+        .rposition(|(start, _)| *start <= cursor)
+        .unwrap_or(0)
+}
+
+/// `text` word wrapped to `width` columns
+pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let graphemes: Vec<&str> = text.graphemes(true).collect();
+    wrap_rows(&graphemes, width)
+        .iter()
+        .map(|(start, end)| graphemes[*start..*end].concat())
+        .collect()
+}
+
+/// columns taken by `text` in the terminal
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// cuts `text` so it fits in `width` columns
+pub fn truncate_to_width(text: &str, width: usize) -> String {
+    let mut used = 0;
+    text.graphemes(true)
+        .take_while(|g| {
+            used += g.width();
+            used <= width
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed(text: &str) -> TextInput {
+        let mut input = TextInput::default();
+        text.chars().for_each(|c| input.insert(c));
+        input
+    }
+
+    fn visible(spans: &[Span]) -> String {
+        spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn cursor_moves_and_deletes_whole_graphemes() {
+        // an e with a combining accent, a thumb with a skin tone and a wide character
+        let mut input = typed("e\u{301}👍🏽日");
+        assert_eq!(input.cursor, 3);
+        input.move_left();
+        input.delete_back();
+        assert_eq!(input.value(), "e\u{301}日");
+        input.move_home();
+        input.move_right();
+        assert_eq!(input.cursor, 1);
+        input.delete_back();
+        assert_eq!(input.value(), "日");
+
+        // a combining mark typed after a letter joins it
+        let mut input = typed("cafe");
+        input.insert('\u{301}');
+        assert_eq!((input.value(), input.cursor), ("cafe\u{301}", 4));
+        input.delete_back();
+        assert_eq!(input.value(), "caf");
+    }
+
+    #[test]
+    fn line_breaks_and_control_characters() {
+        let mut input = typed("a\nb\tc");
+        assert_eq!(input.value(), "a bc");
+        input = TextInput::multiline("", 10);
+        "a\nb".chars().for_each(|c| input.insert(c));
+        assert_eq!(input.value(), "a\nb");
+    }
+
+    #[test]
+    fn ctrl_w_deletes_the_word_before_the_cursor() {
+        let mut input = typed("fix the  bug  ");
+        assert!(input.handle_action(&Action::DeleteWord));
+        assert_eq!(input.value(), "fix the  ");
+        input.handle_action(&Action::DeleteWord);
+        assert_eq!(input.value(), "fix ");
+        input.handle_action(&Action::DeleteWord);
+        input.handle_action(&Action::DeleteWord);
+        assert_eq!((input.value(), input.cursor), ("", 0));
+
+        // at the start of the line there is nothing to delete
+        let mut input = typed("été là");
+        input.move_home();
+        input.delete_word();
+        assert_eq!(input.value(), "été là");
+        // from the middle of a word, only its start goes
+        input.cursor = 2;
+        input.delete_word();
+        assert_eq!((input.value(), input.cursor), ("é là", 0));
+    }
+
+    #[test]
+    fn ctrl_u_deletes_up_to_the_cursor() {
+        let mut input = typed("naïve 日本");
+        input.move_left();
+        assert!(input.handle_action(&Action::DeleteToStart));
+        assert_eq!((input.value(), input.cursor), ("本", 0));
+        assert!(!input.handle_action(&Action::Up));
+    }
+
+    #[test]
+    fn long_values_scroll_to_the_cursor() {
+        let mut input = typed("abcdefghij");
+        let spans = input.spans(5, Style::default(), Style::default());
+        assert_eq!(spans[0].content, "ghij");
+        assert_eq!(spans[1].content, " ");
+        input.move_home();
+        let spans = input.spans(5, Style::default(), Style::default());
+        assert_eq!(visible(&spans), "abcde");
+        assert_eq!(spans[1].content, "a");
+
+        // wide characters are never cut in half and short values are padded
+        let input = typed("日本語");
+        assert_eq!(
+            visible(&input.spans(4, Style::default(), Style::default())),
+            "語  "
+        );
+        let input = typed("ok");
+        assert_eq!(
+            visible(&input.spans(5, Style::default(), Style::default())),
+            "ok   "
+        );
+    }
+
+    #[test]
+    fn text_wraps_at_the_field_width() {
+        assert_eq!(
+            wrap_text("hello world foo", 11),
+            vec!["hello ", "world foo"]
+        );
+        assert_eq!(wrap_text("abcde", 5), vec!["abcde"]);
+        assert_eq!(wrap_text("abcdefgh", 3), vec!["abc", "def", "gh"]);
+        assert_eq!(wrap_text("日本語", 5), vec!["日本", "語"]);
+        assert_eq!(wrap_text("one\n\ntwo", 10), vec!["one", "", "two"]);
+        assert_eq!(display_width("日本語"), 6);
+        assert_eq!(truncate_to_width("日本語", 5), "日本");
+    }
+
+    #[test]
+    fn multiline_cursor_moves_between_rows() {
+        let mut input = TextInput::multiline("hello world", 7);
+        assert_eq!(input.rows(), vec![(0, 6), (6, 11)]);
+        input.handle_action(&Action::Up);
+        assert_eq!(input.cursor, 5);
+        input.handle_action(&Action::Down);
+        assert_eq!(input.cursor, 11);
+        input.handle_action(&Action::First);
+        assert_eq!(input.cursor, 6);
+        input.handle_action(&Action::Up);
+        input.handle_action(&Action::Last);
+        assert_eq!(input.cursor, 6);
+
+        let rows = input.area_spans(3, Style::default(), Style::default());
+        assert_eq!(rows.len(), 3);
+        assert_eq!(visible(&rows[1]), "world  ");
+        assert_eq!(visible(&rows[2]), "       ");
+    }
+}
@@ -0,0 +1,332 @@
+use crate::views::input::{display_width, wrap_rows};
+use crate::views::theme::Theme;
+use ratatui::{
+    style::{Modifier, Style},
+    text::Span,
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// a piece of rendered text and its style
+type Segment = (String, Style);
+
+/// renders the markdown subset used in descriptions as rows `width` columns wide: headings,
+/// **bold**, *italic*, `code`, [links](url), lists, `- [ ]` checkboxes and fenced code blocks.
+/// `selected` highlights that checkbox, counted from 0
+pub fn render(
+    text: &str,
+    width: usize,
+    theme: &Theme,
+    selected: Option<usize>,
+) -> Vec<Vec<Span<'static>>> {
+    let base = theme.field_style(false);
+    let mut rows = vec![];
+    let mut in_code = false;
+    let mut checkbox = 0;
+
+    for line in text.split('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            let code = vec![(line.to_string(), base.patch(theme.code_style()))];
+            rows.extend(wrap_segments(("".to_string(), base), code, width, base));
+            continue;
+        }
+
+        let indent = &line[..line.len() - trimmed.len()];
+        if let Some((level, title)) = heading(trimmed) {
+            let title = inline(title, base.patch(theme.heading_style(level)), theme);
+            rows.extend(wrap_segments(("".to_string(), base), title, width, base));
+        } else if let Some((checked, item)) = checkbox_item(trimmed) {
+            let mark_style = match (selected == Some(checkbox), checked) {
+                (true, _) => theme.selection_style(),
+                (false, true) => base.patch(theme.success_style()),
+                (false, false) => base,
+            };
+            let item_style = match checked {
+                true => base.patch(theme.muted_style()),
+                false => base,
+            };
+            let mark = format!("{}[{}] ", indent, if checked { 'x' } else { ' ' });
+            rows.extend(wrap_segments(
+                (mark, mark_style),
+                inline(item, item_style, theme),
+                width,
+                base,
+            ));
+            checkbox += 1;
+        } else if let Some(item) = bullet(trimmed) {
+            rows.extend(wrap_segments(
+                (format!("{}• ", indent), base),
+                inline(item, base, theme),
+                width,
+                base,
+            ));
+        } else {
+            rows.extend(wrap_segments(
+                ("".to_string(), base),
+                inline(line, base, theme),
+                width,
+                base,
+            ));
+        }
+    }
+
+    rows
+}
+
+/// checkbox items of `text` outside code blocks, with their line number and state
+pub fn checkboxes(text: &str) -> Vec<(usize, bool)> {
+    let mut in_code = false;
+    text.split('\n')
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") {
+                in_code = !in_code;
+                return None;
+            }
+            match in_code {
+                true => None,
+                false => checkbox_item(trimmed).map(|(checked, _)| (idx, checked)),
+            }
+        })
+        .collect()
+}
+
+/// `text` with its checkbox number `idx` checked or unchecked
+pub fn toggle_checkbox(text: &str, idx: usize) -> String {
+    let line_idx = match checkboxes(text).get(idx) {
+        Some((line_idx, _)) => *line_idx,
+        None => return text.to_string(),
+    };
+    text.split('\n')
+        .enumerate()
+        .map(|(idx, line)| {
+            if idx != line_idx {
+                return line.to_string();
+            }
+            let trimmed = line.trim_start();
+            let indent = &line[..line.len() - trimmed.len()];
+            let mark = match checkbox_item(trimmed) {
+                Some((true, _)) => ' ',
+                _ => 'x',
+            };
+            format!("{}{}[{}]{}", indent, &trimmed[..2], mark, &trimmed[5..])
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    match (1..=6).contains(&level) && line[level..].starts_with(' ') {
+        true => Some((level, line[level..].trim())),
+        false => None,
+    }
+}
+
+fn bullet(line: &str) -> Option<&str> {
+    ["- ", "* ", "+ "]
+        .iter()
+        .find(|marker| line.starts_with(**marker))
+        .map(|_| &line[2..])
+}
+
+/// `- [ ] item` or `- [x] item`, with the checked state and the item text
+fn checkbox_item(line: &str) -> Option<(bool, &str)> {
+    let rest = bullet(line)?;
+    let checked = match rest.get(..3) {
+        Some("[ ]") => false,
+        Some("[x]") | Some("[X]") => true,
+        _ => return None,
+    };
+    let item = &rest[3..];
+    match item.is_empty() || item.starts_with(' ') {
+        true => Some((checked, item.trim_start())),
+        false => None,
+    }
+}
+
+/// splits a line in segments for **bold**, *italic*, `code` and [links](url)
+fn inline(text: &str, style: Style, theme: &Theme) -> Vec<Segment> {
+    let mut segments: Vec<Segment> = vec![];
+    let mut current = String::new();
+    let (mut bold, mut italic) = (false, false);
+    let mut prev: Option<char> = None;
+    let mut rest = text;
+
+    let current_style = |bold: bool, italic: bool| {
+        let mut s = style;
+        if bold {
+            s = s.add_modifier(Modifier::BOLD);
+        }
+        if italic {
+            s = s.add_modifier(Modifier::ITALIC);
+        }
+        s
+    };
+
+    while let Some(c) = rest.chars().next() {
+        let next = rest[c.len_utf8()..].chars().next();
+        let mut marker: Option<(usize, Segment)> = None;
+
+        if c == '`' {
+            if let Some(end) = rest[1..].find('`') {
+                let code = (
+                    rest[1..1 + end].to_string(),
+                    style.patch(theme.code_style()),
+                );
+                marker = Some((end + 2, code));
+            }
+        } else if c == '[' {
+            if let Some(close) = rest.find("](") {
+                let label = &rest[1..close];
+                if let (false, Some(end)) = (label.contains(']'), rest[close..].find(')')) {
+                    let url = &rest[close + 2..close + end];
+                    segments.push((std::mem::take(&mut current), current_style(bold, italic)));
+                    segments.push((label.to_string(), style.patch(theme.link_style())));
+                    marker = Some((
+                        close + end + 1,
+                        (format!(" <{}>", url), style.patch(theme.muted_style())),
+                    ));
+                }
+            }
+        } else if rest.starts_with("**") || rest.starts_with("__") {
+            if bold || rest[2..].contains(&rest[..2]) {
+                segments.push((std::mem::take(&mut current), current_style(bold, italic)));
+                bold = !bold;
+                marker = Some((2, ("".to_string(), style)));
+            }
+        } else if c == '*' || c == '_' {
+            // '_' inside words like snake_case is not a marker
+            let in_word = match italic {
+                true => next.map(char::is_alphanumeric).unwrap_or(false),
+                false => prev.map(char::is_alphanumeric).unwrap_or(false),
+            };
+            if (c == '*' || !in_word) && (italic || rest[1..].contains(c)) {
+                segments.push((std::mem::take(&mut current), current_style(bold, italic)));
+                italic = !italic;
+                marker = Some((1, ("".to_string(), style)));
+            }
+        }
+
+        match marker {
+            Some((len, segment)) => {
+                if !current.is_empty() {
+                    segments.push((std::mem::take(&mut current), current_style(bold, italic)));
+                }
+                segments.push(segment);
+                rest = &rest[len..];
+            }
+            None => {
+                current.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        prev = Some(c);
+    }
+    segments.push((current, current_style(bold, italic)));
+    segments.retain(|(text, _)| !text.is_empty());
+    segments
+}
+
+/// word wraps `segments` to `width` columns, the first row starts with `prefix` and the next ones
+/// are indented to line up with it. rows are padded with `pad_style`
+fn wrap_segments(
+    prefix: Segment,
+    segments: Vec<Segment>,
+    width: usize,
+    pad_style: Style,
+) -> Vec<Vec<Span<'static>>> {
+    let indent = display_width(&prefix.0);
+    let cells: Vec<(&str, Style)> = segments
+        .iter()
+        .flat_map(|(text, style)| text.graphemes(true).map(move |g| (g, *style)))
+        .collect();
+    let graphemes: Vec<&str> = cells.iter().map(|(g, _)| *g).collect();
+
+    wrap_rows(&graphemes, width.saturating_sub(indent).max(1))
+        .iter()
+        .enumerate()
+        .map(|(idx, (start, end))| {
+            let mut row = match idx {
+                0 => vec![Span::styled(prefix.0.clone(), prefix.1)],
+                _ => vec![Span::styled(" ".repeat(indent), pad_style)],
+            };
+            let mut used = indent;
+            let mut chunk: Option<Segment> = None;
+            for (g, style) in &cells[*start..*end] {
+                used += display_width(g);
+                match chunk.as_mut() {
+                    Some((text, chunk_style)) if chunk_style == style => text.push_str(g),
+                    _ => {
+                        if let Some((text, style)) = chunk.take() {
+                            row.push(Span::styled(text, style));
+                        }
+                        chunk = Some((g.to_string(), *style));
+                    }
+                }
+            }
+            if let Some((text, style)) = chunk {
+                row.push(Span::styled(text, style));
+            }
+            row.push(Span::styled(
+                " ".repeat(width.saturating_sub(used)),
+                pad_style,
+            ));
+            row
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggling_checks_and_unchecks() {
+        let text = "# todo\n- [ ] write\n* [x] test\n  + [X] ship it";
+        assert_eq!(checkboxes(text), vec![(1, false), (2, true), (3, true)]);
+        assert_eq!(
+            toggle_checkbox(text, 0),
+            "# todo\n- [x] write\n* [x] test\n  + [X] ship it"
+        );
+        assert_eq!(
+            toggle_checkbox(text, 1),
+            "# todo\n- [ ] write\n* [ ] test\n  + [X] ship it"
+        );
+        assert_eq!(
+            toggle_checkbox(text, 2),
+            "# todo\n- [ ] write\n* [x] test\n  + [ ] ship it"
+        );
+        assert_eq!(toggle_checkbox(text, 3), text);
+    }
+
+    #[test]
+    fn checkboxes_in_code_blocks_are_ignored() {
+        let text = "```\n- [ ] sample\n```\n- [ ] real\n- [x]missing space";
+        assert_eq!(checkboxes(text), vec![(3, false)]);
+        assert_eq!(
+            toggle_checkbox(text, 0),
+            "```\n- [ ] sample\n```\n- [x] real\n- [x]missing space"
+        );
+    }
+
+    #[test]
+    fn non_ascii_text_before_the_marker() {
+        // unicode spaces indent the item, other text makes it a plain line
+        let text = "\u{3000}\u{a0}- [ ] café\nété - [ ] no\n- [ ] 日本";
+        assert_eq!(checkboxes(text), vec![(0, false), (2, false)]);
+        assert_eq!(
+            toggle_checkbox(text, 0),
+            "\u{3000}\u{a0}- [x] café\nété - [ ] no\n- [ ] 日本"
+        );
+        assert_eq!(
+            toggle_checkbox(text, 1),
+            "\u{3000}\u{a0}- [ ] café\nété - [ ] no\n- [x] 日本"
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use ratatui::{layout::*, style::Style, symbols, text::*, widgets::*, Frame};
+
+use crate::controllers::metrics_controller::MetricsCtrl;
+use crate::stats::{Durations, FlowStats, TIME_BUCKETS};
+use crate::views::theme::Theme;
+use crate::views::titled_box;
+
+/// cumulative flow and throughput on top, lead/cycle times and work in progress below
+pub fn metrics_view(ctrl: &mut MetricsCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let stats = match &ctrl.stats {
+        Some(stats) => stats,
+        None => {
+            f.render_widget(titled_box("Metrics".to_string()), area);
+            return;
+        }
+    };
+    let panel = titled_box(format!(
+        "Metrics - {} - last {} days ('Up'/'Down' project, 'Left'/'Right' period)",
+        stats.project,
+        ctrl.period()
+    ));
+    let inner = panel.inner(area);
+    f.render_widget(panel, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)].as_ref())
+        .split(inner);
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(65), Constraint::Percentage(35)].as_ref())
+        .split(rows[0]);
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)].as_ref())
+        .split(rows[1]);
+
+    cfd_chart(stats, f, top[0], theme);
+    throughput_chart(stats, f, top[1], theme);
+    times_chart(stats, f, bottom[0], theme);
+    wip_table(stats, f, bottom[1], theme);
+}
+
+fn status_style(idx: usize, count: usize, theme: &Theme) -> Style {
+    match idx {
+        0 => theme.muted_style(),
+        i if i + 1 == count => theme.success_style(),
+        _ => theme.warning_style(),
+    }
+}
+
+/// one line per status, each stacked on the later statuses so the bands read as areas
+fn cfd_chart(stats: &FlowStats, f: &mut Frame, area: Rect, theme: &Theme) {
+    let bands: Vec<Vec<(f64, f64)>> = (0..stats.statuses.len())
+        .map(|idx| {
+            stats
+                .cfd
+                .iter()
+                .enumerate()
+                .map(|(day, counts)| (day as f64, counts.counts[idx..].iter().sum::<i32>() as f64))
+                .collect()
+        })
+        .collect();
+    let datasets = stats
+        .statuses
+        .iter()
+        .zip(bands.iter())
+        .enumerate()
+        .map(|(idx, (name, band))| {
+            Dataset::default()
+                .name(name.clone())
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(status_style(idx, stats.statuses.len(), theme))
+                .data(band)
+        })
+        .collect();
+    let max = bands
+        .first()
+        .and_then(|band| band.iter().map(|p| p.1).reduce(f64::max))
+        .unwrap_or(0.0)
+        .max(1.0);
+    let content = Chart::new(datasets)
+        .block(titled_box("Cumulative flow".to_string()))
+        .x_axis(
+            Axis::default()
+                .style(theme.muted_style())
+                .bounds([0.0, (stats.cfd.len().max(2) - 1) as f64])
+                .labels(vec![
+                    Span::raw(stats.from.clone()),
+                    Span::raw(stats.to.clone()),
+                ]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(theme.muted_style())
+                .bounds([0.0, max])
+                .labels(vec![Span::raw("0"), Span::raw(format!("{}", max))]),
+        );
+    f.render_widget(content, area);
+}
+
+fn throughput_chart(stats: &FlowStats, f: &mut Frame, area: Rect, theme: &Theme) {
+    let labels: Vec<String> = stats
+        .throughput
+        .iter()
+        .map(|week| week.week.get(5..).unwrap_or("").to_string())
+        .collect();
+    let data: Vec<(&str, u64)> = labels
+        .iter()
+        .zip(stats.throughput.iter())
+        .map(|(label, week)| (label.as_str(), week.done as u64))
+        .collect();
+    let content = BarChart::default()
+        .block(titled_box("Throughput per week".to_string()))
+        .bar_width(5)
+        .bar_gap(1)
+        .bar_style(theme.success_style())
+        .value_style(theme.text_style())
+        .label_style(theme.muted_style())
+        .data(&data);
+    f.render_widget(content, area);
+}
+
+fn times_label(name: &str, times: &Durations) -> String {
+    format!(
+        "{}: {} tasks, avg {}d, p50 {}d, p85 {}d",
+        name,
+        times.days.len(),
+        times.average,
+        times.p50,
+        times.p85
+    )
+}
+
+/// lead and cycle time histograms side by side for each bucket
+fn times_chart(stats: &FlowStats, f: &mut Frame, area: Rect, theme: &Theme) {
+    let panel = titled_box("Lead / cycle time".to_string());
+    let inner = panel.inner(area);
+    f.render_widget(panel, area);
+    let zones = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(3)].as_ref())
+        .split(inner);
+    let summary = Paragraph::new(vec![
+        Line::from(vec![Span::styled(
+            times_label("lead", &stats.lead_time),
+            theme.warning_style(),
+        )]),
+        Line::from(vec![Span::styled(
+            times_label("cycle", &stats.cycle_time),
+            theme.success_style(),
+        )]),
+    ]);
+    f.render_widget(summary, zones[0]);
+
+    let (lead, cycle) = (stats.lead_time.histogram(), stats.cycle_time.histogram());
+    let mut content = BarChart::default()
+        .bar_width(3)
+        .bar_gap(0)
+        .group_gap(2)
+        .value_style(theme.text_style())
+        .label_style(theme.muted_style());
+    for (idx, (_, label)) in TIME_BUCKETS.iter().enumerate() {
+        content = content.data(
+            BarGroup::default().label(Line::from(*label)).bars(&[
+                Bar::default().value(lead[idx]).style(theme.warning_style()),
+                Bar::default()
+                    .value(cycle[idx])
+                    .style(theme.success_style()),
+            ]),
+        );
+    }
+    f.render_widget(content, zones[1]);
+}
+
+fn wip_table(stats: &FlowStats, f: &mut Frame, area: Rect, theme: &Theme) {
+    let header = Row::new(
+        ["Id", "Task", "Status", "Age"]
+            .iter()
+            .map(|h| Cell::from(*h)),
+    )
+    .style(theme.table_header_style());
+    let rows = stats.wip_age.iter().map(|item| {
+        Row::new(vec![
+            Cell::from(item.task_id.to_string()),
+            Cell::from(item.name.clone()),
+            Cell::from(item.status.clone()),
+            Cell::from(format!("{}d", item.age_days)),
+        ])
+    });
+    let content = Table::new(
+        rows,
+        [
+            Constraint::Max(6),
+            Constraint::Min(10),
+            Constraint::Max(12),
+            Constraint::Max(8),
+        ],
+    )
+    .header(header)
+    .block(titled_box(format!(
+        "Work in progress ({})",
+        stats.wip_age.len()
+    )));
+    f.render_widget(content, area);
+}
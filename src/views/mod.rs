@@ -1,5 +1,9 @@
+use crate::config;
+use crate::views::input::{display_width, wrap_text};
+use crate::views::theme::Theme;
+use chrono::{Local, TimeZone};
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -12,28 +16,58 @@ use std::{
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::*,
-    text::Line,
+    style::Style,
+    text::{Line, Span},
     widgets::{Block, Borders, Clear, List, ListItem, Paragraph, StatefulWidget, Widget},
     Frame, Terminal,
 };
 
+pub mod burndown;
 pub mod contact;
 pub mod dashboard;
+pub mod debug;
 pub mod footer;
 pub mod header;
+pub mod help;
+pub mod input;
+pub mod markdown;
+pub mod metrics;
+pub mod next;
+pub mod calendar;
+pub mod timeline;
+pub mod palette;
 pub mod project;
+pub mod sprint;
 pub mod task;
+pub mod theme;
 
-const DEFAULT_BG: Color = Color::Black;
-const HIGHLIGHT_BG: Color = Color::Yellow;
+/// columns of a form field
+pub const FORM_FIELD_WIDTH: usize = 60;
+/// rows of a description while it is edited
+pub const FORM_AREA_ROWS: usize = 5;
+
+/// formats a unix timestamp with the configured `date_format`
+pub fn format_timestamp(timestamp: i64) -> String {
+    if timestamp <= 0 {
+        return "--".to_string();
+    }
+    match Local.timestamp_opt(timestamp, 0).single() {
+        Some(date) => date.format(&config::get().date_format).to_string(),
+        None => "--".to_string(),
+    }
+}
 
 pub fn init_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>, Error> {
     match enable_raw_mode() {
         Ok(_) => {
             let mut stdout = stdout();
 
-            match execute!(stdout, EnterAlternateScreen, EnableMouseCapture) {
+            match execute!(
+                stdout,
+                EnterAlternateScreen,
+                EnableMouseCapture,
+                EnableBracketedPaste
+            ) {
                 Ok(_) => {
                     let backend = CrosstermBackend::new(stdout);
 
@@ -48,7 +82,13 @@ pub fn init_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>, Error> {
 
 pub fn destruct_terminal(mut terminal: Terminal<CrosstermBackend<Stdout>>) {
     disable_raw_mode().unwrap();
-    execute!(stdout(), LeaveAlternateScreen, DisableMouseCapture).unwrap();
+    execute!(
+        stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableBracketedPaste
+    )
+    .unwrap();
     terminal.show_cursor().unwrap();
 }
 
@@ -60,8 +100,8 @@ pub fn masterview(f: &mut Frame) -> Rc<[Rect]> {
         .constraints(
             [
                 Constraint::Length(3),
-                Constraint::Percentage(90),
                 Constraint::Min(1),
+                Constraint::Length(2),
             ]
             .as_ref(),
         )
@@ -100,13 +140,56 @@ pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
+/// area holding the rows of a bordered table with a header of `header_rows` lines
+pub fn table_body(area: Rect, header_rows: u16) -> Rect {
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    Rect {
+        y: inner.y + header_rows.min(inner.height),
+        height: inner.height.saturating_sub(header_rows),
+        ..inner
+    }
+}
+
 /// helper function to make displayed string of size min if length is smaller
 pub fn string_min_size(the_string: &mut String, min_size: usize) {
-    while the_string.len() < min_size {
+    while display_width(the_string) < min_size {
         the_string.push(' ');
     }
 }
 
+/// rows of a multi-line field that isn't being edited, padded or cut to `height` rows when given
+pub fn text_area_rows(text: &str, height: Option<usize>, style: Style) -> Vec<Vec<Span<'static>>> {
+    let mut rows = wrap_text(text, FORM_FIELD_WIDTH);
+    if let Some(height) = height {
+        rows.resize(height, String::new());
+    }
+    rows.into_iter()
+        .map(|mut row| {
+            string_min_size(&mut row, FORM_FIELD_WIDTH);
+            vec![Span::styled(row, style)]
+        })
+        .collect()
+}
+
+/// form lines of a multi-line field, the label goes on the first row and the next rows are
+/// indented to line up with it
+pub fn form_area_lines(label: String, rows: Vec<Vec<Span<'static>>>) -> Vec<Line<'static>> {
+    let indent = " ".repeat(display_width(&label));
+    rows.into_iter()
+        .enumerate()
+        .map(|(idx, row)| {
+            let prefix = if idx == 0 {
+                label.clone()
+            } else {
+                indent.clone()
+            };
+            let mut spans = vec![Span::raw(prefix)];
+            spans.extend(row);
+            Line::from(spans)
+        })
+        .collect()
+}
+
 /// helper function to boostrap popup content
 pub fn generic_popup_stateful<W>(
     title: String,
@@ -114,7 +197,8 @@ pub fn generic_popup_stateful<W>(
     instructions: String,
     f: &mut Frame,
     state: &mut <W as StatefulWidget>::State,
-) where
+) -> Rect
+where
     W: StatefulWidget,
 {
     let area = f.size();
@@ -134,6 +218,7 @@ pub fn generic_popup_stateful<W>(
     f.render_widget(popup_box, popup_rect);
     f.render_stateful_widget(content, popup_layout[0], state);
     f.render_widget(popup_footer, popup_layout[1]);
+    popup_layout[0]
 }
 
 /// helper function to boostrap popup content
@@ -191,9 +276,9 @@ pub fn listitems_from_id_name(list: Vec<HashMap<String, String>>) -> Vec<ListIte
         .collect()
 }
 
-pub fn list_ui(data: Vec<ListItem<'static>>, title: String) -> List<'static> {
+pub fn list_ui(data: Vec<ListItem<'static>>, title: String, theme: &Theme) -> List<'static> {
     List::new(data)
         .block(titled_box(title))
-        .highlight_style(Style::default().bg(HIGHLIGHT_BG))
+        .highlight_style(theme.selection_style())
         .highlight_symbol("->")
 }
@@ -0,0 +1,77 @@
+use ratatui::{layout::*, text::*, widgets::*, Frame};
+
+use crate::controllers::next_controller::NextCtrl;
+use crate::models::task::PRIORITIES;
+use crate::mouse::item_areas;
+use crate::views::table_body;
+use crate::views::theme::Theme;
+
+/// the most urgent tasks, one row each, the first one on top
+pub fn next_view(ctrl: &mut NextCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let title = format!(
+        "Next - the {} most urgent tasks ('s' shows the selected one, 'e' edits it)",
+        ctrl.tasks.len()
+    );
+    if ctrl.tasks.is_empty() {
+        let hint = Paragraph::new(Line::from(Span::styled(
+            "nothing to do: every task is done, blocked or starts later",
+            theme.muted_style(),
+        )))
+        .block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(hint, area);
+        ctrl.row_areas = vec![];
+        return;
+    }
+
+    let header = Row::new(
+        ["Urgency", "Priority", "Id", "Project", "Task", "Due"]
+            .iter()
+            .map(|h| Cell::from(*h)),
+    )
+    .style(theme.table_header_style())
+    .height(1)
+    .bottom_margin(1);
+    let rows: Vec<Row> = ctrl
+        .tasks
+        .iter()
+        .map(|task| {
+            let priority = PRIORITIES
+                .iter()
+                .position(|p| *p == task.priority)
+                .unwrap_or(PRIORITIES.len()) as i32;
+            let project = match task.project.is_empty() {
+                true => "Inbox".to_string(),
+                false => task.project.clone(),
+            };
+            Row::new(vec![
+                Cell::from(format!("{:>7.1}", task.urgency)),
+                Cell::from(Span::styled(
+                    task.priority.clone(),
+                    theme.priority_style(priority),
+                )),
+                Cell::from(format!("#{}", task.id)),
+                Cell::from(project),
+                Cell::from(task.name.clone()),
+                Cell::from(task.due_date.clone()),
+            ])
+        })
+        .collect();
+    let widths = [
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(6),
+        Constraint::Max(24),
+        Constraint::Min(20),
+        Constraint::Length(10),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(theme.row_selected_style())
+        .highlight_symbol(">> ");
+
+    let mut tablestate = ctrl.t_state.clone();
+    f.render_stateful_widget(table, area, &mut tablestate);
+    let heights = vec![1; ctrl.tasks.len()];
+    ctrl.row_areas = item_areas(table_body(area, 2), &heights, tablestate.offset(), 0);
+}
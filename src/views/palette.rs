@@ -0,0 +1,58 @@
+use ratatui::{layout::*, style::*, text::*, widgets::*, Frame};
+
+use crate::controllers::palette_controller::PaletteCtrl;
+use crate::mouse::item_areas;
+use crate::views::input::display_width;
+use crate::views::theme::Theme;
+use crate::views::{centered_rect, titled_box};
+
+pub fn palette_ui(ctrl: &mut PaletteCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let popup_rect = centered_rect(60, 60, area);
+    let popup_box = titled_box("Command palette".to_string());
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([Constraint::Length(2), Constraint::Min(1)].as_ref())
+        .split(popup_box.inner(popup_rect));
+
+    let mut prompt = vec![Span::styled(
+        ": ",
+        Style::default().add_modifier(Modifier::BOLD),
+    )];
+    prompt.extend(ctrl.input.spans(
+        parts[0].width.saturating_sub(2) as usize,
+        Style::default(),
+        theme.cursor_style(),
+    ));
+    let prompt = Paragraph::new(Line::from(prompt));
+
+    let key_width = parts[1].width.saturating_sub(4) as usize;
+    let items: Vec<ListItem> = ctrl
+        .filtered
+        .iter()
+        .map(|idx| {
+            let cmd = &ctrl.commands[*idx];
+            let padding = key_width.saturating_sub(display_width(&cmd.label) + cmd.keys.len());
+            ListItem::new(Line::from(vec![
+                Span::raw(cmd.label.clone()),
+                Span::raw(" ".repeat(padding)),
+                Span::styled(cmd.keys.clone(), theme.muted_style()),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .highlight_style(theme.selection_style())
+        .highlight_symbol("> ");
+
+    f.render_widget(Clear, popup_rect);
+    f.render_widget(popup_box, popup_rect);
+    f.render_widget(prompt, parts[0]);
+    f.render_stateful_widget(list, parts[1], &mut ctrl.l_state);
+    ctrl.popup_area = popup_rect;
+    ctrl.item_areas = item_areas(
+        parts[1],
+        &vec![1; ctrl.filtered.len()],
+        ctrl.l_state.offset(),
+        0,
+    );
+}
@@ -1,61 +1,98 @@
-use ratatui::{layout::*, style::*, text::*, widgets::*, Frame};
+use ratatui::{layout::*, text::*, widgets::*, Frame};
 
+use crate::config;
 use crate::controllers::project_controller::ProjectCtrl;
-use crate::views::{string_min_size, titled_box};
-pub fn list_view(parent_controller: &mut ProjectCtrl, f: &mut Frame, area: Rect) {
+use crate::controllers::CtrlActions;
+use crate::models::project::Project;
+use crate::mouse::item_areas;
+use crate::views::input::truncate_to_width;
+use crate::views::markdown;
+use crate::views::theme::Theme;
+use crate::views::{
+    form_area_lines, format_timestamp, string_min_size, table_body, text_area_rows, titled_box,
+    FORM_AREA_ROWS, FORM_FIELD_WIDTH,
+};
+pub fn list_view(parent_controller: &mut ProjectCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
     //@TODO: add a filter area with an input to search the list above the Table object
     let mut tablestate = parent_controller.t_state.clone();
-    let content = list_project_records(parent_controller);
+    let (content, heights) = list_project_records(parent_controller, theme);
 
     f.render_stateful_widget(content, area, &mut tablestate);
+    parent_controller.row_areas = item_areas(table_body(area, 2), &heights, tablestate.offset(), 1);
 }
 
-pub fn edit_view(parent_controller: &mut ProjectCtrl, f: &mut Frame, area: Rect) {
-    let content = edit_project_form(parent_controller);
+pub fn edit_view(parent_controller: &mut ProjectCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let content = edit_project_form(parent_controller, theme);
 
     f.render_widget(content, area);
 }
 
-pub fn del_view(parent_controller: &mut ProjectCtrl, f: &mut Frame, area: Rect) {
-    let content = show_delete_confirm(parent_controller);
+pub fn del_view(parent_controller: &mut ProjectCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let content = show_delete_confirm(parent_controller, theme);
     f.render_widget(content, area);
 }
 
-pub fn detail_view(parent_controller: &mut ProjectCtrl, f: &mut Frame, area: Rect) {
-    let content = show_detail_project(parent_controller);
+pub fn detail_view(parent_controller: &mut ProjectCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let content = show_detail_project(parent_controller, theme);
     f.render_widget(content, area);
 }
 
-fn list_project_records(controller: &mut ProjectCtrl) -> Table<'_> {
-    let selected_style = Style::default().add_modifier(Modifier::REVERSED);
-    let normal_style = Style::default().bg(Color::LightYellow);
-    let header_cells = ["Id", "Reference", "Name", "Description"]
-        .iter()
-        .map(|h| Cell::from(*h).style(Style::default().fg(Color::Red)));
+/// header and width of a column that can be enabled with `columns.projects`
+fn project_column(name: &str) -> (&'static str, Constraint) {
+    match name {
+        "id" => ("Id", Constraint::Max(6)),
+        "reference" => ("Reference", Constraint::Min(10)),
+        "name" => ("Name", Constraint::Max(30)),
+        "start_date" => ("Starting Date", Constraint::Max(14)),
+        "end_date" => ("Ending Date", Constraint::Max(14)),
+        _ => ("Description", Constraint::Percentage(50)),
+    }
+}
+
+fn project_column_value(project: &Project, name: &str) -> String {
+    match name {
+        "id" => format!("{}", project.id),
+        "reference" => project.reference.clone(),
+        "name" => project.name.clone(),
+        "start_date" => project.start_date.clone(),
+        "end_date" => project.end_date.clone(),
+        _ => project.description.clone(),
+    }
+}
+
+/// the table and the height of each row
+fn list_project_records<'a>(
+    controller: &'a mut ProjectCtrl,
+    theme: &Theme,
+) -> (Table<'a>, Vec<u16>) {
+    let selected_style = theme.row_selected_style();
+    let normal_style = theme.table_header_style();
+    let columns = &config::get().columns.projects;
+    let header_cells = columns.iter().map(|c| Cell::from(project_column(c).0));
     let header = Row::new(header_cells)
         .style(normal_style)
         .height(1)
         .bottom_margin(1);
 
     let mut rows: Vec<Row> = vec![];
+    let mut heights: Vec<u16> = vec![];
 
     match controller.project_list() {
         Ok(list) => {
             rows = list
                 .iter()
                 .map(|item| -> Row<'_> {
-                    let row_values: Vec<String> = vec![
-                        format!("{}", item.id),
-                        item.reference.clone(),
-                        item.name.clone(),
-                        item.description.clone(),
-                    ];
+                    let row_values: Vec<String> = columns
+                        .iter()
+                        .map(|c| project_column_value(item, c))
+                        .collect();
                     let height = row_values
                         .iter()
                         .map(|content| content.chars().filter(|c| *c == '\n').count())
                         .max()
                         .unwrap_or(0)
                         + 1;
+                    heights.push(height as u16);
                     let cells = row_values.iter().map(|c| Cell::from(c.clone()));
                     Row::new(cells).height(height as u16).bottom_margin(1)
                 })
@@ -65,25 +102,21 @@ fn list_project_records(controller: &mut ProjectCtrl) -> Table<'_> {
             //@TODO display error
         }
     }
-    Table::new(
-        rows,
-        [
-            Constraint::Max(6),
-            Constraint::Min(10),
-            Constraint::Max(30),
-            Constraint::Percentage(50),
-        ],
-    )
-    .header(header)
-    .block(Block::default().borders(Borders::ALL).title("Projects"))
-    .highlight_style(selected_style)
-    .highlight_symbol(">> ")
+    let widths: Vec<Constraint> = columns.iter().map(|c| project_column(c).1).collect();
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title("Projects"))
+        .highlight_style(selected_style)
+        .highlight_symbol(">> ");
+    (table, heights)
 }
 
-fn get_project_form_fields(data: &ProjectCtrl, show_selected: bool) -> Vec<Line<'_>> {
-    let normal_bg = Color::Black;
-    let higlight_bg = Color::Yellow;
-    let str_min_size = 60;
+fn get_project_form_fields<'a>(
+    data: &'a ProjectCtrl,
+    show_selected: bool,
+    theme: &Theme,
+) -> Vec<Line<'a>> {
+    let str_min_size = FORM_FIELD_WIDTH;
     let lbl_min_size = 16;
     let record = &data.project_table;
     let fields: Vec<String> = vec![
@@ -104,41 +137,65 @@ fn get_project_form_fields(data: &ProjectCtrl, show_selected: bool) -> Vec<Line<
     let mut form_parts = vec![Line::from(vec![Span::raw("")])];
 
     fields.iter().enumerate().for_each(|(i, x)| {
-        let mut field = String::from(x);
+        let focused = data.field_idx == i as u32 && show_selected;
         let mut label = String::from(&labels[i]);
-        string_min_size(&mut field, str_min_size);
         string_min_size(&mut label, lbl_min_size);
-        form_parts.push(Line::from(vec![
-            Span::raw(label),
-            Span::styled(
-                field,
-                Style::default().bg(if data.field_idx == i as u32 && show_selected {
-                    higlight_bg
-                } else {
-                    normal_bg
-                }),
-            ),
-        ]));
+        if i == 2 {
+            let rows = match (focused, show_selected) {
+                (true, _) => data.input.area_spans(
+                    FORM_AREA_ROWS,
+                    theme.field_style(true),
+                    theme.cursor_style(),
+                ),
+                (false, true) => text_area_rows(x, Some(FORM_AREA_ROWS), theme.field_style(false)),
+                (false, false) => {
+                    let selected = match data.action {
+                        CtrlActions::Detail => Some(data.checkbox_idx),
+                        _ => None,
+                    };
+                    markdown::render(x, FORM_FIELD_WIDTH, theme, selected)
+                }
+            };
+            form_parts.extend(form_area_lines(label, rows));
+            form_parts.push(Line::from(vec![Span::raw("")]));
+            return;
+        }
+        let mut line = vec![Span::raw(label)];
+        if focused {
+            line.extend(data.input.spans(
+                str_min_size,
+                theme.field_style(true),
+                theme.cursor_style(),
+            ));
+        } else {
+            let mut field = truncate_to_width(x, str_min_size);
+            string_min_size(&mut field, str_min_size);
+            line.push(Span::styled(field, theme.field_style(focused)));
+        }
+        form_parts.push(Line::from(line));
         form_parts.push(Line::from(vec![Span::raw("")]));
     });
 
     form_parts
 }
-fn edit_project_form(data: &ProjectCtrl) -> Paragraph {
-    let mut form_parts = get_project_form_fields(data, true);
+fn edit_project_form<'a>(data: &'a ProjectCtrl, theme: &Theme) -> Paragraph<'a> {
+    let mut form_parts = get_project_form_fields(data, true, theme);
 
     form_parts.push(Line::from(vec![Span::raw("")]));
     form_parts.push(Line::from(vec![Span::raw(
         "Press 'Tab' to switch fields, 'Enter' to Save, 'Esc' to cancel.",
     )]));
+    form_parts.push(Line::from(vec![Span::raw(
+        "'Alt-Enter' adds a line break to the description, 'Ctrl-E' opens it in $EDITOR.",
+    )]));
 
     Paragraph::new(form_parts)
         .alignment(Alignment::Center)
         .block(titled_box("Edit Project".to_string()))
 }
 
-fn show_delete_confirm(data: &ProjectCtrl) -> Paragraph {
-    let mut form_parts = get_project_form_fields(data, false);
+fn show_delete_confirm<'a>(data: &'a ProjectCtrl, theme: &Theme) -> Paragraph<'a> {
+    let mut form_parts = get_project_form_fields(data, false, theme);
 
     form_parts.push(Line::from(vec![Span::raw("")]));
     form_parts.push(Line::from(vec![Span::raw(
@@ -151,13 +208,24 @@ fn show_delete_confirm(data: &ProjectCtrl) -> Paragraph {
         .block(titled_box("Confirm Deletion".to_string()))
 }
 
-fn show_detail_project(data: &ProjectCtrl) -> Paragraph {
-    let mut form_parts = get_project_form_fields(data, false);
+fn show_detail_project<'a>(data: &'a ProjectCtrl, theme: &Theme) -> Paragraph<'a> {
+    let mut form_parts = get_project_form_fields(data, false, theme);
+    form_parts.push(Line::from(vec![Span::raw(format!(
+        "Created : {} by {}    Updated : {}",
+        format_timestamp(data.project_table.created_at),
+        data.get_creator_name(),
+        format_timestamp(data.project_table.updated_at)
+    ))]));
 
     form_parts.push(Line::from(vec![Span::raw("")]));
     form_parts.push(Line::from(vec![Span::raw(
         "Press 'Esc' to close or 'Enter' to edit.",
     )]));
+    if !markdown::checkboxes(&data.project_table.description).is_empty() {
+        form_parts.push(Line::from(vec![Span::raw(
+            "'Up'/'Down' select a checkbox, 'Space' checks or unchecks it.",
+        )]));
+    }
 
     Paragraph::new(form_parts)
         .alignment(Alignment::Center)
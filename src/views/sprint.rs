@@ -0,0 +1,326 @@
+use ratatui::{layout::*, text::*, widgets::*, Frame};
+use std::collections::HashMap;
+
+use crate::controllers::sprint_controller::{PlanningFocus, SprintCtrl};
+use crate::estimate::EstimateScale;
+use crate::models::{sprint::Sprint, DbObj};
+use crate::mouse::item_areas;
+use crate::views::input::truncate_to_width;
+use crate::views::task::project_list_ui;
+use crate::views::theme::Theme;
+use crate::views::{
+    form_area_lines, generic_popup_stateful, list_ui, string_min_size, table_body, text_area_rows,
+    titled_box, FORM_AREA_ROWS, FORM_FIELD_WIDTH,
+};
+pub fn list_view(parent_controller: &mut SprintCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let mut tablestate = parent_controller.t_state.clone();
+    let (content, heights) = list_sprint_records(parent_controller, theme);
+
+    f.render_stateful_widget(content, area, &mut tablestate);
+    parent_controller.row_areas = item_areas(table_body(area, 2), &heights, tablestate.offset(), 1);
+}
+
+pub fn edit_view(parent_controller: &mut SprintCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let content = edit_sprint_form(parent_controller, theme);
+
+    f.render_widget(content, area);
+    if parent_controller.show_popup {
+        project_popup(parent_controller, f, theme);
+    }
+}
+
+pub fn del_view(parent_controller: &mut SprintCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let content = show_delete_confirm(parent_controller, theme);
+    f.render_widget(content, area);
+}
+
+/// backlog on the left, the sprint on the right, with the points of each
+pub fn planning_view(parent_controller: &mut SprintCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let data = parent_controller;
+    let scale = EstimateScale::configured();
+    let zones = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(5), Constraint::Length(3)].as_ref())
+        .split(area);
+    let cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .split(zones[0]);
+
+    let backlog_title = format!(
+        "Backlog ({})",
+        scale.total_label(total_points(&data.backlog))
+    );
+    let sprint_title = format!(
+        "{} {} - {} ({})",
+        data.sprint_table.name,
+        data.sprint_table.start_date,
+        data.sprint_table.end_date,
+        scale.total_label(total_points(&data.planned))
+    );
+    let lists = [
+        (
+            PlanningFocus::Backlog,
+            backlog_title,
+            &data.backlog,
+            data.backlog_state.clone(),
+        ),
+        (
+            PlanningFocus::Sprint,
+            sprint_title,
+            &data.planned,
+            data.planned_state.clone(),
+        ),
+    ];
+    for (i, (focus, title, tasks, state)) in lists.iter().enumerate() {
+        let mut state = state.clone();
+        let title = match data.focus == *focus {
+            true => format!("<{}>", title),
+            false => {
+                state.select(None);
+                title.clone()
+            }
+        };
+        let content = list_ui(planning_items(tasks, &scale), title, theme);
+        f.render_stateful_widget(content, cols[i], &mut state);
+    }
+
+    let goal = match data.sprint_table.goal.is_empty() {
+        true => "no goal".to_string(),
+        false => data.sprint_table.goal.replace('\n', " "),
+    };
+    let footer = Paragraph::new(vec![Line::from(vec![Span::raw(format!(
+        "Goal : {}    'Left'/'Right' switch lists, 'Enter' moves the task across, 'Esc' to close.",
+        goal
+    ))])])
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(footer, zones[1]);
+}
+
+fn total_points(tasks: &[HashMap<String, String>]) -> i32 {
+    tasks
+        .iter()
+        .filter_map(|task| task.get("weight").and_then(|w| w.parse::<i32>().ok()))
+        .sum()
+}
+
+fn planning_items(
+    tasks: &[HashMap<String, String>],
+    scale: &EstimateScale,
+) -> Vec<ListItem<'static>> {
+    tasks
+        .iter()
+        .map(|task| {
+            let weight = task
+                .get("weight")
+                .and_then(|w| w.parse::<i32>().ok())
+                .unwrap_or(0);
+            ListItem::new(format!(
+                "#{} {} [{}]",
+                task.get("id").unwrap(),
+                task.get("name").unwrap(),
+                scale.label(weight)
+            ))
+        })
+        .collect()
+}
+
+/// header and width of the sprint table columns
+const SPRINT_COLUMNS: [(&str, &str, Constraint); 8] = [
+    ("Id", "id", Constraint::Max(6)),
+    ("Project", "project_name", Constraint::Max(20)),
+    ("Name", "name", Constraint::Max(20)),
+    ("Start", "start_date", Constraint::Max(12)),
+    ("End", "end_date", Constraint::Max(12)),
+    ("State", "state", Constraint::Max(8)),
+    ("Points", "points", Constraint::Max(10)),
+    ("Goal", "goal", Constraint::Percentage(40)),
+];
+
+/// the table and the height of each row
+fn list_sprint_records<'a>(controller: &'a mut SprintCtrl, theme: &Theme) -> (Table<'a>, Vec<u16>) {
+    let selected_style = theme.row_selected_style();
+    let normal_style = theme.table_header_style();
+    let scale = EstimateScale::configured();
+    let header_cells = SPRINT_COLUMNS.iter().map(|c| Cell::from(c.0));
+    let header = Row::new(header_cells)
+        .style(normal_style)
+        .height(1)
+        .bottom_margin(1);
+
+    let mut rows: Vec<Row> = vec![];
+    let mut heights: Vec<u16> = vec![];
+
+    match controller.sprint_list() {
+        Ok(list) => {
+            rows = list
+                .iter()
+                .map(|item| -> Row<'_> {
+                    let mut fields = item.clone();
+                    let sprint = Sprint::from_hashmap(&mut fields).unwrap_or_default();
+                    let row_values: Vec<String> = SPRINT_COLUMNS
+                        .iter()
+                        .map(|c| match c.1 {
+                            "state" => sprint.state().to_string(),
+                            "points" => scale.total_label(
+                                item.get("points")
+                                    .and_then(|p| p.parse::<i32>().ok())
+                                    .unwrap_or(0),
+                            ),
+                            key => item.get(key).cloned().unwrap_or_default(),
+                        })
+                        .collect();
+                    let height = row_values
+                        .iter()
+                        .map(|content| content.chars().filter(|c| *c == '\n').count())
+                        .max()
+                        .unwrap_or(0)
+                        + 1;
+                    heights.push(height as u16);
+                    let cells = row_values.iter().map(|c| Cell::from(c.clone()));
+                    Row::new(cells).height(height as u16).bottom_margin(1)
+                })
+                .collect();
+        }
+        Err(_e) => {
+            //@TODO display error
+        }
+    }
+    let widths: Vec<Constraint> = SPRINT_COLUMNS.iter().map(|c| c.2).collect();
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Sprints ('s' to plan, 'c' to close)"),
+        )
+        .highlight_style(selected_style)
+        .highlight_symbol(">> ");
+    (table, heights)
+}
+
+fn get_sprint_form_fields<'a>(
+    data: &'a SprintCtrl,
+    show_selected: bool,
+    theme: &Theme,
+) -> Vec<Line<'a>> {
+    let str_min_size = FORM_FIELD_WIDTH;
+    let lbl_min_size = 16;
+    let record = &data.sprint_table;
+    let fields: Vec<String> = vec![
+        data.get_project_name(),
+        record.name.clone(),
+        record.goal.clone(),
+        record.start_date.clone(),
+        record.end_date.clone(),
+    ];
+    let labels = [
+        "Project :".to_string(),
+        "Name :".to_string(),
+        "Goal :".to_string(),
+        "Starting Date :".to_string(),
+        "Ending Date :".to_string(),
+    ];
+
+    let mut form_parts = vec![Line::from(vec![Span::raw("")])];
+
+    fields.iter().enumerate().for_each(|(i, x)| {
+        let focused = data.field_idx == i as u32 && show_selected;
+        let mut label = String::from(&labels[i]);
+        string_min_size(&mut label, lbl_min_size);
+        if i == 2 {
+            let rows = match focused {
+                true => data.input.area_spans(
+                    FORM_AREA_ROWS,
+                    theme.field_style(true),
+                    theme.cursor_style(),
+                ),
+                false => text_area_rows(x, Some(FORM_AREA_ROWS), theme.field_style(false)),
+            };
+            form_parts.extend(form_area_lines(label, rows));
+            form_parts.push(Line::from(vec![Span::raw("")]));
+            return;
+        }
+        let mut line = vec![Span::raw(label)];
+        if focused && i > 0 {
+            line.extend(data.input.spans(
+                str_min_size,
+                theme.field_style(true),
+                theme.cursor_style(),
+            ));
+        } else {
+            let mut field = truncate_to_width(x, str_min_size);
+            string_min_size(&mut field, str_min_size);
+            line.push(Span::styled(field, theme.field_style(focused)));
+        }
+        form_parts.push(Line::from(line));
+        form_parts.push(Line::from(vec![Span::raw("")]));
+    });
+
+    form_parts
+}
+
+fn edit_sprint_form<'a>(data: &'a SprintCtrl, theme: &Theme) -> Paragraph<'a> {
+    let mut form_parts = get_sprint_form_fields(data, true, theme);
+
+    if let Some(error) = &data.form_error {
+        form_parts.push(Line::from(Span::styled(error.clone(), theme.error_style())));
+    }
+    form_parts.push(Line::from(vec![Span::raw("")]));
+    form_parts.push(Line::from(vec![Span::raw(
+        "Press 'Tab' to switch fields, 'Enter' to Save, 'Esc' to cancel.",
+    )]));
+    form_parts.push(Line::from(vec![Span::raw(
+        "'Space' on the project picks it from a list, dates are YYYY-MM-DD.",
+    )]));
+
+    Paragraph::new(form_parts)
+        .alignment(Alignment::Center)
+        .block(titled_box("Edit Sprint".to_string()))
+}
+
+fn show_delete_confirm<'a>(data: &'a SprintCtrl, theme: &Theme) -> Paragraph<'a> {
+    let mut form_parts = get_sprint_form_fields(data, false, theme);
+
+    form_parts.push(Line::from(vec![Span::raw("")]));
+    let (title, message) = match data.closing {
+        true => (
+            "Close Sprint",
+            format!(
+                "Unfinished tasks will move to {}. Press 'Esc' to cancel or 'Enter' to confirm.",
+                data.carry_over_name()
+            ),
+        ),
+        false => (
+            "Confirm Deletion",
+            "Its tasks go back to the backlog. Press 'Esc' to cancel or 'Enter' to confirm."
+                .to_string(),
+        ),
+    };
+    form_parts.push(Line::from(vec![Span::raw(message)]));
+
+    Paragraph::new(form_parts)
+        .alignment(Alignment::Center)
+        .block(titled_box(title.to_string()))
+}
+
+fn project_popup(data: &mut SprintCtrl, f: &mut Frame, theme: &Theme) {
+    if let Ok(list) = data.project_list() {
+        let heights = vec![1; list.len()];
+        let content = project_list_ui(list, theme);
+
+        let list_area = generic_popup_stateful(
+            "Project List".to_string(),
+            content,
+            "Press UP and Down to select, Enter to accept and Esc to cancel.".to_string(),
+            f,
+            &mut data.l_state,
+        );
+        data.popup_areas = item_areas(
+            Block::default().borders(Borders::ALL).inner(list_area),
+            &heights,
+            data.l_state.offset(),
+            0,
+        );
+    }
+}
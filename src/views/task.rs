@@ -1,76 +1,214 @@
-use ratatui::{layout::*, style::*, text::*, widgets::*, Frame};
+use ratatui::{layout::*, text::*, widgets::*, Frame};
 
-use crate::controllers::task_controller::{PopupTaskType, TaskCtrl};
+use crate::config;
+use crate::controllers::task_controller::{DetailInput, PopupTaskType, TaskCtrl, TaskFilter};
+use crate::controllers::CtrlActions;
+use crate::current_user_id;
+use crate::estimate::EstimateScale;
+use crate::models::task::PRIORITIES;
+use crate::mouse::item_areas;
+use crate::views::input::{truncate_to_width, wrap_text};
+use crate::views::markdown;
+use crate::views::theme::Theme;
 use crate::views::{
-    generic_popup_stateful, list_ui, listitems_from_id_name, string_min_size, titled_box,
-    DEFAULT_BG, HIGHLIGHT_BG,
+    form_area_lines, format_timestamp, generic_popup_stateful, list_ui, listitems_from_id_name,
+    string_min_size, table_body, text_area_rows, titled_box, FORM_AREA_ROWS, FORM_FIELD_WIDTH,
 };
 use std::collections::HashMap;
 
-pub fn list_view(parent_controller: &mut TaskCtrl, f: &mut Frame, area: Rect) {
+pub fn list_view(parent_controller: &mut TaskCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
     //@TODO: add a filter area with an input to search the list above the Table object
     let mut tablestate = parent_controller.t_state.clone();
-    let content = list_task_records(parent_controller);
+    let (content, heights) = list_task_records(parent_controller, theme);
 
     f.render_stateful_widget(content, area, &mut tablestate);
+    parent_controller.row_areas = item_areas(table_body(area, 2), &heights, tablestate.offset(), 1);
 }
 
-pub fn edit_view(parent_controller: &mut TaskCtrl, f: &mut Frame, area: Rect) {
-    let content = edit_task_form(parent_controller);
+pub fn edit_view(parent_controller: &mut TaskCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let content = edit_task_form(parent_controller, theme);
 
     f.render_widget(content, area);
     if parent_controller.show_popup {
-        show_task_popup(parent_controller, f);
+        show_task_popup(parent_controller, f, theme);
     }
 }
 
-pub fn show_task_popup(data: &mut TaskCtrl, f: &mut Frame) {
+pub fn show_task_popup(data: &mut TaskCtrl, f: &mut Frame, theme: &Theme) {
     match data.popup_type {
-        PopupTaskType::ProjectList => project_popup(data, f),
-        PopupTaskType::TaskStatusList => task_status_popup(data, f),
+        PopupTaskType::ProjectList => project_popup(data, f, theme),
+        PopupTaskType::TaskStatusList => task_status_popup(data, f, theme),
+        PopupTaskType::AssigneeList => assignee_popup(data, f, theme),
+        PopupTaskType::EstimateList => estimate_popup(data, f, theme),
+        PopupTaskType::DependencyList => dependency_popup(data, f, theme),
+        PopupTaskType::ParentList => parent_popup(data, f, theme),
+        PopupTaskType::PriorityList => priority_popup(data, f, theme),
         _ => {}
     }
 }
-pub fn del_view(parent_controller: &mut TaskCtrl, f: &mut Frame, area: Rect) {
-    let content = show_delete_confirm(parent_controller);
+pub fn del_view(parent_controller: &mut TaskCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let content = show_delete_confirm(parent_controller, theme);
     f.render_widget(content, area);
 }
 
-pub fn detail_view(parent_controller: &mut TaskCtrl, f: &mut Frame, area: Rect) {
-    let content = show_detail_task(parent_controller);
-    f.render_widget(content, area);
+pub fn detail_view(parent_controller: &mut TaskCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let zones = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(10), Constraint::Percentage(40)].as_ref())
+        .split(area);
+    let content = show_detail_task(parent_controller, theme);
+    f.render_widget(content, zones[0]);
+    comments_panel(parent_controller, f, zones[1], theme);
+}
+
+/// comment thread of the task, with the comment being written at the bottom
+fn comments_panel(data: &mut TaskCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let panel = titled_box(format!("Comments ({})", data.comments.len()));
+    let inner = panel.inner(area);
+    f.render_widget(panel, area);
+
+    let input = match &data.detail_input {
+        Some((DetailInput::Comment(_), input)) => Some(input),
+        _ => None,
+    };
+    let input_rows = match input {
+        Some(_) => FORM_AREA_ROWS as u16 + 2,
+        None => 0,
+    };
+    let zones = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(input_rows)].as_ref())
+        .split(inner);
+
+    let body_width = (zones[0].width as usize).saturating_sub(4).max(10);
+    let items: Vec<ListItem> = data
+        .comments
+        .iter()
+        .map(|comment| {
+            let author = match comment.get("author_id") {
+                Some(id) if *id == current_user_id().to_string() => "you".to_string(),
+                _ => match comment.get("author_name") {
+                    Some(name) if !name.is_empty() => name.to_string(),
+                    _ => "unknown".to_string(),
+                },
+            };
+            let timestamp = |key: &str| {
+                comment
+                    .get(key)
+                    .and_then(|t| t.parse::<i64>().ok())
+                    .unwrap_or(0)
+            };
+            let mut header = format!("{} · {}", author, format_timestamp(timestamp("created_at")));
+            if timestamp("updated_at") > timestamp("created_at") {
+                header.push_str(" (edited)");
+            }
+            let mut lines = vec![Line::from(Span::styled(header, theme.muted_style()))];
+            lines.extend(
+                wrap_text(
+                    comment.get("body").map(|b| b.as_str()).unwrap_or(""),
+                    body_width,
+                )
+                .into_iter()
+                .map(|row| Line::from(Span::styled(row, theme.text_style()))),
+            );
+            ListItem::new(lines)
+        })
+        .collect();
+    if items.is_empty() {
+        let hint = Paragraph::new(Line::from(Span::styled(
+            "no comments yet, press 'c' to add one",
+            theme.muted_style(),
+        )));
+        f.render_widget(hint, zones[0]);
+    } else {
+        let list = List::new(items)
+            .highlight_style(theme.selection_style())
+            .highlight_symbol("->");
+        f.render_stateful_widget(list, zones[0], &mut data.comment_state);
+    }
+
+    if let Some(input) = input {
+        let rows = input.area_spans(
+            FORM_AREA_ROWS,
+            theme.field_style(true),
+            theme.cursor_style(),
+        );
+        let editor = Paragraph::new(rows.into_iter().map(Line::from).collect::<Vec<Line>>())
+            .block(titled_box("Comment".to_string()));
+        f.render_widget(editor, zones[1]);
+    }
+}
+
+/// header, width and task_list() key of a column that can be enabled with `columns.tasks`
+fn task_column(name: &str) -> (&'static str, Constraint, &'static str) {
+    match name {
+        "id" => ("Id", Constraint::Max(6), "id"),
+        "project" => ("Project", Constraint::Max(30), "project_name"),
+        "name" => ("Name", Constraint::Max(30), "name"),
+        "weight" => ("Estimate", Constraint::Max(8), "weight"),
+        "status" => ("Status", Constraint::Max(12), "status_name"),
+        "checklist" => ("Checklist", Constraint::Max(10), "checklist"),
+        "comments" => ("Comments", Constraint::Max(9), "comments"),
+        "assignees" => ("Assigned to", Constraint::Max(24), "assignees"),
+        "due" => ("Due", Constraint::Max(10), "due_date"),
+        "start" => ("Start", Constraint::Max(10), "start_date"),
+        "depends" => ("Depends on", Constraint::Max(12), "depends_on"),
+        "priority" => ("Priority", Constraint::Max(8), "priority"),
+        "urgency" => ("Urgency", Constraint::Max(7), "urgency"),
+        "tags" => ("Tags", Constraint::Max(20), "tags"),
+        _ => ("Description", Constraint::Percentage(30), "description"),
+    }
 }
 
-fn list_task_records(controller: &mut TaskCtrl) -> Table<'_> {
-    let selected_style = Style::default().add_modifier(Modifier::REVERSED);
-    let normal_style = Style::default().bg(Color::LightYellow);
-    let header_cells = ["Id", "Project", "Name", "Description"]
+/// the table and the height of each row
+fn list_task_records<'a>(controller: &'a mut TaskCtrl, theme: &Theme) -> (Table<'a>, Vec<u16>) {
+    let selected_style = theme.row_selected_style();
+    let normal_style = theme.table_header_style();
+    let columns: Vec<(&str, Constraint, &str)> = config::get()
+        .columns
+        .tasks
         .iter()
-        .map(|h| Cell::from(*h).style(Style::default().fg(Color::Red)));
+        .map(|c| task_column(c))
+        .collect();
+    let header_cells = columns.iter().map(|(h, _, _)| Cell::from(*h));
     let header = Row::new(header_cells)
         .style(normal_style)
         .height(1)
         .bottom_margin(1);
 
     let mut rows: Vec<Row> = vec![];
+    let mut heights: Vec<u16> = vec![];
+    let scale = EstimateScale::configured();
 
     match controller.task_list() {
         Ok(list) => {
             rows = list
                 .iter()
                 .map(|item| -> Row<'_> {
-                    let row_values: Vec<String> = vec![
-                        item.get("id").unwrap().to_string(),
-                        item.get("project_name").unwrap().to_string(),
-                        item.get("name").unwrap().to_string(),
-                        item.get("description").unwrap().to_string(),
-                    ];
+                    let row_values: Vec<String> = columns
+                        .iter()
+                        .map(|(_, _, key)| match *key {
+                            "weight" => scale.label(
+                                item.get(*key)
+                                    .and_then(|w| w.parse().ok())
+                                    .unwrap_or_default(),
+                            ),
+                            "priority" => item
+                                .get(*key)
+                                .and_then(|p| p.parse::<usize>().ok())
+                                .and_then(|p| PRIORITIES.get(p))
+                                .map(|p| p.to_string())
+                                .unwrap_or_default(),
+                            _ => item.get(*key).cloned().unwrap_or_default(),
+                        })
+                        .collect();
                     let height = row_values
                         .iter()
                         .map(|content| content.chars().filter(|c| *c == '\n').count())
                         .max()
                         .unwrap_or(0)
                         + 1;
+                    heights.push(height as u16);
                     let cells = row_values.iter().map(|c| Cell::from(c.clone()));
                     Row::new(cells).height(height as u16).bottom_margin(1)
                 })
@@ -80,29 +218,32 @@ fn list_task_records(controller: &mut TaskCtrl) -> Table<'_> {
             //@TODO display error
         }
     }
-    Table::new(
-        rows,
-        [
-            Constraint::Max(6),
-            Constraint::Max(30),
-            Constraint::Max(30),
-            Constraint::Percentage(30),
-        ],
-    )
-    .header(header)
-    .block(Block::default().borders(Borders::ALL).title("Tasks"))
-    .highlight_style(selected_style)
-    .highlight_symbol(">> ")
-}
-
-fn get_task_form_fields(data: &TaskCtrl, show_selected: bool) -> Vec<Line<'_>> {
-    let normal_bg = DEFAULT_BG;
-    let higlight_bg = HIGHLIGHT_BG;
-
-    let str_min_size = 60;
+    let widths: Vec<Constraint> = columns.iter().map(|(_, w, _)| *w).collect();
+    let title = match controller.filter {
+        TaskFilter::All => "Tasks",
+        TaskFilter::AssignedToMe => "Tasks (assigned to me)",
+        TaskFilter::CreatedByMe => "Tasks (created by me)",
+    };
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(selected_style)
+        .highlight_symbol(">> ");
+    (table, heights)
+}
+
+fn get_task_form_fields<'a>(
+    data: &'a TaskCtrl,
+    show_selected: bool,
+    theme: &Theme,
+) -> Vec<Line<'a>> {
+    let str_min_size = FORM_FIELD_WIDTH;
     let lbl_min_size = 16;
     let project_name = data.get_project_name();
     let status_name = data.get_status_name();
+    let assignee_names = data.get_assignee_names();
+    let dependency_names = data.get_dependency_names();
+    let parent_name = data.get_parent_name();
     let record = &data.task_table;
 
     let fields: Vec<String> = vec![
@@ -110,89 +251,301 @@ fn get_task_form_fields(data: &TaskCtrl, show_selected: bool) -> Vec<Line<'_>> {
         project_name.clone(),
         record.name.clone(),
         record.description.clone(),
-        record.weight.to_string(),
+        EstimateScale::configured().label(record.weight),
         status_name.clone(),
+        assignee_names,
+        record.due_date.clone(),
+        record.start_date.clone(),
+        dependency_names,
+        parent_name,
+        record.priority_label().to_string(),
+        data.tags.clone(),
     ];
     let labels = vec![
         "Project :".to_string(),
         "Name :".to_string(),
         "Description :".to_string(),
-        "Weight :".to_string(),
+        "Estimate :".to_string(),
         "Status :".to_string(),
+        "Assigned to :".to_string(),
+        "Due date :".to_string(),
+        "Start date :".to_string(),
+        "Depends on :".to_string(),
+        "Parent :".to_string(),
+        "Priority :".to_string(),
+        "Tags :".to_string(),
     ];
 
     let mut form_parts = vec![Line::from(vec![Span::raw("")])];
 
     fields.iter().enumerate().for_each(|(i, x)| {
-        let mut field = String::from(x);
+        let focused = data.field_idx == i as u32 && show_selected;
         let mut label = String::from(&labels[i]);
-        string_min_size(&mut field, str_min_size);
         string_min_size(&mut label, lbl_min_size);
-        form_parts.push(Line::from(vec![
-            Span::raw(label),
-            Span::styled(
-                field,
-                Style::default().bg(if data.field_idx == i as u32 && show_selected {
-                    higlight_bg
-                } else {
-                    normal_bg
-                }),
-            ),
-        ]));
+        if i == 2 {
+            let rows = match (focused, show_selected) {
+                (true, _) => data.input.area_spans(
+                    FORM_AREA_ROWS,
+                    theme.field_style(true),
+                    theme.cursor_style(),
+                ),
+                (false, true) => text_area_rows(x, Some(FORM_AREA_ROWS), theme.field_style(false)),
+                (false, false) => {
+                    let selected = match data.action {
+                        CtrlActions::Detail => Some(data.detail_idx),
+                        _ => None,
+                    };
+                    markdown::render(x, FORM_FIELD_WIDTH, theme, selected)
+                }
+            };
+            form_parts.extend(form_area_lines(label, rows));
+            form_parts.push(Line::from(vec![Span::raw("")]));
+            return;
+        }
+        let mut line = vec![Span::raw(label)];
+        let picked = [0, 4, 5, 8, 9, 10].contains(&i) || (i == 3 && data.picks_estimate());
+        if focused && !picked {
+            line.extend(data.input.spans(
+                str_min_size,
+                theme.field_style(true),
+                theme.cursor_style(),
+            ));
+        } else {
+            let mut field = truncate_to_width(x, str_min_size);
+            string_min_size(&mut field, str_min_size);
+            line.push(Span::styled(field, theme.field_style(focused)));
+        }
+        form_parts.push(Line::from(line));
         form_parts.push(Line::from(vec![Span::raw("")]));
     });
 
     form_parts
 }
 
-pub fn project_list_ui(list: Vec<HashMap<String, String>>) -> List<'static> {
-    list_ui(listitems_from_id_name(list), "".to_string())
+pub fn project_list_ui(list: Vec<HashMap<String, String>>, theme: &Theme) -> List<'static> {
+    list_ui(listitems_from_id_name(list), "".to_string(), theme)
 }
 
-pub fn task_status_list_ui(list: Vec<HashMap<String, String>>) -> List<'static> {
-    list_ui(listitems_from_id_name(list), "".to_string())
+pub fn task_status_list_ui(list: Vec<HashMap<String, String>>, theme: &Theme) -> List<'static> {
+    list_ui(listitems_from_id_name(list), "".to_string(), theme)
 }
 
-fn project_popup(data: &mut TaskCtrl, f: &mut Frame) {
+fn project_popup(data: &mut TaskCtrl, f: &mut Frame, theme: &Theme) {
     match data.project_list() {
         Ok(list) => {
-            let content = project_list_ui(list);
+            let heights = vec![1; list.len()];
+            let content = project_list_ui(list, theme);
 
-            generic_popup_stateful(
+            let list_area = generic_popup_stateful(
                 "Project List".to_string(),
                 content,
                 "Press UP and Down to select, Enter to accept and Esc to cancel.".to_string(),
                 f,
                 &mut data.l_state,
             );
+            data.popup_areas = item_areas(
+                Block::default().borders(Borders::ALL).inner(list_area),
+                &heights,
+                data.l_state.offset(),
+                0,
+            );
         }
-        Err(e) => {}
+        Err(e) => log::error!("failed to load the projects: {}", e),
     }
 }
 
-fn task_status_popup(data: &mut TaskCtrl, f: &mut Frame) {
+fn task_status_popup(data: &mut TaskCtrl, f: &mut Frame, theme: &Theme) {
     match data.task_status_list() {
         Ok(list) => {
-            let content = task_status_list_ui(list);
+            let heights = vec![1; list.len()];
+            let content = task_status_list_ui(list, theme);
 
-            generic_popup_stateful(
+            let list_area = generic_popup_stateful(
                 "Task Status List".to_string(),
                 content,
                 "Press UP and Down to select, Enter to accept and Esc to cancel.".to_string(),
                 f,
                 &mut data.l_state,
             );
+            data.popup_areas = item_areas(
+                Block::default().borders(Borders::ALL).inner(list_area),
+                &heights,
+                data.l_state.offset(),
+                0,
+            );
+        }
+        Err(e) => log::error!("failed to load the task statuses: {}", e),
+    }
+}
+
+fn estimate_popup(data: &mut TaskCtrl, f: &mut Frame, theme: &Theme) {
+    let choices = EstimateScale::configured().choices().unwrap_or_default();
+    let heights = vec![1; choices.len()];
+    let items: Vec<ListItem> = choices
+        .iter()
+        .map(|(label, _)| ListItem::new(Line::from(label.clone())))
+        .collect();
+    let content = list_ui(items, "".to_string(), theme);
+
+    let list_area = generic_popup_stateful(
+        "Estimate".to_string(),
+        content,
+        "Press UP and Down to select, Enter to accept and Esc to cancel.".to_string(),
+        f,
+        &mut data.l_state,
+    );
+    data.popup_areas = item_areas(
+        Block::default().borders(Borders::ALL).inner(list_area),
+        &heights,
+        data.l_state.offset(),
+        0,
+    );
+}
+
+fn assignee_popup(data: &mut TaskCtrl, f: &mut Frame, theme: &Theme) {
+    match data.person_list() {
+        Ok(people) => {
+            let heights = vec![1; people.len()];
+            let items: Vec<ListItem> = people
+                .iter()
+                .map(|person| {
+                    let mark = match data.assignees.contains(&person.id) {
+                        true => 'x',
+                        false => ' ',
+                    };
+                    ListItem::new(Line::from(format!("[{}] {}", mark, person.full_name())))
+                })
+                .collect();
+            let content = list_ui(items, "".to_string(), theme);
+
+            let list_area = generic_popup_stateful(
+                "Assign People".to_string(),
+                content,
+                "Press Space to assign or unassign, Enter to pick one and close, Esc to close."
+                    .to_string(),
+                f,
+                &mut data.l_state,
+            );
+            data.popup_areas = item_areas(
+                Block::default().borders(Borders::ALL).inner(list_area),
+                &heights,
+                data.l_state.offset(),
+                0,
+            );
+        }
+        Err(e) => {
+            log::error!("failed to list people: {}", e);
+        }
+    }
+}
+
+fn dependency_popup(data: &mut TaskCtrl, f: &mut Frame, theme: &Theme) {
+    match data.dependency_list() {
+        Ok(tasks) => {
+            let heights = vec![1; tasks.len()];
+            let items: Vec<ListItem> = tasks
+                .iter()
+                .map(|task| {
+                    let mark = match data.depends_on.contains(&task.id) {
+                        true => 'x',
+                        false => ' ',
+                    };
+                    ListItem::new(Line::from(format!("[{}] #{} {}", mark, task.id, task.name)))
+                })
+                .collect();
+            let content = list_ui(items, "".to_string(), theme);
+
+            let list_area = generic_popup_stateful(
+                "Depends On".to_string(),
+                content,
+                "Press Space to add or remove, Enter to pick one and close, Esc to close."
+                    .to_string(),
+                f,
+                &mut data.l_state,
+            );
+            data.popup_areas = item_areas(
+                Block::default().borders(Borders::ALL).inner(list_area),
+                &heights,
+                data.l_state.offset(),
+                0,
+            );
+        }
+        Err(e) => {
+            log::error!("failed to list tasks: {}", e);
+        }
+    }
+}
+
+fn parent_popup(data: &mut TaskCtrl, f: &mut Frame, theme: &Theme) {
+    match data.dependency_list() {
+        Ok(tasks) => {
+            let heights = vec![1; tasks.len() + 1];
+            let mut items = vec![ListItem::new(Line::from("-- no parent --"))];
+            items.extend(
+                tasks
+                    .iter()
+                    .map(|task| ListItem::new(Line::from(format!("#{} {}", task.id, task.name)))),
+            );
+            let content = list_ui(items, "".to_string(), theme);
+
+            let list_area = generic_popup_stateful(
+                "Parent Task".to_string(),
+                content,
+                "Press UP and Down to select, Enter to accept and Esc to cancel.".to_string(),
+                f,
+                &mut data.l_state,
+            );
+            data.popup_areas = item_areas(
+                Block::default().borders(Borders::ALL).inner(list_area),
+                &heights,
+                data.l_state.offset(),
+                0,
+            );
+        }
+        Err(e) => {
+            log::error!("failed to list tasks: {}", e);
         }
-        Err(e) => {}
     }
 }
 
-fn edit_task_form(data: &TaskCtrl) -> Paragraph {
-    let mut form_parts = get_task_form_fields(data, true);
+fn priority_popup(data: &mut TaskCtrl, f: &mut Frame, theme: &Theme) {
+    let heights = vec![1; PRIORITIES.len()];
+    let items: Vec<ListItem> = PRIORITIES
+        .iter()
+        .map(|label| ListItem::new(Line::from(label.to_string())))
+        .collect();
+    let content = list_ui(items, "".to_string(), theme);
+
+    let list_area = generic_popup_stateful(
+        "Priority".to_string(),
+        content,
+        "Press UP and Down to select, Enter to accept and Esc to cancel.".to_string(),
+        f,
+        &mut data.l_state,
+    );
+    data.popup_areas = item_areas(
+        Block::default().borders(Borders::ALL).inner(list_area),
+        &heights,
+        data.l_state.offset(),
+        0,
+    );
+}
+
+fn edit_task_form<'a>(data: &'a TaskCtrl, theme: &Theme) -> Paragraph<'a> {
+    let mut form_parts = get_task_form_fields(data, true, theme);
 
+    if let Some(error) = &data.form_error {
+        form_parts.push(Line::from(Span::styled(error.clone(), theme.error_style())));
+    }
     form_parts.push(Line::from(vec![Span::raw("")]));
     form_parts.push(Line::from(vec![Span::raw(
-        "Press 'Tab' to switch fields, 'Enter' to Save, 'Esc' to cancel.",
+        "Press 'Tab' to switch fields, 'Enter' to Save, 'Esc' to cancel, dates are YYYY-MM-DD, tags are comma separated.",
+    )]));
+    form_parts.push(Line::from(vec![Span::raw(
+        "'Space' picks the project, status, estimate, assignees, dependencies, parent or priority.",
+    )]));
+    form_parts.push(Line::from(vec![Span::raw(
+        "'Alt-Enter' adds a line break to the description, 'Ctrl-E' opens it in $EDITOR.",
     )]));
 
     Paragraph::new(form_parts)
@@ -200,8 +553,8 @@ fn edit_task_form(data: &TaskCtrl) -> Paragraph {
         .block(titled_box("Edit Task".to_string()))
 }
 
-fn show_delete_confirm(data: &TaskCtrl) -> Paragraph {
-    let mut form_parts = get_task_form_fields(data, false);
+fn show_delete_confirm<'a>(data: &'a TaskCtrl, theme: &Theme) -> Paragraph<'a> {
+    let mut form_parts = get_task_form_fields(data, false, theme);
 
     form_parts.push(Line::from(vec![Span::raw("")]));
     form_parts.push(Line::from(vec![Span::raw(
@@ -214,16 +567,103 @@ fn show_delete_confirm(data: &TaskCtrl) -> Paragraph {
         .block(titled_box("Confirm Deletion".to_string()))
 }
 
-fn show_detail_task(data: &TaskCtrl) -> Paragraph {
-    let mut form_parts = get_task_form_fields(data, false);
+fn show_detail_task<'a>(data: &'a TaskCtrl, theme: &Theme) -> Paragraph<'a> {
+    let mut form_parts = get_task_form_fields(data, false, theme);
+    form_parts.extend(checklist_lines(data, theme));
+    form_parts.push(Line::from(vec![Span::raw("")]));
+    form_parts.push(Line::from(vec![Span::raw(format!(
+        "Created : {} by {}    Updated : {}",
+        format_timestamp(data.task_table.created_at),
+        data.get_creator_name(),
+        format_timestamp(data.task_table.updated_at)
+    ))]));
 
     form_parts.push(Line::from(vec![Span::raw("")]));
-    form_parts.push(Line::from(vec![Span::raw(
-        "Press 'Esc' to close or 'Enter' to edit.",
-    )]));
+    match &data.detail_input {
+        Some((DetailInput::ChecklistItem, _)) => {
+            form_parts.push(Line::from(vec![Span::raw(
+                "Type the item and press 'Enter' to add it or 'Esc' to cancel.",
+            )]));
+        }
+        Some((DetailInput::Comment(_), _)) => {
+            form_parts.push(Line::from(vec![Span::raw(
+                "Write the comment and press 'Enter' to save it or 'Esc' to cancel.",
+            )]));
+            form_parts.push(Line::from(vec![Span::raw(
+                "'Alt-Enter' adds a line break.",
+            )]));
+        }
+        None => {
+            form_parts.push(Line::from(vec![Span::raw(
+                "Press 'Esc' to close or 'Enter' to edit.",
+            )]));
+            form_parts.push(Line::from(vec![Span::raw(
+                "'Up'/'Down' select a checkbox or comment, 'Space' checks or unchecks it.",
+            )]));
+            form_parts.push(Line::from(vec![Span::raw(
+                "'a' adds a checklist item, 'Shift-Up'/'Shift-Down' move it.",
+            )]));
+            form_parts.push(Line::from(vec![Span::raw(
+                "'c' adds a comment, 'e' edits your selected comment, 'd' deletes the selection.",
+            )]));
+        }
+    }
 
     Paragraph::new(form_parts)
         .alignment(Alignment::Center)
         .alignment(Alignment::Center)
         .block(titled_box("Task details".to_string()))
 }
+
+/// checklist of the detail view, with the selected item and the one being added
+fn checklist_lines(data: &TaskCtrl, theme: &Theme) -> Vec<Line<'static>> {
+    let base = theme.field_style(false);
+    let text_width = FORM_FIELD_WIDTH - 4;
+    let first = markdown::checkboxes(&data.task_table.description).len();
+
+    let mut rows: Vec<Vec<Span<'static>>> = data
+        .checklist
+        .iter()
+        .enumerate()
+        .map(|(idx, item)| {
+            let (mark_style, text_style) = match item.done {
+                true => (
+                    base.patch(theme.success_style()),
+                    base.patch(theme.muted_style()),
+                ),
+                false => (base, base),
+            };
+            let mark_style = match data.detail_idx == first + idx {
+                true => theme.selection_style(),
+                false => mark_style,
+            };
+            let mut text = truncate_to_width(&item.text, text_width);
+            string_min_size(&mut text, text_width);
+            vec![
+                Span::styled(
+                    format!("[{}] ", if item.done { 'x' } else { ' ' }),
+                    mark_style,
+                ),
+                Span::styled(text, text_style),
+            ]
+        })
+        .collect();
+    if let Some((DetailInput::ChecklistItem, input)) = &data.detail_input {
+        let mut row = vec![Span::styled("[ ] ", base)];
+        row.extend(input.spans(text_width, theme.field_style(true), theme.cursor_style()));
+        rows.push(row);
+    }
+    if rows.is_empty() {
+        let mut text = "no items".to_string();
+        string_min_size(&mut text, FORM_FIELD_WIDTH);
+        rows.push(vec![Span::styled(text, base.patch(theme.muted_style()))]);
+    }
+
+    let done = data.checklist.iter().filter(|item| item.done).count();
+    let mut label = match data.checklist.len() {
+        0 => "Checklist :".to_string(),
+        total => format!("Checklist {}/{}:", done, total),
+    };
+    string_min_size(&mut label, 16);
+    form_area_lines(label, rows)
+}
@@ -0,0 +1,340 @@
+use crate::config::{parse_color, Config};
+use ratatui::style::{Color, Modifier, Style};
+use std::collections::HashMap;
+use std::env;
+
+pub const BUILTIN_THEMES: [&str; 4] = ["default", "dark", "light", "high-contrast"];
+/// color names accepted in `[theme]` and `[themes.<name>]` config sections
+pub const THEME_COLORS: [&str; 15] = [
+    "text",
+    "muted",
+    "accent",
+    "accent_active",
+    "table_header_fg",
+    "table_header_bg",
+    "field_fg",
+    "field_bg",
+    "field_focus_fg",
+    "field_focus_bg",
+    "selection_fg",
+    "selection_bg",
+    "error",
+    "warning",
+    "success",
+];
+
+/// colors used by every view, built from the config file and handed down by `App`
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub name: String,
+    pub text: Color,
+    pub muted: Color,
+    pub accent: Color,
+    pub accent_active: Color,
+    pub table_header_fg: Color,
+    pub table_header_bg: Color,
+    pub field_fg: Color,
+    pub field_bg: Color,
+    pub field_focus_fg: Color,
+    pub field_focus_bg: Color,
+    pub selection_fg: Color,
+    pub selection_bg: Color,
+    pub error: Color,
+    pub warning: Color,
+    pub success: Color,
+    /// set when `NO_COLOR` is defined, styles then rely on modifiers only
+    pub no_color: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Theme {
+        Theme {
+            name: "dark".to_string(),
+            text: Color::Reset,
+            muted: Color::DarkGray,
+            accent: Color::Green,
+            accent_active: Color::Yellow,
+            table_header_fg: Color::Red,
+            table_header_bg: Color::LightYellow,
+            field_fg: Color::White,
+            field_bg: Color::Black,
+            field_focus_fg: Color::Black,
+            field_focus_bg: Color::Yellow,
+            selection_fg: Color::Black,
+            selection_bg: Color::Yellow,
+            error: Color::Red,
+            warning: Color::Yellow,
+            success: Color::Green,
+            no_color: false,
+        }
+    }
+
+    pub fn light() -> Theme {
+        Theme {
+            name: "light".to_string(),
+            text: Color::Black,
+            muted: Color::DarkGray,
+            accent: Color::Blue,
+            accent_active: Color::Magenta,
+            table_header_fg: Color::White,
+            table_header_bg: Color::Blue,
+            field_fg: Color::Black,
+            field_bg: Color::Gray,
+            field_focus_fg: Color::White,
+            field_focus_bg: Color::Blue,
+            selection_fg: Color::White,
+            selection_bg: Color::Blue,
+            error: Color::Red,
+            warning: Color::Magenta,
+            success: Color::Green,
+            no_color: false,
+        }
+    }
+
+    pub fn high_contrast() -> Theme {
+        Theme {
+            name: "high-contrast".to_string(),
+            text: Color::White,
+            muted: Color::White,
+            accent: Color::White,
+            accent_active: Color::LightYellow,
+            table_header_fg: Color::Black,
+            table_header_bg: Color::White,
+            field_fg: Color::White,
+            field_bg: Color::Black,
+            field_focus_fg: Color::Black,
+            field_focus_bg: Color::LightYellow,
+            selection_fg: Color::Black,
+            selection_bg: Color::White,
+            error: Color::LightRed,
+            warning: Color::LightYellow,
+            success: Color::LightGreen,
+            no_color: false,
+        }
+    }
+
+    pub fn builtin(name: &str) -> Option<Theme> {
+        match name {
+            "default" | "dark" => Some(Theme::dark()),
+            "light" => Some(Theme::light()),
+            "high-contrast" => Some(Theme::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// resolves `theme.name` (a built-in or a `[themes.<name>]` section), applies the color
+    /// overrides from `[theme]` and honours `NO_COLOR`
+    pub fn from_config(cfg: &Config) -> Result<Theme, String> {
+        let name = cfg.theme.name.as_str();
+        let mut theme = match cfg.themes.get(name) {
+            Some(custom) => {
+                let base = custom.get("base").map(|b| b.as_str()).unwrap_or("dark");
+                let mut theme = Theme::builtin(base).ok_or(format!(
+                    "config: themes.{}.base: unknown theme '{}', expected one of: {}",
+                    name,
+                    base,
+                    BUILTIN_THEMES.join(", ")
+                ))?;
+                theme
+                    .apply_colors(custom, &format!("themes.{}", name))
+                    .map_err(|e| format!("config: {}", e))?;
+                theme.name = name.to_string();
+                theme
+            }
+            None => Theme::builtin(name).ok_or(format!(
+                "config: theme.name has unknown value '{}', expected one of: {}",
+                name,
+                BUILTIN_THEMES
+                    .iter()
+                    .map(|t| t.to_string())
+                    .chain(cfg.themes.keys().cloned())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ))?,
+        };
+
+        if let Some(color) = &cfg.theme.background {
+            theme.field_bg =
+                parse_color(color).map_err(|e| format!("config: theme.background: {}", e))?;
+        }
+        if let Some(color) = &cfg.theme.highlight {
+            let color =
+                parse_color(color).map_err(|e| format!("config: theme.highlight: {}", e))?;
+            theme.field_focus_bg = color;
+            theme.selection_bg = color;
+        }
+        theme
+            .apply_colors(&cfg.theme.colors, "theme")
+            .map_err(|e| format!("config: {}", e))?;
+
+        theme.no_color = env::var("NO_COLOR").map(|v| !v.is_empty()).unwrap_or(false);
+        Ok(theme)
+    }
+
+    fn apply_colors(
+        &mut self,
+        colors: &HashMap<String, String>,
+        section: &str,
+    ) -> Result<(), String> {
+        for (key, value) in colors {
+            if key == "base" {
+                continue;
+            }
+            let color = parse_color(value).map_err(|e| format!("{}.{}: {}", section, key, e))?;
+            let slot = match key.as_str() {
+                "text" => &mut self.text,
+                "muted" => &mut self.muted,
+                "accent" => &mut self.accent,
+                "accent_active" => &mut self.accent_active,
+                "table_header_fg" => &mut self.table_header_fg,
+                "table_header_bg" => &mut self.table_header_bg,
+                "field_fg" => &mut self.field_fg,
+                "field_bg" => &mut self.field_bg,
+                "field_focus_fg" => &mut self.field_focus_fg,
+                "field_focus_bg" => &mut self.field_focus_bg,
+                "selection_fg" => &mut self.selection_fg,
+                "selection_bg" => &mut self.selection_bg,
+                "error" => &mut self.error,
+                "warning" => &mut self.warning,
+                "success" => &mut self.success,
+                _ => {
+                    return Err(format!(
+                        "{}: unknown color '{}', expected one of: {}",
+                        section,
+                        key,
+                        THEME_COLORS.join(", ")
+                    ))
+                }
+            };
+            *slot = color;
+        }
+        Ok(())
+    }
+
+    fn fg(&self, color: Color) -> Style {
+        if self.no_color {
+            Style::default()
+        } else {
+            Style::default().fg(color)
+        }
+    }
+
+    fn fg_bg(&self, fg: Color, bg: Color) -> Style {
+        if self.no_color {
+            Style::default()
+        } else {
+            Style::default().fg(fg).bg(bg)
+        }
+    }
+
+    pub fn text_style(&self) -> Style {
+        self.fg(self.text)
+    }
+
+    pub fn muted_style(&self) -> Style {
+        if self.no_color {
+            Style::default().add_modifier(Modifier::DIM)
+        } else {
+            self.fg(self.muted)
+        }
+    }
+
+    pub fn tab_style(&self) -> Style {
+        self.fg(self.accent)
+    }
+
+    pub fn tab_active_style(&self) -> Style {
+        self.fg(self.accent_active)
+            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+    }
+
+    /// header row of tables
+    pub fn table_header_style(&self) -> Style {
+        self.fg_bg(self.table_header_fg, self.table_header_bg)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    /// selected row of tables
+    pub fn row_selected_style(&self) -> Style {
+        Style::default().add_modifier(Modifier::REVERSED)
+    }
+
+    /// selected item of lists and popups
+    pub fn selection_style(&self) -> Style {
+        if self.no_color {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            self.fg_bg(self.selection_fg, self.selection_bg)
+        }
+    }
+
+    /// form fields, `focused` for the one being edited
+    pub fn field_style(&self, focused: bool) -> Style {
+        match (focused, self.no_color) {
+            (true, true) => Style::default().add_modifier(Modifier::REVERSED),
+            (false, true) => Style::default().add_modifier(Modifier::UNDERLINED),
+            (true, false) => self.fg_bg(self.field_focus_fg, self.field_focus_bg),
+            (false, false) => self.fg_bg(self.field_fg, self.field_bg),
+        }
+    }
+
+    /// cursor inside the focused field
+    pub fn cursor_style(&self) -> Style {
+        if self.no_color {
+            Style::default().add_modifier(Modifier::UNDERLINED)
+        } else {
+            self.fg_bg(self.field_focus_bg, self.field_focus_fg)
+        }
+    }
+
+    /// markdown headings, `level` 1 is the biggest
+    pub fn heading_style(&self, level: usize) -> Style {
+        match level {
+            1 => self
+                .fg(self.accent_active)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            _ => self.fg(self.accent).add_modifier(Modifier::BOLD),
+        }
+    }
+
+    /// inline code and code blocks
+    pub fn code_style(&self) -> Style {
+        if self.no_color {
+            Style::default().add_modifier(Modifier::DIM)
+        } else {
+            self.fg(self.success)
+        }
+    }
+
+    pub fn link_style(&self) -> Style {
+        self.fg(self.accent).add_modifier(Modifier::UNDERLINED)
+    }
+
+    pub fn error_style(&self) -> Style {
+        self.fg(self.error).add_modifier(Modifier::BOLD)
+    }
+
+    pub fn warning_style(&self) -> Style {
+        self.fg(self.warning)
+    }
+
+    pub fn success_style(&self) -> Style {
+        self.fg(self.success)
+    }
+
+    /// priority labels, P0 stands out the most and P3 the least
+    pub fn priority_style(&self, priority: i32) -> Style {
+        match priority {
+            0 => self.error_style(),
+            1 => self.warning_style(),
+            2 => self.text_style(),
+            _ => self.muted_style(),
+        }
+    }
+}
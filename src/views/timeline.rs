@@ -0,0 +1,285 @@
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use ratatui::{layout::*, style::Style, text::*, widgets::*, Frame};
+
+use crate::controllers::timeline_controller::{TimelineCtrl, TimelineRow, TimelineZoom};
+use crate::views::input::truncate_to_width;
+use crate::views::string_min_size;
+use crate::views::theme::Theme;
+use crate::views::titled_box;
+
+/// width of the task and project names on the left
+const LABEL_WIDTH: u16 = 28;
+const HEADER_ROWS: u16 = 2;
+const BAR: char = '█';
+const TODAY: char = '┆';
+
+/// characters a column takes for each zoom level
+fn column_width(zoom: TimelineZoom) -> i64 {
+    match zoom {
+        TimelineZoom::Day => 3,
+        TimelineZoom::Week => 4,
+        TimelineZoom::Month => 6,
+    }
+}
+
+/// maps days to character positions on the time axis, `origin` being the first column
+struct Scale {
+    zoom: TimelineZoom,
+    origin: NaiveDate,
+    width: i64,
+}
+
+impl Scale {
+    /// position of the start of `day`, days inside a week or month column are spread over the
+    /// width of the column
+    fn x(&self, day: NaiveDate) -> i64 {
+        let start = self.zoom.column_start(day);
+        let next = self.zoom.add_columns(start, 1);
+        let column = match self.zoom {
+            TimelineZoom::Day => (start - self.origin).num_days(),
+            TimelineZoom::Week => (start - self.origin).num_days().div_euclid(7),
+            TimelineZoom::Month => {
+                (start.year() as i64 - self.origin.year() as i64) * 12 + start.month() as i64
+                    - self.origin.month() as i64
+            }
+        };
+        let inside = (day - start).num_days() * self.width / (next - start).num_days();
+        column * self.width + inside
+    }
+
+    /// first and last character of a bar, at least one character wide
+    fn span(&self, start: NaiveDate, end: NaiveDate) -> (i64, i64) {
+        let from = self.x(start);
+        (from, (self.x(end + Duration::days(1)) - 1).max(from))
+    }
+}
+
+/// one row per project or task, the bars scaled to the zoom level, a mark on today and arrows
+/// from each task to those depending on it
+pub fn timeline_view(ctrl: &mut TimelineCtrl, f: &mut Frame, area: Rect, theme: &Theme) {
+    let title = match &ctrl.project {
+        Some(project) => format!(
+            "Timeline - {} - by {} ('z' zoom, 'Left'/'Right' scroll, 'Home' today, 'Esc' projects)",
+            project.name,
+            ctrl.zoom.name()
+        ),
+        None => format!(
+            "Timeline - projects - by {} ('z' zoom, 'Left'/'Right' scroll, 'Home' today, 'Enter' tasks)",
+            ctrl.zoom.name()
+        ),
+    };
+    let panel = titled_box(title);
+    let inner = panel.inner(area);
+    f.render_widget(panel, area);
+
+    let label_width = LABEL_WIDTH.min(inner.width / 3);
+    let chart_width = inner.width.saturating_sub(label_width + 1) as i64;
+    let width = column_width(ctrl.zoom);
+    ctrl.visible_columns = chart_width / width;
+    let scale = Scale {
+        zoom: ctrl.zoom,
+        origin: ctrl.origin,
+        width,
+    };
+
+    let body_rows = inner.height.saturating_sub(HEADER_ROWS) as usize;
+    let offset = match ctrl.selected.checked_sub(body_rows.saturating_sub(1)) {
+        Some(offset) if body_rows > 0 => offset,
+        _ => 0,
+    };
+    let rows: Vec<&TimelineRow> = ctrl.rows.iter().skip(offset).take(body_rows).collect();
+
+    let today = scale.x(Local::now().date_naive());
+    let mut grid = vec![vec![(' ', theme.text_style()); chart_width as usize]; rows.len()];
+    if today >= 0 && today < chart_width {
+        for line in grid.iter_mut() {
+            line[today as usize] = (TODAY, theme.warning_style());
+        }
+    }
+    for (idx, row) in rows.iter().enumerate() {
+        if let (Some(start), Some(end)) = (row.start, row.end) {
+            let style = match (row.done, row.is_overdue()) {
+                (true, _) => theme.success_style(),
+                (false, true) => theme.error_style(),
+                (false, false) => theme.tab_style(),
+            };
+            let (from, to) = scale.span(start, end);
+            for x in from.max(0)..=to.min(chart_width - 1) {
+                grid[idx][x as usize] = (BAR, style);
+            }
+        }
+    }
+    if ctrl.project.is_some() {
+        draw_dependencies(&rows, &scale, &mut grid, theme);
+    }
+
+    let mut lines = header_lines(&scale, chart_width, today, label_width as usize, theme);
+    for (idx, row) in rows.iter().enumerate() {
+        let mut label = truncate_to_width(&row.label, label_width as usize - 1);
+        string_min_size(&mut label, label_width as usize);
+        let label_style = match offset + idx == ctrl.selected {
+            true => theme.selection_style(),
+            false => theme.text_style(),
+        };
+        let mut spans = vec![Span::styled(label, label_style), Span::raw(" ")];
+        if row.start.is_none() {
+            spans.push(Span::styled("no dates", theme.muted_style()));
+        } else {
+            spans.extend(grid_spans(&grid[idx]));
+        }
+        lines.push(Line::from(spans));
+    }
+    if ctrl.rows.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "Nothing to show, give the projects or tasks a start or due date.",
+            theme.muted_style(),
+        )));
+    }
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+/// merges the cells of a row in spans of the same style
+fn grid_spans(cells: &[(char, Style)]) -> Vec<Span<'static>> {
+    let mut spans: Vec<(String, Style)> = vec![];
+    for (c, style) in cells {
+        match spans.last_mut() {
+            Some((text, last)) if last == style => text.push(*c),
+            _ => spans.push((c.to_string(), *style)),
+        }
+    }
+    spans
+        .into_iter()
+        .map(|(text, style)| Span::styled(text, style))
+        .collect()
+}
+
+/// months or years on the first line, the columns on the second one with today marked
+fn header_lines(
+    scale: &Scale,
+    chart_width: i64,
+    today: i64,
+    label_width: usize,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let mut major = vec![' '; chart_width as usize];
+    let mut minor = vec![' '; chart_width as usize];
+    let write = |line: &mut Vec<char>, x: i64, text: &str| {
+        for (i, c) in text.chars().enumerate() {
+            if let Some(cell) = line.get_mut(x as usize + i) {
+                *cell = c;
+            }
+        }
+    };
+    let columns = chart_width / scale.width;
+    let mut last_major = None;
+    for column in 0..columns {
+        let start = scale.zoom.add_columns(scale.origin, column);
+        let x = column * scale.width;
+        let (major_text, minor_text) = match scale.zoom {
+            TimelineZoom::Day => (start.format("%B %Y"), start.format("%d")),
+            TimelineZoom::Week => (start.format("%b %Y"), start.format("%d")),
+            TimelineZoom::Month => (start.format("%Y"), start.format("%b")),
+        };
+        let major_text = major_text.to_string();
+        if last_major.as_ref() != Some(&major_text) {
+            // cuts the previous label short so they don't run together
+            if x > 0 {
+                write(&mut major, x - 1, " ");
+            }
+            write(&mut major, x, &major_text);
+            last_major = Some(major_text);
+        }
+        write(&mut minor, x, &minor_text.to_string());
+    }
+    let mut minor_spans = vec![Span::raw(" ".repeat(label_width + 1))];
+    match today >= 0 && today < chart_width {
+        true => {
+            let (before, after) = minor.split_at(today as usize);
+            minor_spans.push(Span::styled(
+                before.iter().collect::<String>(),
+                theme.muted_style(),
+            ));
+            minor_spans.push(Span::styled("▼", theme.warning_style()));
+            minor_spans.push(Span::styled(
+                after.iter().skip(1).collect::<String>(),
+                theme.muted_style(),
+            ));
+        }
+        false => minor_spans.push(Span::styled(
+            minor.iter().collect::<String>(),
+            theme.muted_style(),
+        )),
+    }
+    vec![
+        Line::from(vec![
+            Span::raw(" ".repeat(label_width + 1)),
+            Span::styled(major.iter().collect::<String>(), theme.heading_style(2)),
+        ]),
+        Line::from(minor_spans),
+    ]
+}
+
+/// draws an arrow from the end of each task to the start of the tasks depending on it, in red
+/// when the task starts before the one it depends on is finished
+fn draw_dependencies(
+    rows: &[&TimelineRow],
+    scale: &Scale,
+    grid: &mut [Vec<(char, Style)>],
+    theme: &Theme,
+) {
+    let width = grid.first().map(|line| line.len() as i64).unwrap_or(0);
+    let put = |grid: &mut [Vec<(char, Style)>], row: usize, x: i64, c: char, style: Style| {
+        if x >= 0 && x < width && grid[row][x as usize].0 != BAR {
+            grid[row][x as usize] = (c, style);
+        }
+    };
+    for (to_idx, row) in rows.iter().enumerate() {
+        let to_start = match row.start {
+            Some(start) => scale.x(start),
+            None => continue,
+        };
+        for depends_on in &row.depends_on {
+            let from_idx = match rows.iter().position(|r| r.id == *depends_on) {
+                Some(idx) => idx,
+                None => continue,
+            };
+            let from_end = match (rows[from_idx].start, rows[from_idx].end) {
+                (Some(start), Some(end)) => scale.span(start, end).1 + 1,
+                _ => continue,
+            };
+            let style = match from_end > to_start {
+                true => theme.error_style(),
+                false => theme.muted_style(),
+            };
+            let turn = from_end.min(to_start - 1);
+            put(
+                grid,
+                from_idx,
+                turn,
+                match to_idx > from_idx {
+                    true => '┐',
+                    false => '┘',
+                },
+                style,
+            );
+            let (top, bottom) = (from_idx.min(to_idx), from_idx.max(to_idx));
+            for line in top + 1..bottom {
+                put(grid, line, turn, '│', style);
+            }
+            put(
+                grid,
+                to_idx,
+                turn,
+                match to_idx > from_idx {
+                    true => '└',
+                    false => '┌',
+                },
+                style,
+            );
+            for x in turn + 1..to_start - 1 {
+                put(grid, to_idx, x, '─', style);
+            }
+            put(grid, to_idx, to_start - 1, '▶', style);
+        }
+    }
+}